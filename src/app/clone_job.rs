@@ -0,0 +1,83 @@
+//! Cloning a repository for a new session
+//!
+//! The new session dialog can clone a URL before creating the session. The
+//! clone runs on a background thread so the list stays responsive; the app
+//! polls it each tick, showing progress, and creates the session once it
+//! has succeeded.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use anyhow::Result;
+
+use crate::git;
+
+/// A clone running in the background
+#[derive(Debug)]
+pub struct CloneJob {
+    /// URL being cloned
+    pub url: String,
+    /// Directory cloned into
+    pub dest: PathBuf,
+    /// Session to create in `dest` once the clone is done
+    pub session_name: String,
+    /// Whether to start Claude Code in the new session
+    pub start_claude: bool,
+    received: Arc<AtomicUsize>,
+    total: Arc<AtomicUsize>,
+    handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl CloneJob {
+    /// Start cloning `url` into `dest`
+    pub fn start(url: String, dest: PathBuf, session_name: String, start_claude: bool) -> Self {
+        let received = Arc::new(AtomicUsize::new(0));
+        let total = Arc::new(AtomicUsize::new(0));
+
+        let handle = {
+            let (url, dest) = (url.clone(), dest.clone());
+            let (received, total) = (received.clone(), total.clone());
+            std::thread::spawn(move || {
+                git::clone_repository(&url, &dest, move |done, of| {
+                    received.store(done, Ordering::Relaxed);
+                    total.store(of, Ordering::Relaxed);
+                })
+            })
+        };
+
+        Self {
+            url,
+            dest,
+            session_name,
+            start_claude,
+            received,
+            total,
+            handle: Some(handle),
+        }
+    }
+
+    /// The clone's outcome once it has finished; `None` while it runs
+    pub fn poll(&mut self) -> Option<Result<()>> {
+        if !self.handle.as_ref()?.is_finished() {
+            return None;
+        }
+        let handle = self.handle.take()?;
+        Some(
+            handle
+                .join()
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("The clone thread panicked"))),
+        )
+    }
+
+    /// Progress for the status bar, e.g. `cloning proj 45%`
+    pub fn progress(&self) -> String {
+        let name = self.dest.file_name().unwrap_or_default().to_string_lossy();
+        let received = self.received.load(Ordering::Relaxed);
+        match self.total.load(Ordering::Relaxed) {
+            0 => format!("cloning {}", name),
+            total => format!("cloning {} {}%", name, received * 100 / total),
+        }
+    }
+}
@@ -1,8 +1,15 @@
 //! Helper utilities for the app module
 //!
-//! Pure functions for path manipulation and name sanitization.
+//! Pure functions for path manipulation, name sanitization, shell quoting
+//! and fitting text into fixed-width UI.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::git::PullRequestComment;
+
+use super::{CommentLine, SessionAction};
 
 /// Expand ~ to home directory in a path string
 pub fn expand_path(path: &str) -> PathBuf {
@@ -18,9 +25,63 @@ pub fn expand_path(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
+/// Canonical form of `path` for comparisons and for handing to tmux and
+/// git: symlinks resolved and trailing slashes and `.`/`..` dropped. A path
+/// that doesn't exist yet keeps its missing tail on top of the canonical
+/// form of its nearest existing ancestor.
+pub fn canonical_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = std::fs::canonicalize(path) {
+        return canonical;
+    }
+    let mut missing = Vec::new();
+    let mut ancestor = path;
+    while let Some(parent) = ancestor.parent() {
+        missing.extend(ancestor.file_name());
+        ancestor = parent;
+        if let Ok(mut canonical) = std::fs::canonicalize(ancestor) {
+            canonical.extend(missing.iter().rev());
+            return canonical;
+        }
+    }
+    path.components().collect()
+}
+
+/// PR body with a `Closes #N` line for `issue` appended, so the issue is
+/// closed when the PR is merged. An empty `issue` leaves the body as is.
+pub fn pull_request_body(body: &str, issue: &str) -> Result<String, String> {
+    let issue = issue.trim().trim_start_matches('#');
+    if issue.is_empty() {
+        return Ok(body.to_string());
+    }
+    let number: u64 = issue
+        .parse()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| format!("'{}' is not an issue number", issue))?;
+
+    let body = body.trim_end();
+    if body.is_empty() {
+        Ok(format!("Closes #{}", number))
+    } else {
+        Ok(format!("{}\n\nCloses #{}", body, number))
+    }
+}
+
 /// Sanitize a branch name for use as a session name
 /// e.g., "feature/new-thing" -> "new-thing"
 pub fn sanitize_for_session_name(branch: &str) -> String {
+    branch
+        .rsplit('/')
+        .next()
+        .unwrap_or(branch)
+        .chars()
+        .map(|c| if is_session_name_char(c) { c } else { '-' })
+        .collect()
+}
+
+/// Sanitize a branch name for use as a directory name, keeping characters
+/// that are fine in paths: "feature/v1+hotfix" -> "v1+hotfix"
+pub fn sanitize_for_path(branch: &str) -> String {
     branch
         .rsplit('/')
         .next()
@@ -28,14 +89,485 @@ pub fn sanitize_for_session_name(branch: &str) -> String {
         .replace(['/', '\\', ' ', ':', '.'], "-")
 }
 
+/// `base`, or `base-2`, `base-3`, ... when `taken` says it is in use
+pub fn unique_name(base: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|name| !taken(name))
+        .expect("an unused suffix exists")
+}
+
+/// Whether `name` matches the glob `pattern`, where `*` matches any run of
+/// characters and `?` any single character
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name position it is matched up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // Let the last `*` swallow one more character
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Most diff lines kept for the diff viewer
+const MAX_DIFF_LINES: usize = 10_000;
+
+/// Split a diff into lines for the diff viewer, with tabs expanded. Past
+/// `MAX_DIFF_LINES` the rest is dropped and a last line says how much.
+pub fn diff_lines(diff: &str) -> Vec<String> {
+    let mut lines: Vec<String> = diff
+        .lines()
+        .take(MAX_DIFF_LINES)
+        .map(|line| line.replace('\t', "    "))
+        .collect();
+    let dropped = diff.lines().count().saturating_sub(MAX_DIFF_LINES);
+    if dropped > 0 {
+        lines.push(format!(
+            "… diff truncated, {} more lines not shown",
+            dropped
+        ));
+    }
+    lines
+}
+
+/// Lay out pull request comments for the viewer, grouped by reviewer in
+/// order of their first comment, each group oldest first
+pub fn comment_lines(comments: &[PullRequestComment]) -> Vec<CommentLine> {
+    let mut authors: Vec<&str> = Vec::new();
+    for comment in comments {
+        if !authors.contains(&comment.author.as_str()) {
+            authors.push(&comment.author);
+        }
+    }
+
+    let mut lines = Vec::new();
+    for author in authors {
+        let theirs: Vec<_> = comments.iter().filter(|c| c.author == author).collect();
+        lines.push(CommentLine::Reviewer(format!(
+            "{} ({})",
+            author,
+            theirs.len()
+        )));
+        for comment in theirs {
+            // "2024-05-01T09:30:00Z" reads as "2024-05-01 09:30"
+            let posted = comment.created_at.replacen('T', " ", 1);
+            let mut header: String = posted.chars().take(16).collect();
+            if let Some(ref state) = comment.state {
+                header.push_str(&format!("  {}", state.to_lowercase().replace('_', " ")));
+            }
+            lines.push(CommentLine::Header(header));
+            lines.extend(
+                comment.body.trim_end().lines().map(|line| {
+                    CommentLine::Body(line.trim_end_matches('\r').replace('\t', "    "))
+                }),
+            );
+            lines.push(CommentLine::Blank);
+        }
+    }
+    lines
+}
+
+/// Memory in KiB as a short size: `980K`, `41M`, `1.5G`
+pub fn format_memory(kb: u64) -> String {
+    const MIB: u64 = 1024;
+    const GIB: u64 = 1024 * 1024;
+    if kb >= GIB {
+        format!("{:.1}G", kb as f64 / GIB as f64)
+    } else if kb >= MIB {
+        format!("{}M", kb / MIB)
+    } else {
+        format!("{}K", kb)
+    }
+}
+
+/// Shorten `s` to at most `max` terminal columns, ending it with `…` when
+/// anything was cut. Used wherever user-provided text (session names,
+/// commit subjects, PR titles) is interpolated into fixed-width UI.
+pub fn truncate_display(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+
+    let mut out = String::new();
+    let mut width = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if width + w > max - 1 {
+            break;
+        }
+        width += w;
+        out.push(c);
+    }
+    out.push('…');
+    out
+}
+
+/// Quote a string for safe use as a single POSIX shell word
+/// e.g., "/tmp/my dir" -> "'/tmp/my dir'"
+pub fn shell_quote(s: &str) -> String {
+    let is_safe = !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/-_.,:@%+=".contains(c));
+    if is_safe {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
 /// Generate default worktree path from repo path and branch name
-/// e.g., ~/repos/project + feature/foo -> ~/repos/project-foo
-pub fn default_worktree_path(repo_path: &std::path::Path, branch: &str) -> PathBuf {
+///
+/// The `template` supports `{parent}`, `{repo}` and `{branch}` placeholders,
+/// e.g. `{parent}/{repo}-{branch}`: ~/repos/project + feature/foo -> ~/repos/project-foo
+pub fn default_worktree_path(repo_path: &std::path::Path, branch: &str, template: &str) -> PathBuf {
     let parent = repo_path.parent().unwrap_or(repo_path);
     let repo_name = repo_path
         .file_name()
         .and_then(|s| s.to_str())
         .unwrap_or("repo");
-    let branch_suffix = sanitize_for_session_name(branch);
-    parent.join(format!("{}-{}", repo_name, branch_suffix))
+    let branch_suffix = sanitize_for_path(branch);
+    let path = template
+        .replace("{parent}", &parent.to_string_lossy())
+        .replace("{repo}", repo_name)
+        .replace("{branch}", &branch_suffix);
+    expand_path(&path)
+}
+
+/// Characters allowed in session names, whether typed or suggested
+pub fn is_session_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Generate the session name for a new worktree from repo path and branch name
+///
+/// The `template` supports `{repo}` and `{branch}` placeholders. Characters
+/// not allowed in typed session names (`is_session_name_char`) become `-`,
+/// and the result is cut to `max_len` characters if given (0 means no limit):
+/// ~/repos/project + feature/foo with `{repo}-{branch}` -> project-foo
+pub fn worktree_session_name(
+    repo_path: &std::path::Path,
+    branch: &str,
+    template: &str,
+    max_len: Option<usize>,
+) -> String {
+    let repo_name = repo_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("repo");
+    let name: String = template
+        .replace("{repo}", repo_name)
+        .replace("{branch}", &sanitize_for_session_name(branch))
+        .chars()
+        .map(|c| if is_session_name_char(c) { c } else { '-' })
+        .collect();
+
+    match max_len.filter(|&max| max > 0) {
+        Some(max) if name.chars().count() > max => name
+            .chars()
+            .take(max)
+            .collect::<String>()
+            .trim_end_matches(['-', '_'])
+            .to_string(),
+        _ => name,
+    }
+}
+
+/// Index of the next item after `current` (or before it, going backward)
+/// for which `matches` holds, wrapping around the ends of `0..count`.
+/// `current` itself is only returned when it is the sole match.
+pub fn next_wrapping(
+    count: usize,
+    current: usize,
+    forward: bool,
+    matches: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    (1..=count)
+        .map(|step| {
+            if forward {
+                (current + step) % count
+            } else {
+                (current + count - step) % count
+            }
+        })
+        .find(|&i| matches(i))
+}
+
+/// Reorder `actions` by the identifiers in `order`. Unlisted actions keep
+/// their default order after the listed ones; unknown identifiers are ignored.
+pub fn order_actions(actions: &mut [SessionAction], order: &[String]) {
+    if order.is_empty() {
+        return;
+    }
+    actions.sort_by_key(|action| {
+        order
+            .iter()
+            .position(|id| id == action.id())
+            .unwrap_or(order.len())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_path() {
+        let base = canonical_path(&std::env::temp_dir())
+            .join(format!("claude-tmux-canonical-{}", std::process::id()));
+        let real = base.join("real");
+        let link = base.join("link");
+        std::fs::create_dir_all(&real).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let with_slash = PathBuf::from(format!("{}/", real.display()));
+        assert_eq!(canonical_path(&with_slash), real);
+        assert_eq!(canonical_path(&real.join(".")), real);
+        #[cfg(unix)]
+        {
+            assert_eq!(canonical_path(&link), real);
+            // Not created yet: resolved through the existing ancestor
+            assert_eq!(canonical_path(&link.join("wt/")), real.join("wt"));
+        }
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_pull_request_body() {
+        assert_eq!(pull_request_body("Adds X", "").unwrap(), "Adds X");
+        assert_eq!(
+            pull_request_body("Adds X\n", "#42").unwrap(),
+            "Adds X\n\nCloses #42"
+        );
+        assert_eq!(pull_request_body("", "7").unwrap(), "Closes #7");
+        assert!(pull_request_body("", "0").is_err());
+        assert!(pull_request_body("", "4#2").is_err());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("music", "music"));
+        assert!(!glob_match("music", "music-2"));
+        assert!(glob_match("scratch*", "scratch"));
+        assert!(glob_match("scratch*", "scratch-notes"));
+        assert!(glob_match("*-tmp", "build-tmp"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxbyy"));
+        assert!(glob_match("log?", "log1"));
+        assert!(!glob_match("log?", "log"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn test_order_actions() {
+        let mut actions = vec![
+            SessionAction::SwitchTo,
+            SessionAction::Rename,
+            SessionAction::Commit,
+            SessionAction::Push,
+            SessionAction::Kill,
+        ];
+        let order = ["push", "bogus", "commit"].map(String::from);
+        order_actions(&mut actions, &order);
+        assert_eq!(
+            actions,
+            vec![
+                SessionAction::Push,
+                SessionAction::Commit,
+                SessionAction::SwitchTo,
+                SessionAction::Rename,
+                SessionAction::Kill,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(diff_lines("+\tx\n-y\n"), vec!["+    x", "-y"]);
+
+        let long = "+line\n".repeat(MAX_DIFF_LINES + 3);
+        let lines = diff_lines(&long);
+        assert_eq!(lines.len(), MAX_DIFF_LINES + 1);
+        assert_eq!(
+            lines[MAX_DIFF_LINES],
+            "… diff truncated, 3 more lines not shown"
+        );
+    }
+
+    #[test]
+    fn test_comment_lines() {
+        let comment =
+            |author: &str, state: Option<&str>, body: &str, at: &str| PullRequestComment {
+                author: author.to_string(),
+                state: state.map(str::to_string),
+                body: body.to_string(),
+                created_at: at.to_string(),
+            };
+        let comments = [
+            comment("bob", None, "Why?\r\n\tBecause", "2024-05-01T09:30:00Z"),
+            comment(
+                "alice",
+                Some("CHANGES_REQUESTED"),
+                "Fix it",
+                "2024-05-02T10:00:00Z",
+            ),
+            comment("bob", Some("APPROVED"), "", "2024-05-03T11:15:00Z"),
+        ];
+        let body = |s: &str| CommentLine::Body(s.to_string());
+        let header = |s: &str| CommentLine::Header(s.to_string());
+        assert_eq!(
+            comment_lines(&comments),
+            vec![
+                CommentLine::Reviewer("bob (2)".to_string()),
+                header("2024-05-01 09:30"),
+                body("Why?"),
+                body("    Because"),
+                CommentLine::Blank,
+                header("2024-05-03 11:15  approved"),
+                CommentLine::Blank,
+                CommentLine::Reviewer("alice (1)".to_string()),
+                header("2024-05-02 10:00  changes requested"),
+                body("Fix it"),
+                CommentLine::Blank,
+            ]
+        );
+        assert!(comment_lines(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_format_memory() {
+        assert_eq!(format_memory(980), "980K");
+        assert_eq!(format_memory(42_000), "41M");
+        assert_eq!(format_memory(1_572_864), "1.5G");
+    }
+
+    #[test]
+    fn test_truncate_display() {
+        assert_eq!(truncate_display("short", 10), "short");
+        assert_eq!(truncate_display("exactly10!", 10), "exactly10!");
+        assert_eq!(truncate_display("a long commit subject", 10), "a long co…");
+        // Wide characters take two columns each
+        assert_eq!(truncate_display("日本語のテキスト", 7), "日本語…");
+        assert_eq!(truncate_display("anything", 0), "");
+    }
+
+    #[test]
+    fn test_unique_name() {
+        let taken = ["proj", "proj-2"];
+        assert_eq!(unique_name("proj", |n| taken.contains(&n)), "proj-3");
+        assert_eq!(unique_name("other", |n| taken.contains(&n)), "other");
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/home/me/project"), "/home/me/project");
+        assert_eq!(shell_quote("/tmp/my dir"), "'/tmp/my dir'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_default_worktree_path_sibling() {
+        let path = default_worktree_path(
+            Path::new("/repos/project"),
+            "feature/foo",
+            "{parent}/{repo}-{branch}",
+        );
+        assert_eq!(path, PathBuf::from("/repos/project-foo"));
+    }
+
+    #[test]
+    fn test_default_worktree_path_nested_template() {
+        let path = default_worktree_path(
+            Path::new("/repos/project"),
+            "fix.bug",
+            "/worktrees/{repo}/{branch}",
+        );
+        assert_eq!(path, PathBuf::from("/worktrees/project/fix-bug"));
+    }
+
+    #[test]
+    fn test_default_worktree_path_keeps_path_characters() {
+        let path = default_worktree_path(
+            Path::new("/repos/project"),
+            "release/v1+hotfix",
+            "{parent}/{repo}-{branch}",
+        );
+        assert_eq!(path, PathBuf::from("/repos/project-v1+hotfix"));
+    }
+
+    #[test]
+    fn test_worktree_session_name_templates() {
+        let repo = Path::new("/repos/my.project");
+        assert_eq!(
+            worktree_session_name(repo, "feature/foo", "{repo}-{branch}", None),
+            "my-project-foo"
+        );
+        assert_eq!(
+            worktree_session_name(repo, "feature/foo", "{repo}/{branch}", None),
+            "my-project-foo"
+        );
+        assert_eq!(
+            worktree_session_name(repo, "fix.bug", "{branch}", None),
+            "fix-bug"
+        );
+    }
+
+    #[test]
+    fn test_worktree_session_name_truncates() {
+        let repo = Path::new("/repos/project");
+        assert_eq!(
+            worktree_session_name(repo, "long-branch-name", "{repo}-{branch}", Some(13)),
+            "project-long"
+        );
+        assert_eq!(
+            worktree_session_name(repo, "x", "{repo}-{branch}", Some(20)),
+            "project-x"
+        );
+        assert_eq!(
+            worktree_session_name(repo, "x", "{repo}-{branch}", Some(0)),
+            "project-x"
+        );
+    }
+
+    #[test]
+    fn test_next_wrapping() {
+        let waiting = [false, true, false, true, false];
+        let is_waiting = |i: usize| waiting[i];
+        assert_eq!(next_wrapping(5, 1, true, is_waiting), Some(3));
+        assert_eq!(next_wrapping(5, 3, true, is_waiting), Some(1));
+        assert_eq!(next_wrapping(5, 1, false, is_waiting), Some(3));
+        assert_eq!(next_wrapping(5, 4, false, is_waiting), Some(3));
+        assert_eq!(next_wrapping(5, 0, true, |_| false), None);
+        assert_eq!(next_wrapping(5, 1, true, |i| i == 1), Some(1));
+        assert_eq!(next_wrapping(0, 0, true, |_| true), None);
+    }
 }
@@ -0,0 +1,139 @@
+//! Macros: named sequences of actions from the config
+//!
+//! A `MacroRun` tracks a macro being run on one session. Steps run one after
+//! another; a step that opens a dialog or asks for confirmation pauses the
+//! run until the dialog closes. Each finished step's outcome is kept for the
+//! summary shown when the run ends.
+
+use crate::config::Macro;
+
+use super::SessionAction;
+
+/// A macro in progress
+#[derive(Debug, Clone)]
+pub struct MacroRun {
+    /// Macro name, for messages
+    pub name: String,
+    /// Session the steps run on
+    pub session: String,
+    /// Actions to run, in order
+    pub steps: Vec<SessionAction>,
+    /// Index into `steps` of the next step to run
+    pub next: usize,
+    /// Message template for commit steps, if not prompted for
+    pub commit_message: Option<String>,
+    /// Whether destructive steps keep their confirmation
+    pub confirm: bool,
+    /// Step whose dialog or confirmation is open
+    pub waiting: Option<SessionAction>,
+    /// Label and outcome of each step that has ended, in order
+    pub results: Vec<(String, Result<String, String>)>,
+}
+
+impl MacroRun {
+    /// Start running `config` on `session`, or explain why it cannot run
+    pub fn new(config: &Macro, session: &str) -> Result<Self, String> {
+        let mut steps = Vec::new();
+        for id in &config.steps {
+            let step = SessionAction::from_id(id)
+                .ok_or_else(|| format!("Macro '{}' has unknown step '{}'", config.name, id))?;
+            steps.push(step);
+        }
+        if steps.is_empty() {
+            return Err(format!("Macro '{}' has no steps", config.name));
+        }
+
+        Ok(Self {
+            name: config.name.clone(),
+            session: session.to_string(),
+            steps,
+            next: 0,
+            commit_message: config.commit_message.clone(),
+            confirm: config.confirm,
+            waiting: None,
+            results: Vec::new(),
+        })
+    }
+
+    /// Commit message for a commit step on `branch`, if the macro sets one
+    pub fn commit_message(&self, branch: &str) -> Option<String> {
+        self.commit_message.as_ref().map(|template| {
+            template
+                .replace("{branch}", branch)
+                .replace("{session}", &self.session)
+        })
+    }
+
+    /// Record how the step in progress ended, if one is
+    pub fn record(&mut self, outcome: Result<String, String>) {
+        if let Some(step) = self.waiting.take() {
+            self.results.push((step.label().to_string(), outcome));
+        }
+    }
+
+    /// Where the run is, e.g. `step 2/3 (Commit staged changes)`
+    pub fn position(&self) -> String {
+        let label = self
+            .next
+            .checked_sub(1)
+            .and_then(|i| self.steps.get(i))
+            .map_or("", |step| step.label());
+        format!("step {}/{} ({})", self.next, self.steps.len(), label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_macro_run() {
+        let config = Macro {
+            name: "ship".to_string(),
+            steps: vec![
+                "stage".to_string(),
+                "commit".to_string(),
+                "push".to_string(),
+            ],
+            commit_message: Some("wip on {branch} ({session})".to_string()),
+            ..Macro::default()
+        };
+        let mut run = MacroRun::new(&config, "api").unwrap();
+        assert_eq!(
+            run.steps,
+            vec![
+                SessionAction::Stage,
+                SessionAction::Commit,
+                SessionAction::Push
+            ]
+        );
+        assert_eq!(
+            run.commit_message("feat").as_deref(),
+            Some("wip on feat (api)")
+        );
+        run.next = 2;
+        assert_eq!(run.position(), "step 2/3 (Commit staged changes)");
+
+        run.record(Ok("Nothing running".to_string()));
+        assert!(run.results.is_empty());
+        run.waiting = Some(SessionAction::Commit);
+        run.record(Err("Nothing to commit".to_string()));
+        assert_eq!(
+            run.results,
+            vec![(
+                "Commit staged changes".to_string(),
+                Err("Nothing to commit".to_string())
+            )]
+        );
+        assert_eq!(run.waiting, None);
+
+        let bad = Macro {
+            steps: vec!["stage".to_string(), "deploy".to_string()],
+            ..config
+        };
+        assert_eq!(
+            MacroRun::new(&bad, "api").unwrap_err(),
+            "Macro 'ship' has unknown step 'deploy'"
+        );
+    }
+}
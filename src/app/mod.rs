@@ -5,28 +5,61 @@
 //! - Mode handling and transitions
 //! - Session actions and execution
 //! - Dialog flows (rename, new session, worktree, PR)
+//! - Macros of actions run in sequence
 
+mod clone_job;
 mod helpers;
+mod macros;
 mod mode;
+mod pr_status;
+mod search;
+mod text_input;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
+use ansi_to_tui::IntoText;
 use anyhow::Result;
+use unicode_width::UnicodeWidthStr;
 
-use crate::detection::{detect_static_status, detect_status};
-use crate::git::{self, GitContext, PullRequestInfo};
+use crate::color_tags::{ColorTags, TagColor};
+use crate::config::{Config, OnLastKill, RepoConfig};
+use crate::detection::{
+    detect_launch_failure, detect_static_status, detect_status, explain_status,
+};
+use crate::git::{self, DiffStat, GitContext, MergeOutcome, MergeStrategy, PullRequestInfo};
+use crate::history::History;
+use crate::hooks::{self, HookContext, HookEvent};
+use crate::keymap::Keymap;
 use crate::scroll_state::ScrollState;
-use crate::session::{ClaudeCodeStatus, Session};
-use crate::tmux::Tmux;
+use crate::session::{ClaudeCodeStatus, Session, SessionSnapshot};
+use crate::tmux::{Tmux, DRAFT_PR_OPTION};
 
 // Re-export types that are part of the public API
+pub use clone_job::CloneJob;
+pub use macros::MacroRun;
 pub use mode::{
-    CreatePullRequestField, Mode, NewSessionField, NewWorktreeField, SessionAction,
+    CommentLine, CommitField, CreatePullRequestField, CreateTagField, Edits, Mode, NewSessionField,
+    NewWorktreeField, PaneDebug, SessionAction, SortMode,
+};
+pub use pr_status::{pr_label, PrRetry, PrStatus};
+pub use search::{filter_ranges, match_ranges, PreviewSearch};
+pub use text_input::TextInput;
+
+pub use helpers::{
+    canonical_path, expand_path, format_memory, is_session_name_char, truncate_display,
 };
 
 // Use helpers internally
-use helpers::{default_worktree_path, expand_path, sanitize_for_session_name};
+use helpers::{
+    comment_lines, default_worktree_path, diff_lines, glob_match, next_wrapping, pull_request_body,
+    sanitize_for_session_name, shell_quote, unique_name, worktree_session_name,
+};
+
+/// Columns of a commit subject or PR title quoted in a message
+const SUBJECT_COLUMNS: usize = 50;
 
 /// Main application state
 pub struct App {
@@ -38,6 +71,8 @@ pub struct App {
     pub mode: Mode,
     /// Whether the app should quit
     pub should_quit: bool,
+    /// Whether no tmux server was running at the last refresh (only checked when the list is empty)
+    pub no_server: bool,
     /// Name of the currently attached session (if any)
     pub current_session: Option<String>,
     /// Filter text for filtering sessions
@@ -48,20 +83,92 @@ pub struct App {
     pub message: Option<String>,
     /// Cached preview content for the selected session's pane
     pub preview_content: Option<String>,
+    /// Whether the preview re-captures the selected pane on every loop tick
+    pub follow_preview: bool,
+    /// Lines the preview is scrolled up from the bottom of its buffer
+    pub preview_scroll: usize,
+    /// Rows of preview content in the last drawn frame, so scrolling stops
+    /// once the top of the buffer is in view
+    pub preview_height: usize,
+    /// Columns the preview is scrolled right, for lines wider than the pane
+    pub preview_h_scroll: usize,
+    /// Active search in the preview, if any
+    pub preview_search: Option<PreviewSearch>,
     /// Available actions for the selected session (computed when entering action menu)
     pub available_actions: Vec<SessionAction>,
     /// Currently highlighted action in ActionMenu mode
     pub selected_action: usize,
     /// Action pending confirmation
     pub pending_action: Option<SessionAction>,
+    /// How a pending pull request merge lands, cycled in its confirmation
+    pub merge_strategy: MergeStrategy,
     /// PR info for the selected session (computed when entering action menu)
     pub pr_info: Option<PullRequestInfo>,
+    /// Size of the selected session's uncommitted changes (computed when
+    /// entering action menu, only for dirty trees)
+    pub diff_stat: Option<DiffStat>,
+    /// Whether the selected worktree session's branch is merged into the
+    /// base branch with nothing left uncommitted, so the worktree can go
+    /// (computed when entering action menu)
+    pub branch_merged: bool,
     /// Scroll state for the session list
     pub scroll_state: ScrollState,
+    /// Claude Code panes of the session rows drawn last, whose status is
+    /// kept current when `eager_sessions` caps status detection
+    pub shown_panes: Vec<String>,
+    /// Whether the session list uses the dense layout
+    pub dense: bool,
+    /// Whether sessions are grouped under repository header rows
+    pub group_by_repo: bool,
+    /// Whether the action menu shows the metadata, git and PR rows
+    pub menu_details: bool,
+    /// Only show sessions of this repository (its main working tree root)
+    pub repo_filter: Option<PathBuf>,
+    /// Whether sessions matching `hidden_sessions` are listed
+    pub show_hidden: bool,
+    /// Order of the session list
+    pub sort_mode: SortMode,
+    /// Whether debugging aids (`--debug`) are enabled
+    pub debug: bool,
+    /// Read-only mode for demos (`--presentation`): actions that change
+    /// sessions, repositories or pull requests are refused
+    pub presentation: bool,
+    /// Whether to quit once an action succeeds (`--quit-after-action`)
+    pub quit_after_action: bool,
+    /// Text of the dialog field to open in `$EDITOR`, taken by the main loop
+    /// which suspends the TUI while the editor runs
+    pub external_edit: Option<String>,
+    /// Clone started from the new session dialog, if one is running
+    pub clone_job: Option<CloneJob>,
+    /// Pull request lookup for the action menu that failed transiently and
+    /// is retried from the main loop
+    pub pr_retry: Option<PrRetry>,
+    /// Extra session list keys from the `[keymap]` config table
+    pub keymap: Keymap,
+    /// User configuration
+    pub config: Config,
+    /// Actions performed so far (persisted when `persist_history` is set)
+    pub history: History,
+    /// Color tags of sessions, by name
+    pub color_tags: ColorTags,
+    /// Layout of the last session killed with `Kill`, for one-time recreation
+    pub last_killed: Option<SessionSnapshot>,
+    /// Remote git action that failed to authenticate, and its session, for
+    /// retrying with `t` once credentials are available
+    pub retry: Option<(SessionAction, String)>,
+    /// Macro being run, step by step
+    pub macro_run: Option<MacroRun>,
+    /// Pull request state per repository and branch, for `pr_column`
+    pub pr_status: PrStatus,
+    /// Parsed `.claude-tmux.toml` files, keyed by file path (cleared on refresh)
+    repo_configs: HashMap<PathBuf, RepoConfig>,
     /// Cache of last captured content per pane ID, used for content-change status detection
     pane_content_cache: HashMap<String, String>,
     /// Timestamp of the last status tick
     last_status_tick: Instant,
+    /// New sessions whose startup command is still to be checked, with
+    /// when it was sent
+    startup_checks: Vec<(String, Instant)>,
 }
 
 impl App {
@@ -71,73 +178,385 @@ impl App {
 
     /// Create a new App instance
     pub fn new() -> Result<Self> {
-        let sessions = Tmux::list_sessions()?;
+        let (config, mut startup_error) = match Config::load() {
+            Ok(config) => (config, None),
+            Err(e) => (Config::default(), Some(format!("{:#}", e))),
+        };
+        if let Some(profile) = Config::missing_profile() {
+            startup_error.get_or_insert(format!(
+                "Profile '{}' not found, using the default config",
+                profile
+            ));
+        }
+        crate::limiter::configure(config.background_jobs);
+        let listed = Tmux::list_sessions(
+            config.directory_pane,
+            &config.detection,
+            config.eager_sessions,
+        )?;
+        let no_server = listed.is_none();
+        let sessions = listed.unwrap_or_default();
         let current_session = Tmux::current_session()?;
 
-        let mut app = Self {
+        let history = if config.persist_history {
+            History::load().unwrap_or_else(|e| {
+                startup_error.get_or_insert(format!("{:#}", e));
+                History::default()
+            })
+        } else {
+            History::default()
+        };
+
+        // Tags of sessions that are gone (e.g. after a tmux restart) are dropped
+        let mut color_tags = ColorTags::load().unwrap_or_else(|e| {
+            startup_error.get_or_insert(format!("{:#}", e));
+            ColorTags::default()
+        });
+        if color_tags.prune(sessions.iter().map(|s| s.name.as_str())) {
+            if let Err(e) = color_tags.save() {
+                startup_error.get_or_insert(format!("{:#}", e));
+            }
+        }
+
+        let macro_steps = config
+            .macros
+            .iter()
+            .map(|m| (format!("macro '{}'", m.name), &m.steps));
+        for (key, ids) in [
+            ("action_order".to_string(), &config.action_order),
+            ("type_to_confirm".to_string(), &config.type_to_confirm),
+        ]
+        .into_iter()
+        .chain(macro_steps)
+        {
+            let unknown_actions: Vec<&str> = ids
+                .iter()
+                .map(String::as_str)
+                .filter(|id| SessionAction::from_id(id).is_none())
+                .collect();
+            if !unknown_actions.is_empty() {
+                startup_error.get_or_insert(format!(
+                    "Unknown action in {}: {}",
+                    key,
+                    unknown_actions.join(", ")
+                ));
+            }
+        }
+
+        let (keymap, invalid_keys) = Keymap::from_config(&config.keymap);
+        if !invalid_keys.is_empty() {
+            startup_error.get_or_insert(format!(
+                "Unknown key in keymap: {}",
+                invalid_keys.join(", ")
+            ));
+        }
+
+        let mut app = Self::from_parts(config, sessions, keymap);
+        app.no_server = no_server;
+        app.current_session = current_session;
+        app.error = startup_error;
+        app.history = history;
+        app.color_tags = color_tags;
+
+        if let Some(eager) = app.config.eager_sessions.filter(|_| app.status_capped()) {
+            app.message = Some(format!(
+                "Read the status of the first {} of {} sessions; the rest load as they \
+                 scroll into view",
+                eager,
+                app.session_count()
+            ));
+        }
+
+        app.update_preview();
+        Ok(app)
+    }
+
+    /// An app over `sessions` with nothing loaded from disk or tmux: no
+    /// history, color tags or attached session
+    pub(crate) fn from_parts(config: Config, sessions: Vec<Session>, keymap: Keymap) -> Self {
+        Self {
             sessions,
+            no_server: false,
             selected: 0,
             mode: Mode::Normal,
             should_quit: false,
-            current_session,
+            current_session: None,
             filter: String::new(),
             error: None,
             message: None,
             preview_content: None,
+            follow_preview: false,
+            preview_scroll: 0,
+            preview_height: 0,
+            preview_h_scroll: 0,
+            preview_search: None,
             available_actions: Vec::new(),
             selected_action: 0,
             pending_action: None,
+            merge_strategy: MergeStrategy::default(),
             pr_info: None,
+            diff_stat: None,
+            branch_merged: false,
             scroll_state: ScrollState::new(),
+            shown_panes: Vec::new(),
+            dense: config.dense_list,
+            group_by_repo: config.group_by_repo,
+            menu_details: config.action_menu_details,
+            repo_filter: None,
+            show_hidden: false,
+            sort_mode: SortMode::default(),
+            debug: false,
+            presentation: false,
+            quit_after_action: config.quit_after_action,
+            external_edit: None,
+            clone_job: None,
+            pr_retry: None,
+            keymap,
+            config,
+            history: History::default(),
+            color_tags: ColorTags::default(),
+            last_killed: None,
+            retry: None,
+            macro_run: None,
+            pr_status: PrStatus::default(),
+            repo_configs: HashMap::new(),
             pane_content_cache: HashMap::new(),
             last_status_tick: Instant::now(),
-        };
+            startup_checks: Vec::new(),
+        }
+    }
 
-        app.update_preview();
-        Ok(app)
+    /// Number of tmux sessions, counting a session with several Claude Code
+    /// panes (and so several rows) once
+    fn session_count(&self) -> usize {
+        let names: HashSet<&str> = self.sessions.iter().map(|s| s.name.as_str()).collect();
+        names.len()
+    }
+
+    /// Whether there are more sessions than `eager_sessions`, so only the
+    /// status of the rows on screen is kept current
+    fn status_capped(&self) -> bool {
+        self.config
+            .eager_sessions
+            .is_some_and(|eager| self.session_count() > eager)
     }
 
-    /// Update the preview content for the currently selected session
+    /// Update the preview content for the currently selected session.
+    ///
+    /// The whole buffer is kept, including scrollback, so the preview can be
+    /// searched; only its tail is shown unless a search scrolls it.
     pub fn update_preview(&mut self) {
-        const PREVIEW_LINES: usize = 15;
+        const PREVIEW_LINES: usize = 1000;
 
-        let pane_id = self.selected_session().and_then(|session| {
+        let pane = self.selected_session().and_then(|session| {
             // Prefer Claude pane, fall back to first pane
-            session
-                .claude_code_pane
-                .clone()
-                .or_else(|| session.panes.first().map(|p| p.id.clone()))
+            let pane = match session.claude_code_pane {
+                Some(ref id) => session.panes.iter().find(|p| &p.id == id),
+                None => session.panes.first(),
+            };
+            pane.map(|p| (p.id.clone(), p.current_command.clone(), p.alternate_on))
         });
 
-        self.preview_content = pane_id.and_then(|id| {
+        self.preview_content = pane.and_then(|(id, command, alternate_on)| {
+            // The capture strategy follows what runs in the pane
+            let capture = self
+                .config
+                .preview_capture
+                .iter()
+                .find(|capture| glob_match(&capture.command, &command))
+                .cloned()
+                .unwrap_or_default();
             // Don't strip empty lines - preserve visual layout for preview
-            Tmux::capture_pane(&id, PREVIEW_LINES, false).ok()
+            Tmux::capture_pane_with_history(&id, PREVIEW_LINES, &capture, alternate_on).ok()
         });
+
+        // Matches move as the captured buffer changes
+        if let Some(mut search) = self.preview_search.take() {
+            search.matches = search::matching_lines(&self.preview_lines(), &search.query);
+            search.current = search.current.min(search.matches.len().saturating_sub(1));
+            self.preview_search = Some(search);
+        }
     }
 
-    /// Refresh Claude Code status for all panes using content-change detection.
+    /// Preview lines with ANSI styling removed
+    fn preview_lines(&self) -> Vec<String> {
+        let Some(ref content) = self.preview_content else {
+            return Vec::new();
+        };
+
+        match content.into_text() {
+            Ok(text) => text
+                .lines
+                .iter()
+                .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+                .collect(),
+            Err(_) => content.lines().map(str::to_string).collect(),
+        }
+    }
+
+    /// Scroll the preview up (positive `delta`) or down by `delta` lines,
+    /// stopping at the top of the captured buffer and at its tail
+    pub fn scroll_preview(&mut self, delta: isize) {
+        let top = self
+            .preview_lines()
+            .len()
+            .saturating_sub(self.preview_height);
+        self.preview_scroll = self.preview_scroll.saturating_add_signed(delta).min(top);
+    }
+
+    /// Scroll the preview sideways by `delta` columns, stopping at the
+    /// widest line
+    pub fn scroll_preview_columns(&mut self, delta: isize) {
+        let widest = self
+            .preview_lines()
+            .iter()
+            .map(|line| line.width())
+            .max()
+            .unwrap_or(0);
+        self.preview_h_scroll = self
+            .preview_h_scroll
+            .saturating_add_signed(delta)
+            .min(widest.saturating_sub(1));
+    }
+
+    /// Toggle live-following of the selected session's preview
+    pub fn toggle_follow_preview(&mut self) {
+        self.follow_preview = !self.follow_preview;
+        self.message = Some(if self.follow_preview {
+            "Following preview".to_string()
+        } else {
+            "Stopped following preview".to_string()
+        });
+    }
+
+    /// Re-capture the preview when following is enabled.
+    ///
+    /// Called on every main-loop iteration (roughly every 100 ms) so the
+    /// preview tracks the selected pane's output in near real time.
+    pub fn tick_preview(&mut self) {
+        if self.follow_preview {
+            self.update_preview();
+        }
+    }
+
+    /// Refresh Claude Code status for all panes.
     ///
     /// Called on every main-loop iteration but self-throttles to run at most
-    /// every 500 ms. Compares the current pane capture against the previous
-    /// one: if the content changed the session is Working; if it is the same
-    /// we fall back to static text inspection for Idle / WaitingInput / Unknown.
+    /// every 500 ms.
     pub fn tick_status(&mut self) {
         const STATUS_INTERVAL: Duration = Duration::from_millis(500);
         if self.last_status_tick.elapsed() < STATUS_INTERVAL {
             return;
         }
+        self.refresh_statuses();
+        self.check_startup_commands();
+        if self.config.pr_column {
+            self.refresh_pr_statuses();
+        }
+    }
+
+    /// Look the pull request up again once a transiently failed lookup for
+    /// the action menu is due, keeping the highlighted action
+    pub fn tick_pr_retry(&mut self) {
+        let Some(retry) = &self.pr_retry else {
+            return;
+        };
+        if !retry.is_due() {
+            return;
+        }
+        let still_shown = matches!(self.mode, Mode::ActionMenu)
+            && self
+                .selected_session()
+                .and_then(|s| s.git_context())
+                .is_some_and(|git| retry.is_for(&git.workdir, &git.branch));
+        if !still_shown {
+            self.pr_retry = None;
+            return;
+        }
+
+        let highlighted = self.available_actions.get(self.selected_action).cloned();
+        self.compute_actions();
+        if let Some(index) =
+            highlighted.and_then(|action| self.available_actions.iter().position(|a| *a == action))
+        {
+            self.selected_action = index;
+        }
+    }
+
+    /// Take in finished pull request lookups and queue lookups for sessions
+    /// whose git context is known (detected when their row was shown)
+    fn refresh_pr_statuses(&mut self) {
+        self.pr_status.poll();
+
+        let max_age = Duration::from_secs(self.config.pr_column_interval);
+        for session in &self.sessions {
+            if let Some(Some(git)) = session.git.get() {
+                if git.has_upstream {
+                    self.pr_status.request(&git.workdir, &git.branch, max_age);
+                }
+            }
+        }
+    }
+
+    /// Check a new session for a failed startup command once
+    /// `check_startup_command` is due
+    fn schedule_startup_check(&mut self, session_name: &str) {
+        if self.config.check_startup_command {
+            self.startup_checks
+                .push((session_name.to_string(), Instant::now()));
+        }
+    }
+
+    /// Look at new sessions' panes a moment after their startup command was
+    /// sent, warning about "command not found"-style output. Each session
+    /// is checked once, so a slow start is never waited on.
+    fn check_startup_commands(&mut self) {
+        const STARTUP_DELAY: Duration = Duration::from_secs(2);
+
+        let (due, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.startup_checks)
+            .into_iter()
+            .partition(|(_, sent)| sent.elapsed() >= STARTUP_DELAY);
+        self.startup_checks = pending;
+
+        for (session_name, _) in due {
+            let Ok(content) = Tmux::capture_pane(&session_name, 20, true) else {
+                continue;
+            };
+            if let Some(line) = detect_launch_failure(&content) {
+                self.error = Some(format!(
+                    "Startup command in '{}' may have failed: {}",
+                    session_name, line
+                ));
+            }
+        }
+    }
+
+    /// Refresh Claude Code status for all panes using content-change detection.
+    ///
+    /// Compares the current pane capture against the previous one: if the
+    /// content changed the session is Working; if it is the same we fall back
+    /// to static text inspection for Idle / WaitingInput / Unknown.
+    fn refresh_statuses(&mut self) {
         self.last_status_tick = Instant::now();
+        // The status sort reorders the list as statuses change
+        let selected_name = (self.sort_mode == SortMode::Status)
+            .then(|| self.selected_session().map(|s| s.display_name()))
+            .flatten();
 
         // Collect (session_index, pane_id) first to satisfy the borrow checker.
+        // With too many sessions, only the rows on screen are captured.
+        let capped = self.status_capped();
         let targets: Vec<(usize, String)> = self
             .sessions
             .iter()
             .enumerate()
             .filter_map(|(i, s)| s.claude_code_pane.as_ref().map(|id| (i, id.clone())))
+            .filter(|(_, id)| !capped || self.shown_panes.contains(id))
             .collect();
 
         for (idx, pane_id) in targets {
-            let Ok(content) = Tmux::capture_pane(&pane_id, 15, true) else {
+            let detection = &self.config.detection;
+            let Ok(content) = Tmux::capture_pane(&pane_id, detection.status_lines, true) else {
                 continue;
             };
 
@@ -145,14 +564,15 @@ impl App {
                 // Content changed since last tick → definitely working
                 Some(prev) if prev != &content => ClaudeCodeStatus::Working,
                 // Content unchanged → use static text check
-                Some(_) => detect_static_status(&content),
+                Some(_) => detect_static_status(&content, detection),
                 // No cached entry yet → fall back to full text detection
-                None => detect_status(&content),
+                None => detect_status(&content, detection),
             };
 
             self.sessions[idx].claude_code_status = status;
             self.pane_content_cache.insert(pane_id, content);
         }
+        self.reselect(selected_name);
     }
 
     /// Clear any displayed messages
@@ -161,20 +581,198 @@ impl App {
         self.message = None;
     }
 
+    /// Effective config for actions scoped to `path`: the global config
+    /// merged with the nearest `.claude-tmux.toml`, if any.
+    ///
+    /// A malformed repo config is reported and the global config is used.
+    fn config_for(&mut self, path: &Path) -> Config {
+        let Some(file) = RepoConfig::find(path) else {
+            return self.config.clone();
+        };
+
+        if !self.repo_configs.contains_key(&file) {
+            match RepoConfig::load(&file) {
+                Ok(repo_config) => {
+                    self.repo_configs.insert(file.clone(), repo_config);
+                }
+                Err(e) => {
+                    self.error = Some(format!("{:#}", e));
+                    return self.config.clone();
+                }
+            }
+        }
+
+        self.config
+            .merged_with(&self.repo_configs[&file], self.config.trusts(&file))
+    }
+
+    /// Base branch for PRs and squashing: the configured `default_base_branch`,
+    /// else the remote's default branch
+    fn base_branch_for(&mut self, path: &Path) -> Option<String> {
+        self.config_for(path)
+            .default_base_branch
+            .or_else(|| git::get_default_branch(path))
+    }
+
+    /// Run the configured hook for an event, if any.
+    ///
+    /// Hooks are fail-soft: a hook that cannot start is reported, but the
+    /// action that triggered it has already completed.
+    fn run_hook(&mut self, event: HookEvent, session: &str, path: &Path, branch: Option<&str>) {
+        let Some(command) = self.config.hooks.command(event).map(str::to_string) else {
+            return;
+        };
+
+        let context = HookContext {
+            session,
+            path,
+            branch,
+        };
+        if let Err(e) = hooks::run(&command, event, &context) {
+            self.error = Some(format!("{:#}", e));
+        }
+    }
+
+    /// Record the outcome of an action in the history.
+    ///
+    /// The outcome is taken from the message or error the action just set;
+    /// actions that set neither (e.g. opening a dialog) are not recorded.
+    /// A success message wins over an error, since errors set alongside one
+    /// come from hooks that run after the action completed.
+    fn record_history(&mut self, action: &str, session: &str) {
+        let (ok, detail) = match (&self.message, &self.error) {
+            (Some(message), _) => (true, message.clone()),
+            (None, Some(error)) => (false, error.clone()),
+            (None, None) => return,
+        };
+
+        self.history.record(action, session, ok, &detail);
+        if self.config.persist_history {
+            if let Err(e) = self.history.save() {
+                self.error = Some(format!("{:#}", e));
+            }
+        }
+
+        if ok {
+            self.quit_if_single_shot();
+        }
+    }
+
+    /// With `quit_after_action`, quit once an action (or a whole macro) has
+    /// succeeded. Anything that set an error keeps the app open to show it.
+    fn quit_if_single_shot(&mut self) {
+        if self.quit_after_action && self.error.is_none() && self.macro_run.is_none() {
+            self.should_quit = true;
+        }
+    }
+
     /// Refresh the session list (shows "Refreshed" message)
     pub fn refresh(&mut self) {
         self.clear_messages();
+        self.repo_configs.clear();
+        self.pr_status.clear();
         if self.refresh_sessions() {
             self.message = Some("Refreshed".to_string());
         }
     }
 
+    /// Re-detect just the selected session (its panes, status and git
+    /// context), rebuilding its rows in place so the rest of the list stays
+    /// as it is
+    pub fn refresh_selected(&mut self) {
+        self.clear_messages();
+        let Some(name) = self.selected_session().map(|s| s.name.clone()) else {
+            return;
+        };
+
+        let rows =
+            match Tmux::list_session(&name, self.config.directory_pane, &self.config.detection) {
+                Ok(rows) => rows,
+                Err(e) => {
+                    self.error = Some(format!("Failed to refresh '{}': {}", name, e));
+                    return;
+                }
+            };
+
+        // Forget what was cached about the old rows
+        for old in self.sessions.iter().filter(|s| s.name == name) {
+            if let Some(ref pane_id) = old.claude_code_pane {
+                self.pane_content_cache.remove(pane_id);
+            }
+            self.pr_status.forget(&old.working_directory);
+        }
+
+        let at = self.sessions.iter().position(|s| s.name == name);
+        self.sessions.retain(|s| s.name != name);
+        if rows.is_empty() {
+            self.message = Some(format!("Session '{}' no longer exists", name));
+        } else {
+            let at = at.unwrap_or(self.sessions.len());
+            self.sessions.splice(at..at, rows);
+            self.message = Some(format!("Refreshed '{}'", name));
+        }
+
+        let count = self.filtered_sessions().len();
+        if self.selected >= count {
+            self.selected = count.saturating_sub(1);
+        }
+        self.update_preview();
+    }
+
+    /// Refresh sessions and statuses right away, without a message. Used
+    /// when the terminal regains focus, so the list is current on return.
+    pub fn refresh_on_focus(&mut self) {
+        if self.refresh_sessions() {
+            self.refresh_statuses();
+        }
+    }
+
     /// Refresh sessions without affecting messages (for use after git operations)
     fn refresh_sessions(&mut self) -> bool {
         self.pane_content_cache.clear();
-        match Tmux::list_sessions() {
-            Ok(sessions) => {
-                self.sessions = sessions;
+        match Tmux::list_sessions(
+            self.config.directory_pane,
+            &self.config.detection,
+            self.config.eager_sessions,
+        ) {
+            Ok(listed) => {
+                self.no_server = listed.is_none();
+                let sessions = listed.unwrap_or_default();
+                let selected_name = self.selected_session().map(|s| s.display_name());
+                let previous = std::mem::replace(&mut self.sessions, sessions);
+                // Rows past `eager_sessions` are listed without reading their
+                // status; keep the last one read so the counts still include them
+                if self.status_capped() {
+                    for session in &mut self.sessions {
+                        if session.claude_code_status != ClaudeCodeStatus::Unknown {
+                            continue;
+                        }
+                        if let Some(known) = previous.iter().find(|old| {
+                            old.claude_code_pane.is_some()
+                                && old.claude_code_pane == session.claude_code_pane
+                        }) {
+                            session.claude_code_status = known.claude_code_status;
+                        }
+                    }
+                }
+                // The attached session changes with switches and renames made
+                // while running. Read it from the listing's attached flags,
+                // keeping the known one while it is still attached, since
+                // other clients may be attached elsewhere.
+                let still_attached = self.current_session.as_ref().is_some_and(|current| {
+                    self.sessions
+                        .iter()
+                        .any(|s| s.attached && &s.name == current)
+                });
+                if !still_attached {
+                    self.current_session = self
+                        .sessions
+                        .iter()
+                        .find(|s| s.attached)
+                        .map(|s| s.name.clone());
+                }
+                // Sorts by activity or status may have moved the selected row
+                self.reselect(selected_name);
                 // Ensure selected index is still valid
                 if self.selected >= self.sessions.len() && !self.sessions.is_empty() {
                     self.selected = self.sessions.len() - 1;
@@ -194,19 +792,222 @@ impl App {
     // =========================================================================
 
     /// Get filtered sessions based on current filter
+    ///
+    /// Sessions are ordered by `sort_mode`. When grouping by repo, sessions
+    /// of the same repository are then made adjacent (groups in order of
+    /// first appearance, non-git sessions last).
     pub fn filtered_sessions(&self) -> Vec<&Session> {
-        if self.filter.is_empty() {
-            self.sessions.iter().collect()
-        } else {
-            let filter_lower = self.filter.to_lowercase();
-            self.sessions
+        self.filtered_session_matches()
+            .into_iter()
+            .map(|(session, _)| session)
+            .collect()
+    }
+
+    /// `filtered_sessions`, each with the byte ranges of its name that
+    /// matched the filter (empty when only the path matched)
+    pub fn filtered_session_matches(&self) -> Vec<(&Session, Vec<Range<usize>>)> {
+        let mut visible: Vec<&Session> = self
+            .sessions
+            .iter()
+            .filter(|s| self.show_hidden || !self.is_hidden(s))
+            .collect();
+        // Sorting first is the same as sorting the matches: the sort is
+        // stable and compares sessions only by their own fields
+        self.sort_sessions(&mut visible);
+
+        let mut filtered: Vec<(&Session, Vec<Range<usize>>)> = visible
+            .into_iter()
+            .filter(|s| {
+                self.repo_filter
+                    .as_ref()
+                    .is_none_or(|repo| s.repo_key() == Some(repo.as_path()))
+            })
+            .filter_map(|s| {
+                if self.filter.is_empty() {
+                    return Some((s, Vec::new()));
+                }
+                let ranges = filter_ranges(&s.name, &self.filter);
+                let matches = !ranges.is_empty()
+                    || !filter_ranges(&s.display_path(), &self.filter).is_empty();
+                matches.then_some((s, ranges))
+            })
+            .collect();
+
+        if self.group_by_repo {
+            let mut group_order: Vec<&std::path::Path> = Vec::new();
+            for (session, _) in &filtered {
+                if let Some(key) = session.repo_key() {
+                    if !group_order.contains(&key) {
+                        group_order.push(key);
+                    }
+                }
+            }
+            // Stable sort keeps the existing order within each group
+            filtered.sort_by_key(|(s, _)| {
+                s.repo_key()
+                    .and_then(|key| group_order.iter().position(|k| *k == key))
+                    .unwrap_or(usize::MAX)
+            });
+        }
+
+        filtered
+    }
+
+    /// Order sessions by the current sort mode. The sort is stable, so
+    /// ties keep tmux's attached-then-name order.
+    fn sort_sessions(&self, sessions: &mut [&Session]) {
+        match self.sort_mode {
+            SortMode::Default => {}
+            SortMode::DirtyFirst => {
+                sessions.sort_by_key(|s| {
+                    let git = s.git_context();
+                    let dirty = git.is_some_and(|g| g.is_dirty());
+                    let ahead = git.map_or(0, |g| g.ahead);
+                    (!dirty, std::cmp::Reverse(ahead))
+                });
+            }
+            SortMode::Status => {
+                // Statuses left out of `status_order` go after the listed ones
+                let order = &self.config.status_order;
+                let rank = |s: &Session| {
+                    order
+                        .iter()
+                        .position(|status| *status == s.claude_code_status)
+                        .unwrap_or(order.len())
+                };
+                sessions.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.name.cmp(&b.name)));
+            }
+            SortMode::Name => sessions.sort_by(|a, b| a.name.cmp(&b.name)),
+            SortMode::Uptime => sessions.sort_by_key(|s| s.created),
+            SortMode::Activity => sessions.sort_by_key(|s| std::cmp::Reverse(s.activity)),
+        }
+    }
+
+    /// Cycle the sort mode, keeping the same session selected
+    pub fn cycle_sort_mode(&mut self) {
+        let selected_name = self.selected_session().map(|s| s.display_name());
+        self.sort_mode = self.sort_mode.next();
+        self.reselect(selected_name);
+        self.message = Some(format!("Sort: {}", self.sort_mode.label()));
+    }
+
+    /// Keep the selection on the row with display name `name` after the
+    /// list was reordered; it stays put if that row is gone
+    fn reselect(&mut self, name: Option<String>) {
+        if let Some(name) = name {
+            if let Some(idx) = self
+                .filtered_sessions()
                 .iter()
-                .filter(|s| {
-                    s.name.to_lowercase().contains(&filter_lower)
-                        || s.display_path().to_lowercase().contains(&filter_lower)
-                })
-                .collect()
+                .position(|s| s.display_name() == name)
+            {
+                self.selected = idx;
+            }
+        }
+    }
+
+    /// Number of repo header rows shown above (and including the group of)
+    /// the filtered session at `index`. Always 0 when not grouping.
+    pub fn group_headers_through(&self, index: usize) -> usize {
+        if !self.group_by_repo {
+            return 0;
+        }
+        let filtered = self.filtered_sessions();
+        let mut headers = 0;
+        for (i, session) in filtered.iter().enumerate().take(index + 1) {
+            if i == 0 || filtered[i - 1].repo_key() != session.repo_key() {
+                headers += 1;
+            }
+        }
+        headers
+    }
+
+    /// Toggle grouping sessions by repository
+    pub fn toggle_group_by_repo(&mut self) {
+        // Keep the same session selected across the reordering
+        let selected_name = self.selected_session().map(|s| s.display_name());
+        self.group_by_repo = !self.group_by_repo;
+        self.reselect(selected_name);
+        self.update_preview();
+    }
+
+    /// Toggle showing only sessions of the selected session's repository
+    pub fn toggle_repo_filter(&mut self) {
+        self.clear_messages();
+        let selected_name = self.selected_session().map(|s| s.display_name());
+
+        if self.repo_filter.is_some() {
+            self.repo_filter = None;
+        } else {
+            let Some(session) = self.selected_session() else {
+                return;
+            };
+            let Some(repo) = session.repo_key() else {
+                self.error = Some(format!(
+                    "Cannot filter by repository: '{}' is not a git repository",
+                    session.display_path()
+                ));
+                return;
+            };
+            self.repo_filter = Some(repo.to_path_buf());
+        }
+
+        // The selected session stays visible either way
+        self.reselect(selected_name);
+        self.update_preview();
+    }
+
+    /// Whether a session matches one of the `hidden_sessions` patterns
+    pub fn is_hidden(&self, session: &Session) -> bool {
+        self.config
+            .hidden_sessions
+            .iter()
+            .any(|pattern| glob_match(pattern, &session.name))
+    }
+
+    /// Number of sessions currently left out of the list by `hidden_sessions`
+    pub fn hidden_count(&self) -> usize {
+        if self.show_hidden {
+            return 0;
         }
+        self.sessions.iter().filter(|s| self.is_hidden(s)).count()
+    }
+
+    /// Toggle listing the sessions matching `hidden_sessions`
+    pub fn toggle_show_hidden(&mut self) {
+        self.clear_messages();
+        if self.config.hidden_sessions.is_empty() {
+            self.message = Some("No hidden_sessions patterns configured".to_string());
+            return;
+        }
+        let selected_name = self.selected_session().map(|s| s.display_name());
+        self.show_hidden = !self.show_hidden;
+
+        // Keep the selection on the same session if it is still listed
+        self.selected = selected_name
+            .and_then(|name| {
+                self.filtered_sessions()
+                    .iter()
+                    .position(|s| s.display_name() == name)
+            })
+            .unwrap_or(0);
+        self.update_preview();
+    }
+
+    /// Back to a clean view: no filter or repository filter, hidden sessions
+    /// hidden again, default sort, no preview search or following, and the
+    /// first session selected
+    pub fn reset_view(&mut self) {
+        self.clear_messages();
+        self.filter.clear();
+        self.repo_filter = None;
+        self.show_hidden = false;
+        self.sort_mode = SortMode::default();
+        self.follow_preview = false;
+        self.preview_h_scroll = 0;
+        self.clear_preview_search();
+        self.selected = 0;
+        self.update_preview();
+        self.message = Some("View reset".to_string());
     }
 
     /// Get the currently selected session
@@ -220,6 +1021,9 @@ impl App {
         let count = self.filtered_sessions().len();
         if count > 0 && self.selected > 0 {
             self.selected -= 1;
+            self.follow_preview = false;
+            self.preview_h_scroll = 0;
+            self.clear_preview_search();
             self.update_preview();
         }
     }
@@ -229,16 +1033,55 @@ impl App {
         let count = self.filtered_sessions().len();
         if count > 0 && self.selected < count - 1 {
             self.selected += 1;
+            self.follow_preview = false;
+            self.preview_h_scroll = 0;
+            self.clear_preview_search();
             self.update_preview();
         }
     }
 
+    /// Move selection to the next (or previous) session waiting for input,
+    /// wrapping around the list
+    pub fn select_waiting(&mut self, forward: bool) {
+        self.clear_messages();
+        let filtered = self.filtered_sessions();
+        let next = next_wrapping(filtered.len(), self.selected, forward, |i| {
+            filtered[i].claude_code_status == ClaudeCodeStatus::WaitingInput
+        });
+
+        match next {
+            Some(idx) if idx != self.selected => {
+                self.selected = idx;
+                self.follow_preview = false;
+                self.preview_h_scroll = 0;
+                self.clear_preview_search();
+                self.update_preview();
+            }
+            Some(_) => {}
+            None => self.message = Some("No sessions waiting for input".to_string()),
+        }
+    }
+
     /// Switch to the selected session
     pub fn switch_to_selected(&mut self) {
         self.clear_messages();
+        let action = if self.config.enter_read_only {
+            SessionAction::SwitchReadOnly
+        } else {
+            SessionAction::SwitchTo
+        };
+        if self.switch_needs_confirmation(&action) {
+            self.confirm_or_execute(action);
+            return;
+        }
         if let Some(session) = self.selected_session() {
             let target = session.switch_target();
-            match Tmux::switch_to_session(&target) {
+            let result = if self.config.enter_read_only {
+                Tmux::switch_to_session_read_only(&target)
+            } else {
+                Tmux::switch_to_session(&target)
+            };
+            match result {
                 Ok(_) => {
                     self.should_quit = true;
                 }
@@ -249,18 +1092,70 @@ impl App {
         }
     }
 
-    // =========================================================================
-    // Action menu
-    // =========================================================================
-
-    /// Enter the action menu for the selected session
-    pub fn enter_action_menu(&mut self) {
+    /// Copy a `cd <working directory>` command for the selected session to the clipboard
+    pub fn copy_cd_command(&mut self) {
         self.clear_messages();
-        if self.selected_session().is_some() {
-            self.compute_actions();
-            self.mode = Mode::ActionMenu;
-        }
-    }
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+
+        let path = expand_path(&session.working_directory.to_string_lossy());
+        let command = format!("cd {}", shell_quote(&path.to_string_lossy()));
+
+        match crate::clipboard::copy(&command) {
+            Ok(_) => self.message = Some(format!("Copied: {}", command)),
+            Err(e) => self.error = Some(format!("Failed to copy: {}", e)),
+        }
+    }
+
+    /// Start `$EDITOR` in the selected session's working directory, typed
+    /// into one of its panes; claude-tmux stays open
+    fn open_editor(&mut self) {
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        let Some(pane) = session.command_pane() else {
+            self.error = Some("The session has no pane to open an editor in".to_string());
+            return;
+        };
+
+        let path = expand_path(&session.working_directory.to_string_lossy());
+        let command = format!(
+            "cd {} && ${{EDITOR:-vi}} .",
+            shell_quote(&path.to_string_lossy())
+        );
+        let name = session.name.clone();
+        let pane_id = pane.id.clone();
+
+        match Tmux::send_keys(&pane_id, &command) {
+            Ok(_) => self.message = Some(format!("Opened the editor in '{}' ({})", name, pane_id)),
+            Err(e) => self.error = Some(format!("Failed to open editor: {:#}", e)),
+        }
+    }
+
+    /// Toggle the dense session list layout
+    pub fn toggle_dense(&mut self) {
+        self.dense = !self.dense;
+    }
+
+    /// Show or hide the detail rows above the action menu
+    pub fn toggle_menu_details(&mut self) {
+        self.menu_details = !self.menu_details;
+    }
+
+    // =========================================================================
+    // Action menu
+    // =========================================================================
+
+    /// Enter the action menu for the selected session
+    pub fn enter_action_menu(&mut self) {
+        self.clear_messages();
+        if self.selected_session().is_some() {
+            self.pr_retry = None;
+            self.compute_actions();
+            self.mode = Mode::ActionMenu;
+        }
+    }
 
     /// Move to next action in the action menu
     pub fn select_next_action(&mut self) {
@@ -283,53 +1178,228 @@ impl App {
     /// Execute the currently selected action from the action menu
     pub fn execute_selected_action(&mut self) {
         if let Some(action) = self.available_actions.get(self.selected_action).cloned() {
-            if action.requires_confirmation() {
-                self.pending_action = Some(action);
-                self.mode = Mode::ConfirmAction;
-            } else {
-                // execute_action handles its own mode transitions
-                self.execute_action(action);
-            }
+            self.confirm_or_execute(action);
+        }
+    }
+
+    /// Ask for confirmation of `action` if it needs any, otherwise execute
+    /// it. Actions listed in `type_to_confirm` need the session name typed.
+    fn confirm_or_execute(&mut self, action: SessionAction) {
+        if !action.is_read_only() && self.refused_in_presentation(action.label()) {
+            return;
+        }
+        self.merge_strategy = MergeStrategy::default();
+        if self
+            .config
+            .type_to_confirm
+            .iter()
+            .any(|id| id == action.id())
+        {
+            let Some(session_name) = self.selected_session().map(|s| s.name.clone()) else {
+                return;
+            };
+            self.pending_action = Some(action);
+            self.mode = Mode::TypeToConfirm {
+                expected: session_name,
+                input: TextInput::new(),
+            };
+        } else if action.requires_confirmation() || self.switch_needs_confirmation(&action) {
+            self.pending_action = Some(action);
+            self.mode = Mode::ConfirmAction;
+        } else {
+            // execute_action handles its own mode transitions
+            self.execute_action(action);
         }
     }
 
+    /// Measure the CPU and memory use of the selected session's processes,
+    /// storing it on each of the session's rows
+    fn measure_selected_usage(&mut self) {
+        let Some(name) = self.selected_session().map(|s| s.name.clone()) else {
+            return;
+        };
+        let usage = Tmux::session_usage(&name);
+        for session in self.sessions.iter_mut().filter(|s| s.name == name) {
+            session.cpu_percent = usage.map(|(cpu, _)| cpu);
+            session.memory_kb = usage.map(|(_, memory)| memory);
+        }
+    }
+
+    /// Whether the action pending confirmation merges a pull request
+    pub fn merge_pending(&self) -> bool {
+        matches!(
+            self.pending_action,
+            Some(SessionAction::MergePullRequest | SessionAction::MergePullRequestAndClose)
+        )
+    }
+
+    /// Cycle the strategy of a pending pull request merge
+    pub fn cycle_merge_strategy(&mut self, forward: bool) {
+        if self.merge_pending() {
+            self.merge_strategy = self.merge_strategy.cycle(forward);
+        }
+    }
+
+    /// Whether `action` switches to the selected session while its Claude is
+    /// working and `confirm_switch_to_working` asks to confirm that
+    fn switch_needs_confirmation(&self, action: &SessionAction) -> bool {
+        self.config.confirm_switch_to_working
+            && matches!(
+                action,
+                SessionAction::SwitchTo | SessionAction::SwitchReadOnly
+            )
+            && self
+                .selected_session()
+                .is_some_and(|s| s.claude_code_status == ClaudeCodeStatus::Working)
+    }
+
     /// Compute available actions for the selected session
     fn compute_actions(&mut self) {
         // Extract data we need from the session first to avoid borrow conflicts
         let session_data = self.selected_session().map(|s| {
-            (s.working_directory.clone(), s.git_context.clone())
+            (
+                s.working_directory.clone(),
+                s.git_context().cloned(),
+                !s.panes.is_empty(),
+            )
         });
 
-        let Some((working_dir, git_context)) = session_data else {
+        let Some((working_dir, git_context, has_panes)) = session_data else {
             self.available_actions = vec![];
             self.pr_info = None;
+            self.diff_stat = None;
+            self.branch_merged = false;
             return;
         };
 
-        let mut actions = vec![SessionAction::SwitchTo, SessionAction::Rename];
+        let mut actions = vec![
+            SessionAction::SwitchTo,
+            SessionAction::SwitchReadOnly,
+            SessionAction::Rename,
+            SessionAction::RenameWindow,
+            SessionAction::NewScratch,
+            SessionAction::ColorTag,
+        ];
+
+        // Compare: needs another session, and a tmux client to open a window in
+        if self.sessions.len() > 1 && std::env::var_os("TMUX").is_some() {
+            actions.push(SessionAction::Compare);
+        }
+
+        // Editor: typed into one of the session's panes
+        if has_panes {
+            actions.push(SessionAction::OpenEditor);
+        }
+        actions.push(SessionAction::CopyPath);
 
         // Reset PR info
         self.pr_info = None;
 
+        // Diffstat for the expanded view, only worth computing when dirty
+        self.diff_stat = git_context
+            .as_ref()
+            .filter(|git| git.is_dirty())
+            .and_then(|_| GitContext::diff_stat(&working_dir).ok());
+
+        // Resource usage walks /proc, so only the expanded session is measured
+        self.measure_selected_usage();
+
+        // Cleanup hint for worktrees whose work has landed
+        self.branch_merged = match git_context.as_ref() {
+            Some(git) if git.is_worktree && !git.is_dirty() => self
+                .base_branch_for(&working_dir)
+                .filter(|base| *base != git.branch)
+                .is_some_and(|base| {
+                    GitContext::is_merged_into(&working_dir, &base).unwrap_or(false)
+                }),
+            _ => false,
+        };
+
         // Add git actions if applicable
-        if let Some(ref git) = git_context {
+        if let Some((git, Some(state))) = git_context.as_ref().map(|git| (git, git.state)) {
+            // Mid-operation: only resolving the operation makes sense
+            if git.has_unstaged || git.has_untracked {
+                actions.push(SessionAction::Stage);
+            }
+            if git.is_dirty() {
+                actions.push(SessionAction::StageFiles);
+                actions.push(SessionAction::ViewDiff);
+            }
+            if state.can_continue() {
+                actions.push(SessionAction::ContinueOperation);
+            }
+            actions.push(SessionAction::AbortOperation);
+        } else if let Some(ref git) = git_context {
             // New worktree: available for any git repo
             actions.push(SessionAction::NewWorktree);
 
-            // Stage: if there are unstaged changes
-            if git.has_unstaged {
+            // Repo root: only when the session is in a subdirectory
+            if canonical_path(&working_dir) != canonical_path(&git.workdir) {
+                actions.push(SessionAction::OpenRepoRoot);
+            }
+
+            // Stage: if there are unstaged changes or untracked files
+            if git.has_unstaged || git.has_untracked {
                 actions.push(SessionAction::Stage);
             }
+            // Stage files and view diff: for any uncommitted change
+            if git.is_dirty() {
+                actions.push(SessionAction::StageFiles);
+                actions.push(SessionAction::ViewDiff);
+            }
             // Commit: if there are staged changes
             if git.has_staged {
                 actions.push(SessionAction::Commit);
             }
+            // Stash: shelve any uncommitted change; pop when one is shelved
+            if git.is_dirty() {
+                actions.push(SessionAction::Stash);
+            }
+            if GitContext::has_stash(&working_dir) {
+                actions.push(SessionAction::StashPop);
+            }
+
+            // Squash: clean tree with several commits since the base branch
+            if !git.is_dirty() {
+                if let Some(base_branch) = self.base_branch_for(&working_dir) {
+                    let squashable = git.branch != base_branch
+                        && GitContext::commits_since_base(&working_dir, &base_branch)
+                            .is_ok_and(|commits| commits.len() > 1);
+                    if squashable {
+                        actions.push(SessionAction::Squash);
+                    }
+                }
+            }
+
+            // Tags: listing is always available, tagging requires a clean tree
+            actions.push(SessionAction::ViewTags);
+            if !git.is_dirty() {
+                actions.push(SessionAction::CreateTag);
+            }
+            // Which tags the remote lacks is only known by asking it, when run
+            if git.has_remote && GitContext::list_tags(&working_dir).is_ok_and(|t| !t.is_empty()) {
+                actions.push(SessionAction::PushTags);
+            }
+
+            // Copying HEAD's SHA or branch name, for pasting elsewhere
+            actions.push(SessionAction::CopySha);
+            actions.push(SessionAction::CopyBranch);
 
             // Fetch: always available if there's a remote (safe operation)
             if git.has_remote {
                 actions.push(SessionAction::Fetch);
             }
 
+            // Merge base branch: clean tree on a branch other than the base
+            if git.has_remote
+                && !git.is_dirty()
+                && self
+                    .base_branch_for(&working_dir)
+                    .is_some_and(|base_branch| git.branch != base_branch)
+            {
+                actions.push(SessionAction::MergeBaseBranch);
+            }
+
             if git.has_upstream {
                 // Push: ahead > 0 (dirty state doesn't prevent pushing commits)
                 if git.ahead > 0 {
@@ -340,16 +1410,36 @@ impl App {
                     actions.push(SessionAction::Pull);
                 }
 
-                // PR actions: upstream exists, gh available, GitHub remote, not on default branch
-                if git::is_gh_available() && git::is_github_remote(&working_dir) {
+                // PR actions: upstream exists, supported host with its CLI available,
+                // not on default branch
+                if let Some(provider) =
+                    git::detect_provider(&working_dir).filter(|provider| provider.is_available())
+                {
                     // Check if not on default branch
                     if let Some(default_branch) = git::get_default_branch(&working_dir) {
                         if git.branch != default_branch {
                             // Check if PR already exists for this branch
-                            let pr_info = git::get_pull_request_info(&working_dir);
+                            let pr_info = match provider.get_pull_request_info(&working_dir) {
+                                Ok(info) => {
+                                    self.pr_retry = None;
+                                    self.pr_status
+                                        .store(&git.workdir, &git.branch, info.clone());
+                                    info
+                                }
+                                Err(_) => {
+                                    self.pr_retry = PrRetry::after(
+                                        self.pr_retry.as_ref(),
+                                        &git.workdir,
+                                        &git.branch,
+                                    );
+                                    None
+                                }
+                            };
                             if let Some(ref info) = pr_info {
                                 if info.state == "OPEN" {
                                     actions.push(SessionAction::ViewPullRequest);
+                                    actions.push(SessionAction::ViewPullRequestDiff);
+                                    actions.push(SessionAction::ViewPullRequestComments);
                                     actions.push(SessionAction::ClosePullRequest);
                                     actions.push(SessionAction::MergePullRequest);
                                     actions.push(SessionAction::MergePullRequestAndClose);
@@ -381,6 +1471,7 @@ impl App {
             }
         }
 
+        helpers::order_actions(&mut actions, &self.config.action_order);
         self.available_actions = actions;
         self.selected_action = 0;
     }
@@ -389,31 +1480,98 @@ impl App {
     // Action execution
     // =========================================================================
 
+    /// In presentation mode, refuse `what` with a message. Returns whether
+    /// it was refused.
+    fn refused_in_presentation(&mut self, what: &str) -> bool {
+        if self.presentation {
+            let what = what.trim_end_matches('.');
+            self.message = Some(format!("Read-only mode: {} is disabled", what));
+        }
+        self.presentation
+    }
+
     /// Start the kill confirmation flow (direct kill without action menu)
     pub fn start_kill(&mut self) {
         self.clear_messages();
         if self.selected_session().is_some() {
-            self.pending_action = Some(SessionAction::Kill);
-            self.mode = Mode::ConfirmAction;
+            self.confirm_or_execute(SessionAction::Kill);
         }
     }
 
+    /// Execute the pending action once the typed confirmation matches
+    pub fn confirm_typed(&mut self) {
+        if let Mode::TypeToConfirm {
+            ref expected,
+            ref input,
+        } = self.mode
+        {
+            if input.as_str() != expected {
+                return;
+            }
+        }
+        self.confirm_action();
+    }
+
     /// Confirm and execute the pending action
     pub fn confirm_action(&mut self) {
+        // Leave the prompt first, so a mode the action opens (e.g. the new
+        // session dialog after killing the last session) stays open
+        self.mode = Mode::Normal;
         if let Some(action) = self.pending_action.take() {
             self.execute_action(action);
         }
-        self.mode = Mode::Normal;
     }
 
-    /// Execute an action on the selected session
+    /// Execute an action on the selected session and record its outcome
     fn execute_action(&mut self, action: SessionAction) {
+        let Some(session_name) = self.selected_session().map(|s| s.name.clone()) else {
+            self.mode = Mode::Normal;
+            return;
+        };
+
+        // Macro steps and retries come here without confirm_or_execute
+        if !action.is_read_only() && self.refused_in_presentation(action.label()) {
+            self.macro_run = None;
+            self.mode = Mode::Normal;
+            return;
+        }
+
+        self.clear_messages();
+        self.retry = None;
+        self.run_action(action.clone());
+        self.record_history(action.label(), &session_name);
+
+        // Only a kill empties the list
+        if self.sessions.is_empty() {
+            self.after_last_kill();
+        }
+    }
+
+    /// Follow `on_last_kill` once the last session is gone
+    fn after_last_kill(&mut self) {
+        match self.config.on_last_kill {
+            OnLastKill::Stay => {}
+            OnLastKill::Quit => self.should_quit = true,
+            OnLastKill::NewSession => {
+                // Keep the kill's message unless the dialog sets its own
+                let message = self.message.take();
+                self.start_new_session();
+                if self.message.is_none() {
+                    self.message = message;
+                }
+            }
+        }
+    }
+
+    /// Run an action on the selected session
+    fn run_action(&mut self, action: SessionAction) {
         let Some(session) = self.selected_session() else {
             self.mode = Mode::Normal;
             return;
         };
         let session_name = session.name.clone();
         let switch_target = session.switch_target();
+        let branch = session.git_context().map(|g| g.branch.clone());
 
         match action {
             SessionAction::SwitchTo => {
@@ -423,10 +1581,62 @@ impl App {
                 }
                 self.mode = Mode::Normal;
             }
+            SessionAction::SwitchReadOnly => {
+                match Tmux::switch_to_session_read_only(&switch_target) {
+                    Ok(_) => self.should_quit = true,
+                    Err(e) => self.error = Some(format!("Failed to switch: {}", e)),
+                }
+                self.mode = Mode::Normal;
+            }
             SessionAction::Rename => {
                 self.mode = Mode::Rename {
                     old_name: session_name.clone(),
-                    new_name: session_name,
+                    new_name: TextInput::from(session_name),
+                };
+            }
+            SessionAction::RenameWindow => {
+                self.start_rename_window();
+            }
+            SessionAction::OpenRepoRoot => {
+                self.open_repo_root();
+            }
+            SessionAction::OpenEditor => {
+                self.open_editor();
+                self.mode = Mode::Normal;
+            }
+            SessionAction::NewScratch => {
+                self.new_scratch_session();
+                self.mode = Mode::Normal;
+            }
+            SessionAction::Compare => {
+                let others: Vec<String> = self
+                    .sessions
+                    .iter()
+                    .map(|s| s.name.clone())
+                    .filter(|name| *name != session_name)
+                    // Viewing our own session would nest the client inside itself
+                    .filter(|name| self.current_session.as_ref() != Some(name))
+                    .collect();
+                if others.is_empty() {
+                    self.error = Some("No other session to compare with".to_string());
+                    self.mode = Mode::Normal;
+                } else {
+                    self.mode = Mode::Compare {
+                        session: session_name,
+                        others,
+                        selected: 0,
+                    };
+                }
+            }
+            SessionAction::ColorTag => {
+                let current = self.color_tags.get(&session_name);
+                let selected = TagColor::ALL
+                    .iter()
+                    .position(|&color| Some(color) == current)
+                    .unwrap_or(TagColor::ALL.len());
+                self.mode = Mode::ColorTag {
+                    session: session_name,
+                    selected,
                 };
             }
             SessionAction::Stage => {
@@ -440,10 +1650,33 @@ impl App {
                 }
                 self.mode = Mode::Normal;
             }
+            SessionAction::Stash => {
+                let path = session.working_directory.clone();
+                match GitContext::stash(&path) {
+                    Ok(_) => {
+                        self.refresh_sessions();
+                        self.message = Some("Stashed changes".to_string());
+                    }
+                    Err(e) => self.error = Some(format!("Stash failed: {:#}", e)),
+                }
+                self.mode = Mode::Normal;
+            }
+            SessionAction::StashPop => {
+                let path = session.working_directory.clone();
+                match GitContext::stash_pop(&path) {
+                    Ok(_) => {
+                        self.refresh_sessions();
+                        self.message = Some("Restored stashed changes".to_string());
+                    }
+                    Err(e) => self.error = Some(format!("Stash pop failed: {:#}", e)),
+                }
+                self.mode = Mode::Normal;
+            }
+            SessionAction::Squash => {
+                self.start_squash();
+            }
             SessionAction::Commit => {
-                self.mode = Mode::Commit {
-                    message: String::new(),
-                };
+                self.start_commit();
             }
             SessionAction::Push => {
                 let path = session.working_directory.clone();
@@ -452,9 +1685,12 @@ impl App {
                         self.refresh_sessions();
                         self.message = Some("Pushed to remote".to_string());
                     }
-                    Err(e) => self.error = Some(format!("Push failed: {}", e)),
+                    Err(e) => self.remote_failure("Push", action, &session_name, e),
                 }
                 self.mode = Mode::Normal;
+                if self.error.is_none() {
+                    self.offer_draft_pull_request(&session_name);
+                }
             }
             SessionAction::PushSetUpstream => {
                 let path = session.working_directory.clone();
@@ -463,9 +1699,12 @@ impl App {
                         self.refresh_sessions();
                         self.message = Some("Pushed and set upstream".to_string());
                     }
-                    Err(e) => self.error = Some(format!("Push failed: {}", e)),
+                    Err(e) => self.remote_failure("Push", action, &session_name, e),
                 }
                 self.mode = Mode::Normal;
+                if self.error.is_none() {
+                    self.offer_draft_pull_request(&session_name);
+                }
             }
             SessionAction::Fetch => {
                 let path = session.working_directory.clone();
@@ -474,7 +1713,30 @@ impl App {
                         self.refresh_sessions();
                         self.message = Some("Fetched from remote".to_string());
                     }
-                    Err(e) => self.error = Some(format!("Fetch failed: {}", e)),
+                    Err(e) => self.remote_failure("Fetch", action, &session_name, e),
+                }
+                self.mode = Mode::Normal;
+            }
+            SessionAction::ContinueOperation | SessionAction::AbortOperation => {
+                let path = session.working_directory.clone();
+                let Some(state) = session.git_context().and_then(|g| g.state) else {
+                    self.error = Some("No operation in progress".to_string());
+                    self.mode = Mode::Normal;
+                    return;
+                };
+                let name = state.command();
+                let result = if action == SessionAction::AbortOperation {
+                    GitContext::abort_operation(&path, state).map(|_| format!("Aborted {}", name))
+                } else {
+                    GitContext::continue_operation(&path, state)
+                        .map(|_| format!("Continued {}", name))
+                };
+                match result {
+                    Ok(message) => {
+                        self.refresh_sessions();
+                        self.message = Some(message);
+                    }
+                    Err(e) => self.error = Some(format!("{}", e)),
                 }
                 self.mode = Mode::Normal;
             }
@@ -485,7 +1747,135 @@ impl App {
                         self.refresh_sessions();
                         self.message = Some("Pulled from remote".to_string());
                     }
-                    Err(e) => self.error = Some(format!("Pull failed: {}", e)),
+                    Err(e) => self.remote_failure("Pull", action, &session_name, e),
+                }
+                self.mode = Mode::Normal;
+            }
+            SessionAction::MergeBaseBranch => {
+                let path = session.working_directory.clone();
+                self.mode = Mode::Normal;
+                let Some(base_branch) = self.base_branch_for(&path) else {
+                    self.error = Some("Could not determine the base branch".to_string());
+                    return;
+                };
+                let author = self.config_for(&path).commit_author.unwrap_or_default();
+                let signature = match git::Identity::commit_signature(&path, &author) {
+                    Ok(signature) => signature,
+                    Err(e) => {
+                        self.error = Some(format!("Invalid commit_author: {}", e));
+                        return;
+                    }
+                };
+                let result = GitContext::merge_base_branch(&path, &base_branch, signature.as_ref());
+                if result.is_ok() {
+                    self.refresh_sessions();
+                }
+                match result {
+                    Ok(MergeOutcome::UpToDate) => {
+                        self.message = Some(format!("Already up to date with '{}'", base_branch));
+                    }
+                    Ok(MergeOutcome::FastForward) => {
+                        self.message = Some(format!("Fast-forwarded to '{}'", base_branch));
+                    }
+                    Ok(MergeOutcome::Merged) => {
+                        self.message = Some(format!("Merged '{}'", base_branch));
+                    }
+                    // The repository stays mid-merge, so Continue/Abort are offered
+                    Ok(MergeOutcome::Conflicts(count)) => {
+                        self.error = Some(format!(
+                            "Merging '{}' left {} conflicted file(s): resolve them in the session, stage, then continue",
+                            base_branch, count
+                        ));
+                    }
+                    Err(e) => self.remote_failure("Merge", action, &session_name, e),
+                }
+            }
+            SessionAction::StageFiles => {
+                let path = session.working_directory.clone();
+                match GitContext::changed_files(&path) {
+                    Ok(files) => {
+                        self.mode = Mode::StageFiles {
+                            path,
+                            files,
+                            selected: 0,
+                            confirm_discard: false,
+                        }
+                    }
+                    Err(e) => {
+                        self.error = Some(format!("Failed to list changed files: {}", e));
+                        self.mode = Mode::Normal;
+                    }
+                }
+            }
+            SessionAction::ViewDiff => {
+                let path = session.working_directory.clone();
+                match GitContext::working_diff(&path) {
+                    Ok(diff) => {
+                        self.mode = Mode::Diff {
+                            title: format!("Uncommitted changes in {}", session_name),
+                            lines: diff_lines(&diff),
+                            scroll: 0,
+                        };
+                    }
+                    Err(e) => {
+                        self.error = Some(format!("Failed to get diff: {}", e));
+                        self.mode = Mode::Normal;
+                    }
+                }
+            }
+            SessionAction::ViewTags => {
+                let path = session.working_directory.clone();
+                match GitContext::list_tags(&path) {
+                    Ok(tags) => self.mode = Mode::Tags { tags, scroll: 0 },
+                    Err(e) => {
+                        self.error = Some(format!("Failed to list tags: {}", e));
+                        self.mode = Mode::Normal;
+                    }
+                }
+            }
+            SessionAction::PushTags => {
+                let path = session.working_directory.clone();
+                match GitContext::unpushed_tags(&path) {
+                    Ok(tags) if tags.is_empty() => {
+                        self.message = Some("Every tag is already on the remote".to_string());
+                    }
+                    Ok(tags) => match GitContext::push_tags(&path, &tags) {
+                        Ok(_) => {
+                            let names = truncate_display(&tags.join(", "), SUBJECT_COLUMNS);
+                            self.message = Some(format!("Pushed {} tag(s): {}", tags.len(), names));
+                        }
+                        Err(e) => self.remote_failure("Push", action, &session_name, e),
+                    },
+                    Err(e) => self.remote_failure("Push", action, &session_name, e),
+                }
+                self.mode = Mode::Normal;
+            }
+            SessionAction::CreateTag => {
+                self.mode = Mode::CreateTag {
+                    name: TextInput::new(),
+                    message: TextInput::new(),
+                    push: false,
+                    field: CreateTagField::Name,
+                };
+            }
+            SessionAction::CopyPath => {
+                let path = expand_path(&session.working_directory.to_string_lossy());
+                match crate::clipboard::copy(&path.to_string_lossy()) {
+                    Ok(_) => self.message = Some("Copied path".to_string()),
+                    Err(e) => self.error = Some(format!("Failed to copy: {}", e)),
+                }
+                self.mode = Mode::Normal;
+            }
+            SessionAction::CopySha | SessionAction::CopyBranch => {
+                let path = session.working_directory.clone();
+                let value = if action == SessionAction::CopySha {
+                    GitContext::head_sha(&path)
+                } else {
+                    GitContext::head_branch(&path)
+                };
+                match value.and_then(|value| crate::clipboard::copy(&value).map(|_| value)) {
+                    Ok(value) => self.message = Some(format!("Copied: {}", value)),
+                    Err(e) => self.error = Some(format!("Failed to copy: {}", e)),
                 }
                 self.mode = Mode::Normal;
             }
@@ -494,7 +1884,7 @@ impl App {
             }
             SessionAction::ViewPullRequest => {
                 let path = session.working_directory.clone();
-                match git::view_pull_request(&path) {
+                match git::provider(&path).and_then(|p| p.view_pull_request(&path)) {
                     Ok(_) => {
                         self.message = Some("Opened PR in browser".to_string());
                     }
@@ -502,9 +1892,50 @@ impl App {
                 }
                 self.mode = Mode::Normal;
             }
+            SessionAction::ViewPullRequestDiff => {
+                let path = session.working_directory.clone();
+                let title = match self.pr_info {
+                    Some(ref info) => format!("PR #{} diff", info.number),
+                    None => "PR diff".to_string(),
+                };
+                match git::provider(&path).and_then(|p| p.pull_request_diff(&path)) {
+                    Ok(diff) => {
+                        self.mode = Mode::Diff {
+                            title,
+                            lines: diff_lines(&diff),
+                            scroll: 0,
+                        };
+                    }
+                    Err(e) => {
+                        self.error = Some(format!("Failed to get PR diff: {}", e));
+                        self.mode = Mode::Normal;
+                    }
+                }
+            }
+            SessionAction::ViewPullRequestComments => {
+                let path = session.working_directory.clone();
+                let title = match self.pr_info {
+                    Some(ref info) => format!("PR #{} comments", info.number),
+                    None => "PR comments".to_string(),
+                };
+                match git::provider(&path).and_then(|p| p.pull_request_comments(&path)) {
+                    Ok(comments) => {
+                        self.mode = Mode::PrComments {
+                            title,
+                            lines: comment_lines(&comments),
+                            scroll: 0,
+                        };
+                    }
+                    Err(e) => {
+                        self.error = Some(format!("Failed to get PR comments: {}", e));
+                        self.mode = Mode::Normal;
+                    }
+                }
+            }
             SessionAction::ClosePullRequest => {
                 let path = session.working_directory.clone();
-                match git::close_pull_request(&path) {
+                self.pr_status.forget(&path);
+                match git::provider(&path).and_then(|p| p.close_pull_request(&path)) {
                     Ok(_) => {
                         self.message = Some("Closed pull request".to_string());
                     }
@@ -514,8 +1945,18 @@ impl App {
             }
             SessionAction::MergePullRequest => {
                 let path = session.working_directory.clone();
-                match git::merge_pull_request(&path, false) {
+                let strategy = std::mem::take(&mut self.merge_strategy);
+                self.pr_status.forget(&path);
+                match git::provider(&path)
+                    .and_then(|p| p.merge_pull_request(&path, strategy, false))
+                {
                     Ok(_) => {
+                        self.run_hook(
+                            HookEvent::MergePullRequest,
+                            &session_name,
+                            &path,
+                            branch.as_deref(),
+                        );
                         self.refresh_sessions();
                         self.message = Some("Merged pull request".to_string());
                     }
@@ -526,14 +1967,24 @@ impl App {
             SessionAction::MergePullRequestAndClose => {
                 let path = session.working_directory.clone();
                 let is_worktree = session
-                    .git_context
-                    .as_ref()
+                    .git_context()
                     .map(|g| g.is_worktree)
                     .unwrap_or(false);
 
                 // Step 1: Merge PR
-                match git::merge_pull_request(&path, false) {
+                let strategy = std::mem::take(&mut self.merge_strategy);
+                self.pr_status.forget(&path);
+                match git::provider(&path)
+                    .and_then(|p| p.merge_pull_request(&path, strategy, false))
+                {
                     Ok(_) => {
+                        self.run_hook(
+                            HookEvent::MergePullRequest,
+                            &session_name,
+                            &path,
+                            branch.as_deref(),
+                        );
+
                         // Step 2: Delete worktree if applicable
                         if is_worktree {
                             if let Err(e) = GitContext::delete_worktree(&path, true) {
@@ -568,10 +2019,17 @@ impl App {
                 self.mode = Mode::Normal;
             }
             SessionAction::Kill => {
+                // Without a snapshot the kill still goes ahead, just without undo
+                let snapshot = Tmux::snapshot_session(&session_name).ok();
                 match Tmux::kill_session(&session_name) {
                     Ok(_) => {
                         self.refresh_sessions();
-                        self.message = Some(format!("Killed session '{}'", session_name));
+                        self.message = Some(if snapshot.is_some() {
+                            format!("Killed session '{}' (u to recreate)", session_name)
+                        } else {
+                            format!("Killed session '{}'", session_name)
+                        });
+                        self.last_killed = snapshot;
                     }
                     Err(e) => self.error = Some(format!("Failed to kill: {}", e)),
                 }
@@ -610,30 +2068,112 @@ impl App {
         }
     }
 
-    // =========================================================================
-    // Dialog flows: Rename
-    // =========================================================================
-
-    /// Start the rename flow
-    pub fn start_rename(&mut self) {
-        self.clear_messages();
-        if let Some(session) = self.selected_session() {
-            self.mode = Mode::Rename {
-                old_name: session.name.clone(),
-                new_name: session.name.clone(),
-            };
+    /// Report a failed push, fetch or pull. Authentication failures keep the
+    /// action so `t` can retry it, e.g. after `ssh-add`.
+    fn remote_failure(
+        &mut self,
+        operation: &str,
+        action: SessionAction,
+        session_name: &str,
+        error: anyhow::Error,
+    ) {
+        if git::is_auth_error(&error) {
+            self.error = Some(format!(
+                "{} failed: {:#}. Press t to retry once credentials are available",
+                operation, error
+            ));
+            self.retry = Some((action, session_name.to_string()));
+        } else {
+            self.error = Some(format!("{} failed: {}", operation, error));
         }
     }
 
-    /// Confirm and execute session rename
-    pub fn confirm_rename(&mut self) {
-        if let Mode::Rename {
-            ref old_name,
-            ref new_name,
-        } = self.mode
-        {
-            let old = old_name.clone();
-            let new = new_name.clone();
+    /// Retry the remote action that last failed to authenticate, on the
+    /// session it was run on. Credentials are looked up afresh, so an SSH
+    /// agent started since then is picked up.
+    pub fn retry_failed_action(&mut self) {
+        let Some((action, session_name)) = self.retry.take() else {
+            self.message = Some("Nothing to retry".to_string());
+            return;
+        };
+        let Some(index) = self
+            .filtered_sessions()
+            .iter()
+            .position(|s| s.name == session_name)
+        else {
+            self.error = Some(format!("Session '{}' is no longer listed", session_name));
+            return;
+        };
+        self.selected = index;
+        self.update_preview();
+        self.execute_action(action);
+    }
+
+    /// Recreate the last killed session from its snapshot.
+    ///
+    /// Windows, pane directories and layout return and pane commands are
+    /// re-run, but the old processes' state is gone. The snapshot is used
+    /// up unless a session with its name exists again.
+    pub fn recreate_killed_session(&mut self) {
+        self.clear_messages();
+        if self.refused_in_presentation("Recreate killed session") {
+            return;
+        }
+        let Some(snapshot) = self.last_killed.take() else {
+            self.error = Some("No killed session to recreate".to_string());
+            return;
+        };
+
+        if Tmux::session_exists(&snapshot.name) {
+            self.error = Some(format!("Session '{}' already exists", snapshot.name));
+            self.last_killed = Some(snapshot);
+            return;
+        }
+
+        let path = snapshot
+            .windows
+            .first()
+            .map(|w| w.panes[0].path.clone())
+            .unwrap_or_default();
+        let claude_command = self.config_for(&path).startup_command;
+
+        match Tmux::restore_session(&snapshot, &claude_command) {
+            Ok(_) => {
+                self.refresh_sessions();
+                self.message = Some(format!("Recreated session '{}'", snapshot.name));
+            }
+            Err(e) => self.error = Some(format!("Failed to recreate session: {}", e)),
+        }
+        self.record_history("Recreate killed session", &snapshot.name);
+    }
+
+    // =========================================================================
+    // Dialog flows: Rename
+    // =========================================================================
+
+    /// Start the rename flow
+    pub fn start_rename(&mut self) {
+        self.clear_messages();
+        if self.refused_in_presentation("Rename session") {
+            return;
+        }
+        if let Some(session) = self.selected_session() {
+            self.mode = Mode::Rename {
+                old_name: session.name.clone(),
+                new_name: TextInput::from(session.name.clone()),
+            };
+        }
+    }
+
+    /// Confirm and execute session rename
+    pub fn confirm_rename(&mut self) {
+        if let Mode::Rename {
+            ref old_name,
+            ref new_name,
+        } = self.mode
+        {
+            let old = old_name.clone();
+            let new = new_name.as_str().to_string();
 
             if old == new {
                 self.mode = Mode::Normal;
@@ -644,23 +2184,268 @@ impl App {
                 Ok(_) => {
                     self.refresh_sessions();
                     self.message = Some(format!("Renamed '{}' to '{}'", old, new));
+                    if self.color_tags.rename(&old, &new) {
+                        if let Err(e) = self.color_tags.save() {
+                            self.error = Some(format!("{:#}", e));
+                        }
+                    }
                 }
                 Err(e) => {
                     self.error = Some(format!("Failed to rename: {}", e));
                 }
             }
+            self.record_history(SessionAction::Rename.label(), &old);
         }
         self.mode = Mode::Normal;
     }
 
+    /// Move the highlight in the color tag picker, wrapping around
+    pub fn select_color_tag(&mut self, delta: isize) {
+        if let Mode::ColorTag {
+            ref mut selected, ..
+        } = self.mode
+        {
+            // The colors, then "no tag"
+            let count = TagColor::ALL.len() as isize + 1;
+            *selected = (*selected as isize + delta).rem_euclid(count) as usize;
+        }
+    }
+
+    /// Apply the highlighted color tag and save the tags
+    pub fn confirm_color_tag(&mut self) {
+        let Mode::ColorTag {
+            ref session,
+            selected,
+        } = self.mode
+        else {
+            return;
+        };
+        let session = session.clone();
+        let color = TagColor::ALL.get(selected).copied();
+
+        self.color_tags.set(&session, color);
+        match self.color_tags.save() {
+            Ok(_) => {
+                self.message = Some(match color {
+                    Some(color) => format!("Tagged '{}' {}", session, color.name()),
+                    None => format!("Removed the color tag of '{}'", session),
+                });
+            }
+            Err(e) => self.error = Some(format!("Failed to save color tags: {:#}", e)),
+        }
+        self.record_history(SessionAction::ColorTag.label(), &session);
+        self.mode = Mode::Normal;
+    }
+
+    /// Move the highlight in the compare picker, wrapping around
+    pub fn select_compare(&mut self, delta: isize) {
+        if let Mode::Compare {
+            ref others,
+            ref mut selected,
+            ..
+        } = self.mode
+        {
+            let count = others.len() as isize;
+            *selected = (*selected as isize + delta).rem_euclid(count) as usize;
+        }
+    }
+
+    /// Open a window with the session and the highlighted one side by side
+    pub fn confirm_compare(&mut self) {
+        let Mode::Compare {
+            ref session,
+            ref others,
+            selected,
+        } = self.mode
+        else {
+            return;
+        };
+        let session = session.clone();
+        let Some(other) = others.get(selected).cloned() else {
+            return;
+        };
+
+        match Tmux::open_side_by_side(&session, &other) {
+            Ok(_) => {
+                self.message = Some(format!("Opened '{}' beside '{}'", session, other));
+                self.should_quit = true;
+            }
+            Err(e) => self.error = Some(format!("Compare failed: {:#}", e)),
+        }
+        self.record_history(SessionAction::Compare.label(), &session);
+        self.mode = Mode::Normal;
+    }
+
+    /// Start renaming the selected session's focused window
+    pub fn start_rename_window(&mut self) {
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        let windows = session.windows();
+        if windows.is_empty() {
+            self.error = Some("Session has no windows".to_string());
+            self.mode = Mode::Normal;
+            return;
+        }
+        let selected = session
+            .focused_window_index()
+            .and_then(|index| windows.iter().position(|(i, _)| i == index))
+            .unwrap_or(0);
+
+        self.mode = Mode::RenameWindow {
+            session: session.name.clone(),
+            new_name: TextInput::from(windows[selected].1.clone()),
+            windows,
+            selected,
+        };
+    }
+
+    /// Move the rename to the next (or previous) window, resetting the name
+    pub fn cycle_rename_window(&mut self, forward: bool) {
+        if let Mode::RenameWindow {
+            ref windows,
+            ref mut selected,
+            ref mut new_name,
+            ..
+        } = self.mode
+        {
+            let count = windows.len();
+            *selected = if forward {
+                (*selected + 1) % count
+            } else {
+                (*selected + count - 1) % count
+            };
+            *new_name = TextInput::from(windows[*selected].1.clone());
+        }
+    }
+
+    /// Confirm and execute window rename
+    pub fn confirm_rename_window(&mut self) {
+        if let Mode::RenameWindow {
+            ref session,
+            ref windows,
+            selected,
+            ref new_name,
+        } = self.mode
+        {
+            let session = session.clone();
+            let (index, old) = windows[selected].clone();
+            let new = new_name.as_str().trim().to_string();
+
+            if new.is_empty() {
+                self.error = Some("Window name cannot be empty".to_string());
+                self.mode = Mode::Normal;
+                return;
+            }
+            if old == new {
+                self.mode = Mode::Normal;
+                return;
+            }
+
+            match Tmux::rename_window(&format!("{}:{}", session, index), &new) {
+                Ok(_) => {
+                    self.refresh_sessions();
+                    self.message = Some(format!("Renamed window '{}' to '{}'", old, new));
+                }
+                Err(e) => {
+                    self.error = Some(format!("Failed to rename window: {}", e));
+                }
+            }
+            self.record_history(SessionAction::RenameWindow.label(), &session);
+        }
+        self.mode = Mode::Normal;
+    }
+
+    // =========================================================================
+    // Dialog flows: External editor
+    // =========================================================================
+
+    /// Ask to compose the commit message or PR body in `$EDITOR`, seeded
+    /// with what was typed so far. Other dialogs have no long text field.
+    pub fn open_external_editor(&mut self) {
+        self.external_edit = match self.mode {
+            Mode::Commit {
+                ref message,
+                ref mut field,
+                ..
+            } => {
+                *field = CommitField::Message;
+                Some(message.as_str().to_string())
+            }
+            Mode::CreatePullRequest {
+                ref body,
+                ref mut field,
+                ..
+            } => {
+                *field = CreatePullRequestField::Body;
+                Some(body.as_str().to_string())
+            }
+            _ => None,
+        };
+    }
+
+    /// Put the text saved in `$EDITOR` back into the dialog's field
+    pub fn finish_external_edit(&mut self, result: Result<String>) {
+        let text = match result {
+            Ok(text) => text,
+            Err(e) => {
+                self.error = Some(format!("Editor failed: {:#}", e));
+                return;
+            }
+        };
+
+        let input = match self.mode {
+            Mode::Commit {
+                ref mut message, ..
+            } => message,
+            Mode::CreatePullRequest { ref mut body, .. } => body,
+            _ => return,
+        };
+        if input.as_str() != text {
+            input.set(text);
+            self.mode.set_edits(Edits::Unsaved);
+        }
+    }
+
     // =========================================================================
     // Dialog flows: Commit
     // =========================================================================
 
+    /// Start the commit flow, pre-filling the author with the configured
+    /// identity, else the one from git config
+    pub fn start_commit(&mut self) {
+        let Some(path) = self.selected_session().map(|s| s.working_directory.clone()) else {
+            return;
+        };
+        let author = self
+            .config_for(&path)
+            .commit_author
+            .or_else(|| git::Identity::from_git_config(&path).map(|i| i.to_string()))
+            .unwrap_or_default();
+
+        self.mode = Mode::Commit {
+            message: TextInput::new(),
+            author: TextInput::from(author),
+            field: CommitField::Message,
+            edits: Edits::Pristine,
+        };
+    }
+
     /// Confirm and execute the commit
     pub fn confirm_commit(&mut self) {
-        if let Mode::Commit { ref message } = self.mode {
-            if message.trim().is_empty() {
+        // Checked here too, as macros with a commit message commit directly
+        if self.refused_in_presentation(SessionAction::Commit.label()) {
+            self.macro_run = None;
+            self.mode = Mode::Normal;
+            return;
+        }
+        if let Mode::Commit {
+            ref message,
+            ref author,
+            ..
+        } = self.mode
+        {
+            if message.as_str().trim().is_empty() {
                 self.error = Some("Commit message cannot be empty".to_string());
                 self.mode = Mode::Normal;
                 return;
@@ -668,17 +2453,415 @@ impl App {
 
             if let Some(session) = self.selected_session() {
                 let path = session.working_directory.clone();
-                let msg = message.clone();
-                match GitContext::commit(&path, &msg) {
+                let session_name = session.name.clone();
+                let branch = session.git_context().map(|g| g.branch.clone());
+                let msg = message.as_str().to_string();
+
+                let signature = match git::Identity::commit_signature(&path, author.as_str()) {
+                    Ok(signature) => signature,
+                    Err(e) => {
+                        // Stay in the dialog so the author can be fixed
+                        self.error = Some(format!("Invalid commit author: {}", e));
+                        return;
+                    }
+                };
+
+                let staged = match GitContext::has_staged_changes(&path) {
+                    Ok(staged) => staged,
+                    Err(e) => {
+                        self.error = Some(format!("Commit failed: {}", e));
+                        self.mode = Mode::Normal;
+                        return;
+                    }
+                };
+
+                // Nothing staged: stage everything first if configured
+                let auto_staged = !staged
+                    && self.config.auto_stage_on_commit
+                    && GitContext::detect(&path).is_some_and(|g| g.has_unstaged || g.has_untracked);
+                if auto_staged {
+                    if let Err(e) = GitContext::stage_all(&path) {
+                        self.error = Some(format!("Failed to stage changes: {}", e));
+                        self.mode = Mode::Normal;
+                        return;
+                    }
+                }
+
+                if !staged && !auto_staged && !self.config.allow_empty_commits {
+                    self.error = Some("nothing staged to commit".to_string());
+                    self.mode = Mode::Normal;
+                    return;
+                }
+
+                match GitContext::commit(&path, &msg, signature.as_ref()) {
                     Ok(_) => {
+                        self.run_hook(HookEvent::Commit, &session_name, &path, branch.as_deref());
                         self.refresh_sessions();
-                        self.message = Some("Committed changes".to_string());
+                        let subject =
+                            truncate_display(msg.lines().next().unwrap_or(""), SUBJECT_COLUMNS);
+                        self.message = Some(if auto_staged {
+                            format!(
+                                "Committed \"{}\" (nothing was staged, so staged all first)",
+                                subject
+                            )
+                        } else {
+                            format!("Committed \"{}\"", subject)
+                        });
                     }
                     Err(e) => self.error = Some(format!("Commit failed: {}", e)),
                 }
+                self.record_history(SessionAction::Commit.label(), &session_name);
+            }
+        }
+        self.mode = Mode::Normal;
+    }
+
+    // =========================================================================
+    // Dialog flows: Squash
+    // =========================================================================
+
+    /// Start the squash flow, pre-filling the message with the oldest
+    /// commit's summary
+    pub fn start_squash(&mut self) {
+        let Some(path) = self.selected_session().map(|s| s.working_directory.clone()) else {
+            return;
+        };
+        let Some(base_branch) = self.base_branch_for(&path) else {
+            self.error = Some("Cannot determine the base branch".to_string());
+            self.mode = Mode::Normal;
+            return;
+        };
+
+        match GitContext::commits_since_base(&path, &base_branch) {
+            Ok(commits) => {
+                self.mode = Mode::Squash {
+                    base_branch,
+                    count: commits.len(),
+                    message: TextInput::from(commits.first().cloned().unwrap_or_default()),
+                };
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to list commits: {}", e));
+                self.mode = Mode::Normal;
+            }
+        }
+    }
+
+    /// Confirm and squash the branch into a single commit
+    pub fn confirm_squash(&mut self) {
+        let (base_branch, message) = if let Mode::Squash {
+            ref base_branch,
+            ref message,
+            ..
+        } = self.mode
+        {
+            (base_branch.clone(), message.as_str().to_string())
+        } else {
+            return;
+        };
+        self.mode = Mode::Normal;
+
+        if message.trim().is_empty() {
+            self.error = Some("Commit message cannot be empty".to_string());
+            return;
+        }
+
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        let path = session.working_directory.clone();
+        let session_name = session.name.clone();
+
+        let author = self.config_for(&path).commit_author.unwrap_or_default();
+        let signature = match git::Identity::commit_signature(&path, &author) {
+            Ok(signature) => signature,
+            Err(e) => {
+                self.error = Some(format!("Invalid commit_author: {}", e));
+                return;
+            }
+        };
+
+        match GitContext::squash(&path, &base_branch, &message, signature.as_ref()) {
+            Ok(_) => {
+                self.refresh_sessions();
+                self.message = Some(format!("Squashed commits since '{}'", base_branch));
+            }
+            Err(e) => self.error = Some(format!("Squash failed: {:#}", e)),
+        }
+        self.record_history(SessionAction::Squash.label(), &session_name);
+    }
+
+    // =========================================================================
+    // Dialog flows: Stage Files
+    // =========================================================================
+
+    /// Move the highlight in the file picker by the given number of rows
+    pub fn move_stage_files_selection(&mut self, delta: isize) {
+        if let Mode::StageFiles {
+            ref files,
+            ref mut selected,
+            ref mut confirm_discard,
+            ..
+        } = self.mode
+        {
+            let max = files.len().saturating_sub(1);
+            *selected = selected.saturating_add_signed(delta).min(max);
+            *confirm_discard = false;
+        }
+    }
+
+    /// Stage the highlighted file, or unstage it if it is fully staged
+    pub fn toggle_stage_highlighted_file(&mut self) {
+        let Some((path, file)) = self.highlighted_changed_file() else {
+            return;
+        };
+
+        let result = if file.unstaged {
+            GitContext::stage_file(&path, &file.path)
+        } else {
+            GitContext::unstage_file(&path, &file.path)
+        };
+
+        match result {
+            Ok(_) => self.reload_changed_files(),
+            Err(e) => self.error = Some(format!("Stage failed: {}", e)),
+        }
+    }
+
+    /// Ask for confirmation before discarding the highlighted file
+    pub fn start_discard_highlighted_file(&mut self) {
+        if let Mode::StageFiles {
+            ref files,
+            ref mut confirm_discard,
+            ..
+        } = self.mode
+        {
+            *confirm_discard = !files.is_empty();
+        }
+    }
+
+    /// Discard all changes to the highlighted file
+    pub fn confirm_discard_highlighted_file(&mut self) {
+        let Some((path, file)) = self.highlighted_changed_file() else {
+            return;
+        };
+
+        match GitContext::discard_file(&path, &file.path) {
+            Ok(_) => {
+                self.message = Some(format!("Discarded changes to {}", file.path));
+                self.reload_changed_files();
+            }
+            Err(e) => self.error = Some(format!("Discard failed: {}", e)),
+        }
+        if let Some(session_name) = self.selected_session().map(|s| s.name.clone()) {
+            self.record_history("Discard file changes", &session_name);
+        }
+    }
+
+    /// Cancel a pending discard without leaving the file picker
+    pub fn cancel_discard(&mut self) {
+        if let Mode::StageFiles {
+            ref mut confirm_discard,
+            ..
+        } = self.mode
+        {
+            *confirm_discard = false;
+        }
+    }
+
+    /// Working directory and highlighted file of the file picker
+    fn highlighted_changed_file(&self) -> Option<(PathBuf, git::ChangedFile)> {
+        if let Mode::StageFiles {
+            ref path,
+            ref files,
+            selected,
+            ..
+        } = self.mode
+        {
+            files.get(selected).map(|file| (path.clone(), file.clone()))
+        } else {
+            None
+        }
+    }
+
+    /// Re-read the changed files in place, keeping the highlight in range
+    fn reload_changed_files(&mut self) {
+        let Mode::StageFiles { ref path, .. } = self.mode else {
+            return;
+        };
+
+        match GitContext::changed_files(path) {
+            Ok(new_files) => {
+                if let Mode::StageFiles {
+                    ref mut files,
+                    ref mut selected,
+                    ref mut confirm_discard,
+                    ..
+                } = self.mode
+                {
+                    *selected = (*selected).min(new_files.len().saturating_sub(1));
+                    *files = new_files;
+                    *confirm_discard = false;
+                }
             }
+            Err(e) => self.error = Some(format!("Failed to list changed files: {}", e)),
         }
+        self.refresh_sessions();
+    }
+
+    // =========================================================================
+    // Dialog flows: Tags
+    // =========================================================================
+
+    /// Confirm and create the tag, pushing it if requested
+    pub fn confirm_create_tag(&mut self) {
+        let (name, message, push) = if let Mode::CreateTag {
+            ref name,
+            ref message,
+            push,
+            ..
+        } = self.mode
+        {
+            (
+                name.as_str().to_string(),
+                message.as_str().to_string(),
+                push,
+            )
+        } else {
+            return;
+        };
         self.mode = Mode::Normal;
+
+        if !git::is_valid_tag_name(&name) {
+            self.error = Some(format!("'{}' is not a valid tag name", name));
+            return;
+        }
+
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        let path = session.working_directory.clone();
+        let session_name = session.name.clone();
+
+        // Annotated tags need a message; default to the tag name
+        let message = if message.trim().is_empty() {
+            name.clone()
+        } else {
+            message
+        };
+
+        let author = self.config_for(&path).commit_author.unwrap_or_default();
+        let signature = match git::Identity::commit_signature(&path, &author) {
+            Ok(signature) => signature,
+            Err(e) => {
+                self.error = Some(format!("Invalid commit_author: {}", e));
+                return;
+            }
+        };
+
+        if let Err(e) = GitContext::create_tag(&path, &name, &message, signature.as_ref()) {
+            self.error = Some(format!("Tag failed: {}", e));
+            self.record_history(SessionAction::CreateTag.label(), &session_name);
+            return;
+        }
+
+        if push {
+            match GitContext::push_tag(&path, &name) {
+                Ok(_) => self.message = Some(format!("Created and pushed tag '{}'", name)),
+                Err(e) => {
+                    self.error = Some(format!("Tag '{}' created but push failed: {}", name, e))
+                }
+            }
+        } else {
+            self.message = Some(format!("Created tag '{}'", name));
+        }
+        self.record_history(SessionAction::CreateTag.label(), &session_name);
+    }
+
+    /// Scroll the tag list by the given number of rows
+    pub fn scroll_tags(&mut self, delta: isize) {
+        if let Mode::Tags {
+            ref tags,
+            ref mut scroll,
+        } = self.mode
+        {
+            let max = tags.len().saturating_sub(1);
+            *scroll = scroll.saturating_add_signed(delta).min(max);
+        }
+    }
+
+    /// Scroll the diff or pull request comments viewer by the given
+    /// number of lines
+    pub fn scroll_diff(&mut self, delta: isize) {
+        let (len, scroll) = match self.mode {
+            Mode::Diff {
+                ref lines,
+                ref mut scroll,
+                ..
+            } => (lines.len(), scroll),
+            Mode::PrComments {
+                ref lines,
+                ref mut scroll,
+                ..
+            } => (lines.len(), scroll),
+            _ => return,
+        };
+        let max = len.saturating_sub(1);
+        *scroll = scroll.saturating_add_signed(delta).min(max);
+    }
+
+    /// Show how status detection sees each pane of the selected session
+    pub fn show_detect_debug(&mut self) {
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        let detection = &self.config.detection;
+
+        let panes = session
+            .panes
+            .iter()
+            .map(|pane| {
+                let (content, notes) =
+                    match Tmux::capture_pane(&pane.id, detection.status_lines, true) {
+                        Ok(content) => {
+                            let notes = explain_status(&content, detection);
+                            (content, notes)
+                        }
+                        Err(e) => (String::new(), vec![format!("capture failed: {}", e)]),
+                    };
+                PaneDebug {
+                    id: pane.id.clone(),
+                    command: pane.current_command.clone(),
+                    claude: pane.current_command.contains("claude"),
+                    capture: content
+                        .lines()
+                        .map(|line| line.replace('\t', "    "))
+                        .collect(),
+                    status: detect_status(&content, detection),
+                    notes,
+                }
+            })
+            .collect();
+
+        self.mode = Mode::DetectDebug {
+            session: session.name.clone(),
+            panes,
+            scroll: 0,
+        };
+    }
+
+    /// Scroll the detection debug overlay by the given number of lines
+    pub fn scroll_detect_debug(&mut self, delta: isize) {
+        if let Mode::DetectDebug {
+            ref panes,
+            ref mut scroll,
+            ..
+        } = self.mode
+        {
+            let total: usize = panes.iter().map(PaneDebug::height).sum();
+            let max = total.saturating_sub(1);
+            *scroll = scroll.saturating_add_signed(delta).min(max);
+        }
     }
 
     // =========================================================================
@@ -688,49 +2871,271 @@ impl App {
     /// Start the new session flow
     pub fn start_new_session(&mut self) {
         self.clear_messages();
-        // Default to current directory
-        let default_path = std::env::current_dir()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| "~".to_string());
+        if self.refused_in_presentation("New session") {
+            return;
+        }
+        let default_path = self.default_session_path();
 
         // Get initial path suggestions
         let completion = crate::completion::complete_path(&default_path);
 
         self.mode = Mode::NewSession {
-            name: String::new(),
-            path: default_path,
+            name: TextInput::new(),
+            path: TextInput::from(default_path),
+            url: TextInput::new(),
             field: NewSessionField::Name,
             path_suggestions: completion.suggestions,
             path_selected: None,
         };
     }
 
+    /// Directory a new session starts in: the current directory, or when
+    /// that is gone or unreadable the selected session's directory, else
+    /// home. A fallback is explained in a message.
+    fn default_session_path(&mut self) -> String {
+        if let Ok(cwd) = std::env::current_dir() {
+            if cwd.is_dir() {
+                return cwd.to_string_lossy().to_string();
+            }
+        }
+
+        let selected = self
+            .selected_session()
+            .map(|s| s.working_directory.clone())
+            .filter(|dir| dir.is_dir());
+        match selected {
+            Some(dir) => {
+                self.message = Some(
+                    "Current directory unavailable; using the selected session's directory"
+                        .to_string(),
+                );
+                dir.to_string_lossy().to_string()
+            }
+            None => {
+                self.message =
+                    Some("Current directory unavailable; using the home directory".to_string());
+                "~".to_string()
+            }
+        }
+    }
+
     /// Create the new session
     pub fn confirm_new_session(&mut self, start_claude: bool) {
         if let Mode::NewSession {
-            ref name, ref path, ..
+            ref name,
+            ref path,
+            ref url,
+            ..
         } = self.mode
         {
+            if !url.as_str().trim().is_empty() {
+                let (url, name) = (url.as_str().trim().to_string(), name.as_str().to_string());
+                let path = path.as_str().to_string();
+                self.start_clone(url, name, &path, start_claude);
+                self.mode = Mode::Normal;
+                return;
+            }
             if name.is_empty() {
                 self.error = Some("Session name cannot be empty".to_string());
                 self.mode = Mode::Normal;
                 return;
             }
 
-            let session_name = name.clone();
-            let session_path = expand_path(path);
+            let session_name = name.as_str().to_string();
+            let session_path = canonical_path(&expand_path(path.as_str()));
 
-            match Tmux::new_session(&session_name, &session_path, start_claude) {
-                Ok(_) => {
-                    self.refresh_sessions();
-                    self.message = Some(format!("Created session '{}'", session_name));
+            self.create_session(&session_name, &session_path, start_claude);
+            self.record_history("New session", &session_name);
+        }
+        self.mode = Mode::Normal;
+    }
+
+    /// Clone `url` in the background and create the session in the clone
+    /// once it is done (see `tick_clone`). Like `git clone`, an existing
+    /// directory at `path` gets the clone in a subdirectory named after the
+    /// repository. Without a `name`, the session is named after it too.
+    fn start_clone(&mut self, url: String, name: String, path: &str, start_claude: bool) {
+        if self.clone_job.is_some() {
+            self.error = Some("A clone is already running".to_string());
+            return;
+        }
+        let Some(repo_name) = git::repo_name_from_url(&url) else {
+            self.error = Some(format!("Cannot tell the repository name from '{}'", url));
+            return;
+        };
+
+        let path = canonical_path(&expand_path(path));
+        let dest = if path.is_dir() {
+            path.join(&repo_name)
+        } else {
+            path
+        };
+        let occupied = dest.is_file() || dest.read_dir().is_ok_and(|mut d| d.next().is_some());
+        if occupied {
+            self.error = Some(format!("'{}' already exists", dest.display()));
+            return;
+        }
+
+        let session_name = if name.is_empty() {
+            sanitize_for_session_name(&repo_name)
+        } else {
+            name
+        };
+        if Tmux::session_exists(&session_name) {
+            self.error = Some(format!("Session '{}' already exists", session_name));
+            return;
+        }
+
+        self.clone_job = Some(CloneJob::start(url, dest, session_name, start_claude));
+    }
+
+    /// Create the session of a finished clone, or report why it failed.
+    ///
+    /// Called on every main-loop iteration; the status bar shows progress
+    /// meanwhile.
+    pub fn tick_clone(&mut self) {
+        let Some(result) = self.clone_job.as_mut().and_then(|job| job.poll()) else {
+            return;
+        };
+        let Some(job) = self.clone_job.take() else {
+            return;
+        };
+
+        self.clear_messages();
+        match result {
+            Ok(()) => {
+                if self.create_session(&job.session_name, &job.dest, job.start_claude) {
+                    self.message = Some(format!(
+                        "Cloned {} and created session '{}'",
+                        job.url, job.session_name
+                    ));
                 }
-                Err(e) => {
-                    self.error = Some(format!("Failed to create session: {}", e));
+                self.record_history("Clone into new session", &job.session_name);
+            }
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Create a session in `path`, sending the startup command when
+    /// `start_claude` allows it. Runs the new-session hook and reports the
+    /// outcome; returns whether the session was created.
+    fn create_session(&mut self, session_name: &str, path: &Path, start_claude: bool) -> bool {
+        if self.refused_in_presentation("New session") {
+            return false;
+        }
+        let config = self.config_for(path);
+        let startup_command =
+            (start_claude && config.start_claude).then_some(config.startup_command.as_str());
+
+        match Tmux::new_session(session_name, path, startup_command) {
+            Ok(_) => {
+                let branch = GitContext::detect(path).map(|g| g.branch);
+                self.run_hook(HookEvent::NewSession, session_name, path, branch.as_deref());
+                self.refresh_sessions();
+                self.message = Some(format!("Created session '{}'", session_name));
+                if startup_command.is_some() {
+                    self.schedule_startup_check(session_name);
                 }
+                true
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to create session: {}", e));
+                false
             }
         }
+    }
+
+    /// Switch to a session at the root of the selected session's checkout,
+    /// creating one (named after the directory) if none is there yet
+    pub fn open_repo_root(&mut self) {
+        self.clear_messages();
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        let Some(git) = session.git_context() else {
+            self.error = Some(format!(
+                "'{}' is not a git repository",
+                session.display_path()
+            ));
+            return;
+        };
+        let root = canonical_path(&git.workdir);
+        if canonical_path(&session.working_directory) == root {
+            self.message = Some("Session is already at the repository root".to_string());
+            return;
+        }
+
+        let existing = self
+            .sessions
+            .iter()
+            .find(|s| canonical_path(&s.working_directory) == root)
+            .map(|s| s.name.clone());
+        let session_name = match existing {
+            Some(name) => name,
+            None => {
+                if self.refused_in_presentation(SessionAction::OpenRepoRoot.label()) {
+                    return;
+                }
+                let dir_name = root
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "repo".to_string());
+                let name = unique_name(&sanitize_for_session_name(&dir_name), |name| {
+                    Tmux::session_exists(name)
+                });
+                let created = self.create_session(&name, &root, true);
+                self.record_history(SessionAction::OpenRepoRoot.label(), &name);
+                if !created {
+                    self.mode = Mode::Normal;
+                    return;
+                }
+                name
+            }
+        };
+
         self.mode = Mode::Normal;
+        match Tmux::switch_to_session(&session_name) {
+            Ok(_) => self.should_quit = true,
+            Err(e) => self.error = Some(format!("Failed to switch: {}", e)),
+        }
+    }
+
+    /// Create a plain directory next to the selected session's project,
+    /// `<project>-scratch` (or `-scratch-2`, ... if taken), and a session
+    /// of the same name in it. Git is not involved.
+    pub fn new_scratch_session(&mut self) {
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        // Next to the repository, or to the directory outside git
+        let project = canonical_path(
+            session
+                .git_context()
+                .map_or(&session.working_directory, |git| &git.workdir),
+        );
+        let Some(parent) = project.parent() else {
+            self.error = Some("No directory to create a scratch directory in".to_string());
+            return;
+        };
+        let dir_name = project
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let base = format!("{}-scratch", sanitize_for_session_name(&dir_name));
+        let name = unique_name(base.trim_start_matches('-'), |name| {
+            parent.join(name).exists() || Tmux::session_exists(name)
+        });
+
+        let path = parent.join(&name);
+        if let Err(e) = std::fs::create_dir(&path) {
+            self.error = Some(format!(
+                "Failed to create scratch directory '{}': {}",
+                path.display(),
+                e
+            ));
+            return;
+        }
+        self.create_session(&name, &path, true);
     }
 
     // =========================================================================
@@ -740,21 +3145,45 @@ impl App {
     /// Start the new worktree flow
     pub fn start_new_worktree(&mut self) {
         self.clear_messages();
+        if self.refused_in_presentation("New worktree") {
+            self.mode = Mode::Normal;
+            return;
+        }
         let Some(session) = self.selected_session() else {
+            self.error = Some("No session selected".to_string());
+            self.mode = Mode::Normal;
             return;
         };
 
-        // Get the repo path (use main repo if this is a worktree)
-        let source_repo = if let Some(ref git) = session.git_context {
+        // A removed directory has no git context; say so rather than
+        // calling it "not a git repository"
+        if !session.working_directory.is_dir() {
+            self.error = Some(format!(
+                "Cannot create a worktree: '{}' no longer exists",
+                session.display_path()
+            ));
+            self.mode = Mode::Normal;
+            return;
+        }
+
+        // Get the repo path (use main repo if this is a worktree and the
+        // main checkout is still there)
+        let source_repo = if let Some(git) = session.git_context() {
             if git.is_worktree {
                 git.main_repo_path
                     .clone()
+                    .filter(|path| path.is_dir())
                     .unwrap_or_else(|| session.working_directory.clone())
             } else {
                 session.working_directory.clone()
             }
         } else {
-            return; // Not a git repo
+            self.error = Some(format!(
+                "Cannot create a worktree: '{}' is not a git repository",
+                session.display_path()
+            ));
+            self.mode = Mode::Normal;
+            return;
         };
 
         // Get list of branches
@@ -766,16 +3195,23 @@ impl App {
             }
         };
 
+        let config = self.config_for(&source_repo);
+
         self.mode = Mode::NewWorktree {
             source_repo,
             all_branches,
-            branch_input: String::new(),
+            branch_input: TextInput::new(),
             selected_branch: None,
-            worktree_path: String::new(),
-            session_name: String::new(),
+            worktree_path: TextInput::new(),
+            path_template: config.worktree_path_template,
+            session_name_template: config.worktree_session_name_template,
+            session_name_max_length: config.session_name_max_length,
+            session_name: TextInput::new(),
+            draft_pr: false,
             field: NewWorktreeField::Branch,
             path_suggestions: Vec::new(),
             path_selected: None,
+            edits: Edits::Pristine,
         };
     }
 
@@ -790,7 +3226,7 @@ impl App {
             if branch_input.is_empty() {
                 all_branches.iter().map(|s| s.as_str()).collect()
             } else {
-                let input_lower = branch_input.to_lowercase();
+                let input_lower = branch_input.as_str().to_lowercase();
                 all_branches
                     .iter()
                     .filter(|b| b.to_lowercase().contains(&input_lower))
@@ -810,6 +3246,9 @@ impl App {
             ref branch_input,
             ref mut selected_branch,
             ref mut worktree_path,
+            ref path_template,
+            ref session_name_template,
+            session_name_max_length,
             ref mut session_name,
             ..
         } = self.mode
@@ -818,7 +3257,7 @@ impl App {
             let filtered: Vec<&str> = if branch_input.is_empty() {
                 all_branches.iter().map(|s| s.as_str()).collect()
             } else {
-                let input_lower = branch_input.to_lowercase();
+                let input_lower = branch_input.as_str().to_lowercase();
                 all_branches
                     .iter()
                     .filter(|b| b.to_lowercase().contains(&input_lower))
@@ -843,44 +3282,53 @@ impl App {
             };
 
             if !branch_for_path.is_empty() {
-                *worktree_path = default_worktree_path(source_repo, branch_for_path)
-                    .to_string_lossy()
-                    .to_string();
-                // Session name: repo-name + branch suffix
-                let repo_name = source_repo
-                    .file_name()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("repo");
-                let branch_suffix = sanitize_for_session_name(branch_for_path);
-                *session_name = format!("{}-{}", repo_name, branch_suffix);
+                worktree_path.set(
+                    default_worktree_path(source_repo, branch_for_path, path_template)
+                        .to_string_lossy(),
+                );
+                session_name.set(worktree_session_name(
+                    source_repo,
+                    branch_for_path,
+                    session_name_template,
+                    session_name_max_length,
+                ));
             }
         }
     }
 
     /// Create the new worktree and session
     pub fn confirm_new_worktree(&mut self) {
-        let (source_repo, all_branches, branch_input, selected_branch, worktree_path, session_name) =
-            if let Mode::NewWorktree {
-                ref source_repo,
-                ref all_branches,
-                ref branch_input,
+        let (
+            source_repo,
+            all_branches,
+            branch_input,
+            selected_branch,
+            worktree_path,
+            session_name,
+            draft_pr,
+        ) = if let Mode::NewWorktree {
+            ref source_repo,
+            ref all_branches,
+            ref branch_input,
+            selected_branch,
+            ref worktree_path,
+            ref session_name,
+            draft_pr,
+            ..
+        } = self.mode
+        {
+            (
+                source_repo.clone(),
+                all_branches.clone(),
+                branch_input.as_str().to_string(),
                 selected_branch,
-                ref worktree_path,
-                ref session_name,
-                ..
-            } = self.mode
-            {
-                (
-                    source_repo.clone(),
-                    all_branches.clone(),
-                    branch_input.clone(),
-                    selected_branch,
-                    worktree_path.clone(),
-                    session_name.clone(),
-                )
-            } else {
-                return;
-            };
+                worktree_path.as_str().to_string(),
+                session_name.as_str().to_string(),
+                draft_pr,
+            )
+        } else {
+            return;
+        };
 
         // Validate inputs
         if branch_input.is_empty() && selected_branch.is_none() {
@@ -931,7 +3379,7 @@ impl App {
             (branch_input.clone(), true)
         };
 
-        let worktree_path_buf = expand_path(&worktree_path);
+        let worktree_path_buf = canonical_path(&expand_path(&worktree_path));
 
         // Create the worktree
         match GitContext::create_worktree(
@@ -941,14 +3389,38 @@ impl App {
             is_new_branch,
         ) {
             Ok(_) => {
-                // Create the session
-                match Tmux::new_session(&session_name, &worktree_path_buf, true) {
+                // Create the session, honoring the worktree's own repo config
+                let config = self.config_for(&worktree_path_buf);
+                let startup_command = config
+                    .start_claude
+                    .then_some(config.startup_command.as_str());
+
+                match Tmux::new_session(&session_name, &worktree_path_buf, startup_command) {
                     Ok(_) => {
+                        self.run_hook(
+                            HookEvent::NewSession,
+                            &session_name,
+                            &worktree_path_buf,
+                            Some(&branch_name),
+                        );
                         self.refresh_sessions();
                         self.message = Some(format!(
                             "Created worktree '{}' and session '{}'",
                             branch_name, session_name
                         ));
+                        if startup_command.is_some() {
+                            self.schedule_startup_check(&session_name);
+                        }
+                        if draft_pr {
+                            if let Err(e) =
+                                Tmux::set_session_option(&session_name, DRAFT_PR_OPTION, "1")
+                            {
+                                self.error = Some(format!(
+                                    "Session created but the draft PR reminder was not saved: {}",
+                                    e
+                                ));
+                            }
+                        }
                     }
                     Err(e) => {
                         self.error = Some(format!(
@@ -962,6 +3434,7 @@ impl App {
                 self.error = Some(format!("Failed to create worktree: {}", e));
             }
         }
+        self.record_history(SessionAction::NewWorktree.label(), &session_name);
 
         self.mode = Mode::Normal;
     }
@@ -974,30 +3447,71 @@ impl App {
     pub fn start_create_pull_request(&mut self) {
         self.clear_messages();
         let Some(session) = self.selected_session() else {
+            self.error = Some("No session selected".to_string());
+            self.mode = Mode::Normal;
             return;
         };
+        if session.git_context().is_none() {
+            self.error = Some(format!(
+                "Cannot create a pull request: '{}' is not a git repository",
+                session.display_path()
+            ));
+            self.mode = Mode::Normal;
+            return;
+        }
 
-        let path = &session.working_directory;
-        let base_branch = git::get_default_branch(path).unwrap_or_else(|| "main".to_string());
+        let path = session.working_directory.clone();
+        let base_branch = self
+            .base_branch_for(&path)
+            .unwrap_or_else(|| "main".to_string());
 
         self.mode = Mode::CreatePullRequest {
-            title: String::new(),
-            body: String::new(),
-            base_branch,
+            title: TextInput::new(),
+            body: TextInput::new(),
+            base_branch: TextInput::from(base_branch),
+            issue: TextInput::new(),
+            draft: false,
             field: CreatePullRequestField::Title,
+            edits: Edits::Pristine,
         };
     }
 
+    /// After a push, open the create PR dialog as a draft if the session was
+    /// created with the draft PR option. The option is cleared, so this
+    /// happens only once.
+    fn offer_draft_pull_request(&mut self, session_name: &str) {
+        if Tmux::session_option(session_name, DRAFT_PR_OPTION).is_none() {
+            return;
+        }
+        // A stale option only means the dialog is offered again next push
+        let _ = Tmux::unset_session_option(session_name, DRAFT_PR_OPTION);
+
+        let message = self.message.take();
+        self.start_create_pull_request();
+        if let Mode::CreatePullRequest { ref mut draft, .. } = self.mode {
+            *draft = true;
+        }
+        self.message = message;
+    }
+
     /// Confirm and execute PR creation
     pub fn confirm_create_pull_request(&mut self) {
-        let (title, body, base_branch) = if let Mode::CreatePullRequest {
+        let (title, body, base_branch, issue, draft) = if let Mode::CreatePullRequest {
             ref title,
             ref body,
             ref base_branch,
+            ref issue,
+            draft,
             ..
         } = self.mode
         {
-            (title.clone(), body.clone(), base_branch.clone())
+            (
+                title.as_str().to_string(),
+                body.as_str().to_string(),
+                base_branch.as_str().to_string(),
+                issue.as_str().to_string(),
+                draft,
+            )
         } else {
             self.mode = Mode::Normal;
             return;
@@ -1009,19 +3523,295 @@ impl App {
             return;
         }
 
+        let body = match pull_request_body(&body, &issue) {
+            Ok(body) => body,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+
         if let Some(session) = self.selected_session() {
             let path = session.working_directory.clone();
-            match git::create_pull_request(&path, &title, &body, &base_branch) {
+            let session_name = session.name.clone();
+            self.pr_status.forget(&path);
+            match git::provider(&path)
+                .and_then(|p| p.create_pull_request(&path, &title, &body, &base_branch, draft))
+            {
                 Ok(result) => {
-                    self.message = Some(format!("Created PR: {}", result.url));
+                    self.message = Some(format!(
+                        "Created PR \"{}\": {}",
+                        truncate_display(&title, SUBJECT_COLUMNS),
+                        result.url
+                    ));
                 }
                 Err(e) => {
                     self.error = Some(format!("Failed to create PR: {}", e));
                 }
             }
+            self.record_history(SessionAction::CreatePullRequest.label(), &session_name);
+        }
+
+        self.mode = Mode::Normal;
+    }
+
+    // =========================================================================
+    // Clients
+    // =========================================================================
+
+    /// Show the clients attached to the tmux server
+    pub fn show_clients(&mut self) {
+        self.clear_messages();
+        match Tmux::list_clients() {
+            Ok(clients) => {
+                self.mode = Mode::Clients {
+                    clients,
+                    own: Tmux::current_client(),
+                    selected: 0,
+                };
+            }
+            Err(e) => self.error = Some(format!("Failed to list clients: {:#}", e)),
+        }
+    }
+
+    /// Re-list the clients, keeping the highlight in range
+    pub fn reload_clients(&mut self) {
+        let clients = match Tmux::list_clients() {
+            Ok(clients) => clients,
+            Err(e) => {
+                self.error = Some(format!("Failed to list clients: {:#}", e));
+                return;
+            }
+        };
+        if let Mode::Clients {
+            clients: ref mut current,
+            ref mut selected,
+            ..
+        } = self.mode
+        {
+            *selected = (*selected).min(clients.len().saturating_sub(1));
+            *current = clients;
+        }
+    }
+
+    /// Move the highlight in the clients view, wrapping around
+    pub fn select_client(&mut self, delta: isize) {
+        if let Mode::Clients {
+            ref clients,
+            ref mut selected,
+            ..
+        } = self.mode
+        {
+            if !clients.is_empty() {
+                let count = clients.len() as isize;
+                *selected = (*selected as isize + delta).rem_euclid(count) as usize;
+            }
+        }
+    }
+
+    /// The highlighted client and whether it is the one claude-tmux runs in
+    fn highlighted_client(&self) -> Option<(String, bool)> {
+        let Mode::Clients {
+            ref clients,
+            ref own,
+            selected,
+        } = self.mode
+        else {
+            return None;
+        };
+        let client = clients.get(selected)?;
+        Some((client.tty.clone(), own.as_ref() == Some(&client.tty)))
+    }
+
+    /// Switch the highlighted client to the session selected in the list
+    pub fn switch_highlighted_client(&mut self) {
+        self.clear_messages();
+        let Some((tty, _)) = self.highlighted_client() else {
+            return;
+        };
+        let Some((name, target)) = self
+            .selected_session()
+            .map(|s| (s.name.clone(), s.switch_target()))
+        else {
+            return;
+        };
+
+        match Tmux::switch_client_to(&tty, &target) {
+            Ok(_) => self.message = Some(format!("Switched {} to '{}'", tty, name)),
+            Err(e) => self.error = Some(format!("Failed to switch client: {:#}", e)),
+        }
+        self.reload_clients();
+    }
+
+    /// Detach the highlighted client, unless it is the one claude-tmux runs in
+    pub fn detach_highlighted_client(&mut self) {
+        self.clear_messages();
+        if self.refused_in_presentation("Detach client") {
+            return;
+        }
+        let Some((tty, own)) = self.highlighted_client() else {
+            return;
+        };
+        if own {
+            self.error = Some("That is this client; quit instead".to_string());
+            return;
+        }
+
+        match Tmux::detach_client(&tty) {
+            Ok(_) => self.message = Some(format!("Detached {}", tty)),
+            Err(e) => self.error = Some(format!("Failed to detach client: {:#}", e)),
+        }
+        self.reload_clients();
+    }
+
+    // =========================================================================
+    // History
+    // =========================================================================
+
+    /// Show the action history overlay
+    pub fn show_history(&mut self) {
+        self.mode = Mode::History {
+            scroll: 0,
+            confirm_clear: false,
+        };
+    }
+
+    /// Ask to confirm clearing the action history
+    pub fn start_clear_history(&mut self) {
+        if self.refused_in_presentation("Clear history") {
+            return;
+        }
+        if let Mode::History {
+            ref mut confirm_clear,
+            ..
+        } = self.mode
+        {
+            *confirm_clear = !self.history.entries().is_empty();
+        }
+    }
+
+    /// Leave the clear confirmation without clearing
+    pub fn cancel_clear_history(&mut self) {
+        if let Mode::History {
+            ref mut confirm_clear,
+            ..
+        } = self.mode
+        {
+            *confirm_clear = false;
+        }
+    }
+
+    /// Clear the action history, deleting the persisted file too when
+    /// `persist_history` is set
+    pub fn clear_history(&mut self) {
+        if self.refused_in_presentation("Clear history") {
+            return;
         }
+        let count = self.history.entries().len();
+        self.history.clear();
+        self.mode = Mode::History {
+            scroll: 0,
+            confirm_clear: false,
+        };
+        let removed = if self.config.persist_history {
+            History::remove_file()
+        } else {
+            Ok(())
+        };
+        match removed {
+            Ok(_) => self.message = Some(format!("Cleared {} history entries", count)),
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Scroll the action history by the given number of rows
+    pub fn scroll_history(&mut self, delta: isize) {
+        let max = self.history.entries().len().saturating_sub(1);
+        if let Mode::History { ref mut scroll, .. } = self.mode {
+            *scroll = scroll.saturating_add_signed(delta).min(max);
+        }
+    }
 
+    // =========================================================================
+    // Preview search
+    // =========================================================================
+
+    /// Start typing a search for the preview
+    pub fn start_preview_search(&mut self) {
+        self.clear_messages();
+        let query = self
+            .preview_search
+            .as_ref()
+            .map(|s| s.query.clone())
+            .unwrap_or_default();
+        self.mode = Mode::PreviewSearch {
+            input: TextInput::from(query),
+        };
+    }
+
+    /// Search the preview and jump to the newest (bottom-most) match
+    pub fn apply_preview_search(&mut self) {
+        let query = if let Mode::PreviewSearch { ref input } = self.mode {
+            input.as_str().to_string()
+        } else {
+            return;
+        };
         self.mode = Mode::Normal;
+
+        if query.is_empty() {
+            self.clear_preview_search();
+            return;
+        }
+
+        let matches = search::matching_lines(&self.preview_lines(), &query);
+        if matches.is_empty() {
+            self.clear_preview_search();
+            self.error = Some(format!("Pattern not found: {}", query));
+            return;
+        }
+
+        self.preview_search = Some(PreviewSearch {
+            query,
+            current: matches.len() - 1,
+            matches,
+        });
+        self.scroll_to_current_match();
+    }
+
+    /// Jump to the next older match (`older`) or newer match, wrapping around
+    pub fn jump_preview_match(&mut self, older: bool) {
+        let Some(ref mut search) = self.preview_search else {
+            return;
+        };
+        let count = search.matches.len();
+        if count == 0 {
+            return;
+        }
+
+        search.current = if older {
+            (search.current + count - 1) % count
+        } else {
+            (search.current + 1) % count
+        };
+        self.scroll_to_current_match();
+    }
+
+    /// Clear the search and return the preview to the tail of the pane
+    pub fn clear_preview_search(&mut self) {
+        self.preview_search = None;
+        self.preview_scroll = 0;
+    }
+
+    /// Scroll the preview so the current match is near its bottom
+    fn scroll_to_current_match(&mut self) {
+        // Lines kept visible below the match for context
+        const CONTEXT_LINES: usize = 2;
+
+        let Some(line) = self.preview_search.as_ref().and_then(|s| s.current_line()) else {
+            return;
+        };
+        let total = self.preview_lines().len();
+        self.preview_scroll = total.saturating_sub(line + 1 + CONTEXT_LINES);
     }
 
     // =========================================================================
@@ -1032,15 +3822,26 @@ impl App {
     pub fn start_filter(&mut self) {
         self.clear_messages();
         self.mode = Mode::Filter {
-            input: self.filter.clone(),
+            input: TextInput::from(self.filter.clone()),
         };
     }
 
+    /// Enter filter mode with `c` appended to the current filter
+    pub fn start_filter_with(&mut self, c: char) {
+        self.start_filter();
+        if let Mode::Filter { ref mut input } = self.mode {
+            input.insert(c);
+        }
+    }
+
     /// Apply filter and return to normal mode
     pub fn apply_filter(&mut self) {
         if let Mode::Filter { ref input } = self.mode {
-            self.filter = input.clone();
+            self.filter = input.as_str().to_string();
             self.selected = 0; // Reset selection when filter changes
+            self.follow_preview = false;
+            self.preview_h_scroll = 0;
+            self.clear_preview_search();
         }
         self.mode = Mode::Normal;
         self.update_preview();
@@ -1050,6 +3851,9 @@ impl App {
     pub fn clear_filter(&mut self) {
         self.filter.clear();
         self.selected = 0;
+        self.follow_preview = false;
+        self.preview_h_scroll = 0;
+        self.clear_preview_search();
     }
 
     /// Show help
@@ -1061,8 +3865,179 @@ impl App {
     /// Cancel current mode and return to normal
     pub fn cancel(&mut self) {
         self.pending_action = None;
+        self.merge_strategy = MergeStrategy::default();
         self.pr_info = None;
+        self.diff_stat = None;
+        self.branch_merged = false;
+        self.mode = Mode::Normal;
+
+        // Dismissing a macro step's prompt stops the macro; closing a
+        // viewer (files, tags) just completes the step
+        if let Some(run) = self.macro_run.as_ref() {
+            let viewing = matches!(
+                run.waiting,
+                Some(
+                    SessionAction::StageFiles
+                        | SessionAction::ViewDiff
+                        | SessionAction::ViewTags
+                        | SessionAction::ViewPullRequestDiff
+                        | SessionAction::ViewPullRequestComments
+                )
+            );
+            if run.waiting.is_some() && !viewing {
+                let mut run = self.macro_run.take().expect("macro is running");
+                let title = format!("Macro '{}' cancelled at {}", run.name, run.position());
+                run.record(Err("Cancelled".to_string()));
+                self.show_macro_results(run, title);
+            }
+        }
+    }
+
+    // =========================================================================
+    // Macros
+    // =========================================================================
+
+    /// Show the macro picker for the selected session
+    pub fn show_macros(&mut self) {
+        if self.config.macros.is_empty() {
+            self.message = Some("No macros configured".to_string());
+            return;
+        }
+        if self.selected_session().is_some() {
+            self.mode = Mode::Macros { selected: 0 };
+        }
+    }
+
+    /// Move the macro picker's highlight, wrapping around
+    pub fn select_macro(&mut self, delta: isize) {
+        let count = self.config.macros.len();
+        if let Mode::Macros { ref mut selected } = self.mode {
+            *selected = (*selected as isize + delta).rem_euclid(count as isize) as usize;
+        }
+    }
+
+    /// Run the highlighted macro on the selected session
+    pub fn run_selected_macro(&mut self) {
+        let Mode::Macros { selected } = self.mode else {
+            return;
+        };
         self.mode = Mode::Normal;
+        self.clear_messages();
+        let Some(session_name) = self.selected_session().map(|s| s.name.clone()) else {
+            return;
+        };
+        match MacroRun::new(&self.config.macros[selected], &session_name) {
+            Ok(run) => {
+                self.macro_run = Some(run);
+                self.run_macro_steps();
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Run macro steps until the macro ends, fails or a step waits on a
+    /// dialog or confirmation
+    fn run_macro_steps(&mut self) {
+        while let Some(run) = self.macro_run.as_mut() {
+            // The previous step got here without an error
+            let done = self.message.clone().unwrap_or_else(|| "Done".to_string());
+            run.record(Ok(done));
+            let Some(step) = run.steps.get(run.next).cloned() else {
+                let run = self.macro_run.take().expect("macro is running");
+                self.quit_if_single_shot();
+                if !self.should_quit {
+                    let title =
+                        format!("Macro '{}' finished ({} steps)", run.name, run.steps.len());
+                    self.show_macro_results(run, title);
+                }
+                return;
+            };
+            run.next += 1;
+            run.waiting = Some(step.clone());
+            let session_name = run.session.clone();
+            let confirm = run.confirm;
+
+            // Steps follow the session wherever it now sits in the list
+            let Some(index) = self
+                .filtered_sessions()
+                .iter()
+                .position(|s| s.name == session_name)
+            else {
+                self.stop_macro(format!("session '{}' is no longer listed", session_name));
+                return;
+            };
+            self.selected = index;
+
+            let commit_message = match step {
+                SessionAction::Commit => self.macro_run.as_ref().and_then(|run| {
+                    let branch = self.selected_session()?.git_context()?.branch.clone();
+                    run.commit_message(&branch)
+                }),
+                _ => None,
+            };
+            if let Some(message) = commit_message {
+                self.clear_messages();
+                self.start_commit();
+                if let Mode::Commit {
+                    message: ref mut input,
+                    ..
+                } = self.mode
+                {
+                    *input = TextInput::from(message);
+                }
+                self.confirm_commit();
+            } else if confirm {
+                self.confirm_or_execute(step);
+            } else {
+                self.execute_action(step);
+            }
+
+            if let Some(error) = self.error.take() {
+                self.stop_macro(error);
+                return;
+            }
+            if self.mode != Mode::Normal || self.should_quit {
+                // A dialog is open (resumed by `resume_macro`), or we switched away
+                if self.should_quit {
+                    self.macro_run = None;
+                }
+                return;
+            }
+        }
+    }
+
+    /// Continue a macro once the dialog its current step opened has closed
+    pub fn resume_macro(&mut self) {
+        let waiting = self
+            .macro_run
+            .as_ref()
+            .is_some_and(|run| run.waiting.is_some());
+        if !waiting || self.mode != Mode::Normal {
+            return;
+        }
+        match self.error.take() {
+            Some(error) => self.stop_macro(error),
+            None => self.run_macro_steps(),
+        }
+    }
+
+    /// Stop the running macro, reporting why
+    fn stop_macro(&mut self, reason: String) {
+        if let Some(mut run) = self.macro_run.take() {
+            let title = format!("Macro '{}' stopped at {}", run.name, run.position());
+            run.record(Err(reason));
+            self.show_macro_results(run, title);
+        }
+    }
+
+    /// Show the outcome of each step of a macro that ended
+    fn show_macro_results(&mut self, run: MacroRun, title: String) {
+        // The summary replaces the last step's message
+        self.clear_messages();
+        self.mode = Mode::BatchResults {
+            title,
+            entries: run.results,
+        };
     }
 
     // =========================================================================
@@ -1089,6 +4064,26 @@ impl App {
         (working, waiting, idle)
     }
 
+    /// Terminal title summarizing session status, e.g.
+    /// "claude-tmux: 2 working, 1 waiting"
+    pub fn terminal_title(&self) -> String {
+        let (working, waiting, _idle) = self.status_counts();
+
+        let mut parts = Vec::new();
+        if working > 0 {
+            parts.push(format!("{} working", working));
+        }
+        if waiting > 0 {
+            parts.push(format!("{} waiting", waiting));
+        }
+
+        if parts.is_empty() {
+            "claude-tmux".to_string()
+        } else {
+            format!("claude-tmux: {}", parts.join(", "))
+        }
+    }
+
     // =========================================================================
     // Path completion methods
     // =========================================================================
@@ -1102,7 +4097,7 @@ impl App {
             ..
         } = self.mode
         {
-            let completion = crate::completion::complete_path(path);
+            let completion = crate::completion::complete_path(path.as_str());
             *path_suggestions = completion.suggestions;
             // Reset selection if it's out of bounds
             if let Some(idx) = *path_selected {
@@ -1126,7 +4121,7 @@ impl App {
             ..
         } = self.mode
         {
-            let completion = crate::completion::complete_path(worktree_path);
+            let completion = crate::completion::complete_path(worktree_path.as_str());
             *path_suggestions = completion.suggestions;
             // Reset selection if it's out of bounds
             if let Some(idx) = *path_selected {
@@ -1197,12 +4192,12 @@ impl App {
             // If a suggestion is selected, use it
             if let Some(idx) = *path_selected {
                 if let Some(suggestion) = path_suggestions.get(idx) {
-                    *path = suggestion.clone();
+                    path.set(suggestion.clone());
                     *path_selected = None;
                 }
             } else if let Some(first) = path_suggestions.first() {
                 // Otherwise use the first suggestion (ghost text)
-                *path = first.clone();
+                path.set(first.clone());
             }
         }
         // Update suggestions after accepting
@@ -1265,12 +4260,12 @@ impl App {
             // If a suggestion is selected, use it
             if let Some(idx) = *path_selected {
                 if let Some(suggestion) = path_suggestions.get(idx) {
-                    *worktree_path = suggestion.clone();
+                    worktree_path.set(suggestion.clone());
                     *path_selected = None;
                 }
             } else if let Some(first) = path_suggestions.first() {
                 // Otherwise use the first suggestion (ghost text)
-                *worktree_path = first.clone();
+                worktree_path.set(first.clone());
             }
         }
         // Update suggestions after accepting
@@ -1290,7 +4285,7 @@ impl App {
             let filtered: Vec<&str> = if branch_input.is_empty() {
                 all_branches.iter().map(|s| s.as_str()).collect()
             } else {
-                let input_lower = branch_input.to_lowercase();
+                let input_lower = branch_input.as_str().to_lowercase();
                 all_branches
                     .iter()
                     .filter(|b| b.to_lowercase().contains(&input_lower))
@@ -1316,7 +4311,7 @@ impl App {
                 ..
             } = self.mode
             {
-                *branch_input = branch_name;
+                branch_input.set(branch_name);
                 *selected_branch = None;
             }
             self.update_worktree_suggestions();
@@ -1340,27 +4335,14 @@ impl App {
 
         match self.mode {
             Mode::ActionMenu => {
-                // Count items before selected session (1 row each)
-                let mut index = self.selected;
+                // Count items before selected session (1 row each, plus repo headers)
+                let mut index = self.selected + self.group_headers_through(self.selected);
 
                 // Add 1 for the selected session row itself
                 index += 1;
 
-                // Add 1 for metadata row (always present when expanded)
-                index += 1;
-
-                // Add 1 for git info row if present
-                if self
-                    .selected_session()
-                    .is_some_and(|s| s.git_context.is_some())
-                {
-                    index += 1;
-
-                    // Add 1 for PR info row if present
-                    if self.pr_info.is_some() {
-                        index += 1;
-                    }
-                }
+                // Add the metadata, git info and PR info rows, if shown
+                index += self.menu_detail_rows();
 
                 // Add 1 for separator
                 index += 1;
@@ -1371,12 +4353,29 @@ impl App {
                 index
             }
             _ => {
-                // In non-ActionMenu modes, just the session index
-                self.selected
+                // In non-ActionMenu modes, just the session index (plus repo headers)
+                self.selected + self.group_headers_through(self.selected)
             }
         }
     }
 
+    /// Number of detail rows shown above the action menu: 1 metadata row,
+    /// 1 git info row (if git context) and 1 PR info row (if pr_info), or
+    /// none when details are hidden
+    fn menu_detail_rows(&self) -> usize {
+        if !self.menu_details {
+            return 0;
+        }
+        let has_git = self
+            .selected_session()
+            .is_some_and(|s| s.git_context().is_some());
+        match (has_git, self.pr_info.is_some()) {
+            (false, _) => 1,
+            (true, false) => 2,
+            (true, true) => 3,
+        }
+    }
+
     /// Compute the total number of items in the rendered list.
     ///
     /// This accounts for the expanded content when in ActionMenu mode.
@@ -1388,35 +4387,22 @@ impl App {
 
         match self.mode {
             Mode::ActionMenu => {
-                // Base: one row per session
-                let mut total = filtered_count;
+                // Base: one row per session, plus repo headers
+                let mut total = filtered_count + self.group_headers_through(filtered_count - 1);
 
                 // Add expanded content for selected session:
-                // - 1 metadata row
-                // - 1 git info row (if git context)
-                // - 1 PR info row (if pr_info)
+                // - detail rows (if shown)
                 // - 1 separator
                 // - N action rows
                 // - 1 end separator
-                total += 1; // metadata row
-
-                if self
-                    .selected_session()
-                    .is_some_and(|s| s.git_context.is_some())
-                {
-                    total += 1; // git info row
-                    if self.pr_info.is_some() {
-                        total += 1; // PR info row
-                    }
-                }
-
+                total += self.menu_detail_rows();
                 total += 1; // separator
                 total += self.available_actions.len(); // action rows
                 total += 1; // end separator
 
                 total
             }
-            _ => filtered_count,
+            _ => filtered_count + self.group_headers_through(filtered_count - 1),
         }
     }
 }
@@ -5,6 +5,11 @@
 
 use std::path::PathBuf;
 
+use crate::git::ChangedFile;
+use crate::session::{ClaudeCodeStatus, Client};
+
+use super::TextInput;
+
 /// The current mode/state of the application
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Mode {
@@ -13,13 +18,22 @@ pub enum Mode {
     /// Viewing actions for selected session
     ActionMenu,
     /// Filtering sessions with search input
-    Filter { input: String },
+    Filter { input: TextInput },
     /// Confirming an action (kill, etc.)
     ConfirmAction,
+    /// Confirming an action by typing the session name
+    TypeToConfirm {
+        /// Text that must be typed to confirm
+        expected: String,
+        input: TextInput,
+    },
     /// Creating a new session
     NewSession {
-        name: String,
-        path: String,
+        name: TextInput,
+        path: TextInput,
+        /// Git URL to clone into `path` before creating the session; empty
+        /// to use `path` as it is
+        url: TextInput,
         field: NewSessionField,
         /// Path completion suggestions
         path_suggestions: Vec<String>,
@@ -27,9 +41,39 @@ pub enum Mode {
         path_selected: Option<usize>,
     },
     /// Renaming a session
-    Rename { old_name: String, new_name: String },
+    Rename {
+        old_name: String,
+        new_name: TextInput,
+    },
+    /// Renaming one of a session's windows
+    RenameWindow {
+        /// Session owning the windows
+        session: String,
+        /// The session's windows as `(index, name)`
+        windows: Vec<(String, String)>,
+        /// Index into `windows` of the window being renamed
+        selected: usize,
+        new_name: TextInput,
+    },
     /// Entering commit message
-    Commit { message: String },
+    Commit {
+        message: TextInput,
+        /// Identity to commit as, `Name <email>`
+        author: TextInput,
+        /// Which field is active
+        field: CommitField,
+        /// Whether anything was typed, and Esc is asking to discard it
+        edits: Edits,
+    },
+    /// Entering the message for squashing the branch into one commit
+    Squash {
+        /// Branch whose merge-base the commits are squashed onto
+        base_branch: String,
+        /// Number of commits being squashed
+        count: usize,
+        /// Message of the squashed commit
+        message: TextInput,
+    },
     /// Creating a new session from a worktree
     NewWorktree {
         /// The source repository path (from selected session)
@@ -37,30 +81,152 @@ pub enum Mode {
         /// All branches in the repository
         all_branches: Vec<String>,
         /// Branch name input (may be new or existing)
-        branch_input: String,
+        branch_input: TextInput,
         /// Selected index in filtered branches (None = creating new branch)
         selected_branch: Option<usize>,
         /// Worktree path
-        worktree_path: String,
+        worktree_path: TextInput,
+        /// Template used to suggest the worktree path (global config merged with the repo's)
+        path_template: String,
+        /// Template used to suggest the session name
+        session_name_template: String,
+        /// Maximum length of the suggested session name
+        session_name_max_length: Option<usize>,
         /// Session name
-        session_name: String,
+        session_name: TextInput,
+        /// Offer a draft pull request after the session's first push
+        draft_pr: bool,
         /// Which field is active
         field: NewWorktreeField,
         /// Path completion suggestions
         path_suggestions: Vec<String>,
         /// Currently selected path suggestion index
         path_selected: Option<usize>,
+        /// Whether anything was typed, and Esc is asking to discard it
+        edits: Edits,
     },
     /// Creating a pull request
     CreatePullRequest {
         /// PR title
-        title: String,
+        title: TextInput,
         /// PR body/description
-        body: String,
+        body: TextInput,
         /// Base branch to merge into
-        base_branch: String,
+        base_branch: TextInput,
+        /// Issue number the PR closes when merged (optional)
+        issue: TextInput,
+        /// Open the pull request as a draft
+        draft: bool,
         /// Which field is active
         field: CreatePullRequestField,
+        /// Whether anything was typed, and Esc is asking to discard it
+        edits: Edits,
+    },
+    /// Creating an annotated tag on HEAD
+    CreateTag {
+        /// Tag name
+        name: TextInput,
+        /// Tag message
+        message: TextInput,
+        /// Whether to push the tag to the remote after creating it
+        push: bool,
+        /// Which field is active
+        field: CreateTagField,
+    },
+    /// Viewing the repository's tags
+    Tags {
+        /// Tag names
+        tags: Vec<String>,
+        /// Scroll offset into the tag list
+        scroll: usize,
+    },
+    /// Viewing a diff, e.g. of the session's pull request
+    Diff {
+        /// Dialog title
+        title: String,
+        /// Diff lines, tabs expanded
+        lines: Vec<String>,
+        /// Scroll offset into the lines
+        scroll: usize,
+    },
+    /// Viewing the comments and reviews on the session's pull request
+    PrComments {
+        /// Dialog title
+        title: String,
+        /// Comments grouped by reviewer, one entry per line
+        lines: Vec<CommentLine>,
+        /// Scroll offset into the lines
+        scroll: usize,
+    },
+    /// Debugging status detection on the selected session (`--debug`)
+    DetectDebug {
+        /// Session name
+        session: String,
+        /// Every pane of the session with what detection made of it
+        panes: Vec<PaneDebug>,
+        /// Scroll offset into the overlay lines
+        scroll: usize,
+    },
+    /// Picking individual files to stage, unstage or discard
+    StageFiles {
+        /// Working directory of the session
+        path: PathBuf,
+        /// Files with uncommitted changes
+        files: Vec<ChangedFile>,
+        /// Highlighted file index
+        selected: usize,
+        /// Whether discarding the highlighted file awaits confirmation
+        confirm_discard: bool,
+    },
+    /// Typing a search for the preview
+    PreviewSearch {
+        /// Search text
+        input: TextInput,
+    },
+    /// Picking a color tag for a session
+    ColorTag {
+        /// Session being tagged
+        session: String,
+        /// Highlighted entry: an index into `TagColor::ALL`, or one past
+        /// the end for no tag
+        selected: usize,
+    },
+    /// Picking the session to show beside another in a new window
+    Compare {
+        /// Session being compared
+        session: String,
+        /// Every other session, in list order
+        others: Vec<String>,
+        /// Index of the highlighted session in `others`
+        selected: usize,
+    },
+    /// Viewing the clients attached to the tmux server
+    Clients {
+        /// Attached clients, in tmux's order
+        clients: Vec<Client>,
+        /// Terminal of the client claude-tmux runs in, if any
+        own: Option<String>,
+        /// Index of the highlighted client
+        selected: usize,
+    },
+    /// Picking a macro to run on the selected session
+    Macros {
+        /// Index of the highlighted macro
+        selected: usize,
+    },
+    /// Outcome of each step of a macro that ended
+    BatchResults {
+        /// How the run ended, e.g. `Macro 'ship' finished (3 steps)`
+        title: String,
+        /// Step label and its outcome: the message it left, or why it failed
+        entries: Vec<(String, Result<String, String>)>,
+    },
+    /// Viewing the history of performed actions
+    History {
+        /// Scroll offset into the history, newest first
+        scroll: usize,
+        /// Whether clearing the history awaits confirmation
+        confirm_clear: bool,
     },
     /// Showing help
     Help,
@@ -71,14 +237,44 @@ pub enum Mode {
 pub enum SessionAction {
     /// Switch to this session
     SwitchTo,
+    /// Switch to this session as a read-only client
+    SwitchReadOnly,
     /// Rename this session
     Rename,
+    /// Rename one of the session's windows
+    RenameWindow,
+    /// Create a plain scratch directory next to the project, with a session
+    NewScratch,
+    /// Pick a color tag for the session's name
+    ColorTag,
+    /// Show the session beside another one in a new tmux window
+    Compare,
+    /// Run `$EDITOR` in the session's working directory, in one of its panes
+    OpenEditor,
+    /// Copy the session's working directory path to the clipboard
+    CopyPath,
     /// Create a new session from a worktree
     NewWorktree,
+    /// Switch to (or create) a session at the repository root
+    OpenRepoRoot,
     /// Stage all changes
     Stage,
+    /// Stage, unstage or discard individual files
+    StageFiles,
+    /// Show the uncommitted changes in the diff viewer
+    ViewDiff,
     /// Commit staged changes
     Commit,
+    /// Stash all uncommitted changes
+    Stash,
+    /// Apply and drop the most recent stash
+    StashPop,
+    /// Continue the merge, rebase, cherry-pick or revert in progress
+    ContinueOperation,
+    /// Abort the merge, rebase, cherry-pick, revert or bisect in progress
+    AbortOperation,
+    /// Squash the branch's commits since its base into one
+    Squash,
     /// Push commits to remote
     Push,
     /// Push and set upstream branch
@@ -87,10 +283,26 @@ pub enum SessionAction {
     Fetch,
     /// Pull commits from remote
     Pull,
+    /// Fetch the base branch and merge it into the current branch
+    MergeBaseBranch,
+    /// List the repository's tags
+    ViewTags,
+    /// Create an annotated tag on HEAD
+    CreateTag,
+    /// Push the tags the remote does not have yet
+    PushTags,
+    /// Copy the HEAD commit SHA to the clipboard
+    CopySha,
+    /// Copy the current branch name to the clipboard
+    CopyBranch,
     /// Create a pull request
     CreatePullRequest,
     /// View pull request in browser
     ViewPullRequest,
+    /// View the pull request's diff in the diff viewer
+    ViewPullRequestDiff,
+    /// View the pull request's comments and reviews, grouped by reviewer
+    ViewPullRequestComments,
     /// Close pull request without merging
     ClosePullRequest,
     /// Merge pull request
@@ -104,20 +316,136 @@ pub enum SessionAction {
 }
 
 impl SessionAction {
+    /// Every action, in the default menu order
+    pub const ALL: &'static [SessionAction] = &[
+        Self::SwitchTo,
+        Self::SwitchReadOnly,
+        Self::Rename,
+        Self::RenameWindow,
+        Self::NewScratch,
+        Self::ColorTag,
+        Self::Compare,
+        Self::OpenEditor,
+        Self::CopyPath,
+        Self::NewWorktree,
+        Self::OpenRepoRoot,
+        Self::Stage,
+        Self::StageFiles,
+        Self::ViewDiff,
+        Self::Commit,
+        Self::Stash,
+        Self::StashPop,
+        Self::ContinueOperation,
+        Self::AbortOperation,
+        Self::Squash,
+        Self::Push,
+        Self::PushSetUpstream,
+        Self::Fetch,
+        Self::Pull,
+        Self::MergeBaseBranch,
+        Self::ViewTags,
+        Self::CreateTag,
+        Self::PushTags,
+        Self::CopySha,
+        Self::CopyBranch,
+        Self::CreatePullRequest,
+        Self::ViewPullRequest,
+        Self::ViewPullRequestDiff,
+        Self::ViewPullRequestComments,
+        Self::ClosePullRequest,
+        Self::MergePullRequest,
+        Self::MergePullRequestAndClose,
+        Self::Kill,
+        Self::KillAndDeleteWorktree,
+    ];
+
+    /// Identifier used for this action in the `action_order` config
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::SwitchTo => "switch_to",
+            Self::SwitchReadOnly => "switch_read_only",
+            Self::Rename => "rename",
+            Self::RenameWindow => "rename_window",
+            Self::NewScratch => "new_scratch",
+            Self::ColorTag => "color_tag",
+            Self::Compare => "compare",
+            Self::OpenEditor => "open_editor",
+            Self::CopyPath => "copy_path",
+            Self::NewWorktree => "new_worktree",
+            Self::OpenRepoRoot => "open_repo_root",
+            Self::Stage => "stage",
+            Self::StageFiles => "stage_files",
+            Self::ViewDiff => "view_diff",
+            Self::Commit => "commit",
+            Self::Stash => "stash",
+            Self::StashPop => "stash_pop",
+            Self::ContinueOperation => "continue_operation",
+            Self::AbortOperation => "abort_operation",
+            Self::Squash => "squash",
+            Self::Push => "push",
+            Self::PushSetUpstream => "push_set_upstream",
+            Self::Fetch => "fetch",
+            Self::Pull => "pull",
+            Self::MergeBaseBranch => "merge_base_branch",
+            Self::ViewTags => "view_tags",
+            Self::CreateTag => "create_tag",
+            Self::PushTags => "push_tags",
+            Self::CopySha => "copy_sha",
+            Self::CopyBranch => "copy_branch",
+            Self::CreatePullRequest => "create_pull_request",
+            Self::ViewPullRequest => "view_pull_request",
+            Self::ViewPullRequestDiff => "view_pull_request_diff",
+            Self::ViewPullRequestComments => "view_pull_request_comments",
+            Self::ClosePullRequest => "close_pull_request",
+            Self::MergePullRequest => "merge_pull_request",
+            Self::MergePullRequestAndClose => "merge_pull_request_and_close",
+            Self::Kill => "kill",
+            Self::KillAndDeleteWorktree => "kill_and_delete_worktree",
+        }
+    }
+
+    /// Look up an action by its config identifier
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().find(|action| action.id() == id).cloned()
+    }
+
     /// Returns the display label for this action
     pub fn label(&self) -> &'static str {
         match self {
             Self::SwitchTo => "Switch to session",
+            Self::SwitchReadOnly => "Switch to session (read-only)",
             Self::Rename => "Rename session",
+            Self::RenameWindow => "Rename window...",
+            Self::NewScratch => "New scratch session",
+            Self::ColorTag => "Set color tag",
+            Self::Compare => "Compare side by side...",
+            Self::OpenEditor => "Open in editor",
+            Self::CopyPath => "Copy path",
             Self::NewWorktree => "New session from worktree",
+            Self::OpenRepoRoot => "Open repository root",
             Self::Stage => "Stage all changes",
+            Self::StageFiles => "Stage files...",
+            Self::ViewDiff => "View uncommitted diff",
             Self::Commit => "Commit staged changes",
+            Self::Stash => "Stash changes",
+            Self::StashPop => "Pop stash",
+            Self::ContinueOperation => "Continue in-progress operation",
+            Self::AbortOperation => "Abort in-progress operation",
+            Self::Squash => "Squash commits since base...",
             Self::Push => "Push to remote",
             Self::PushSetUpstream => "Push and set upstream",
             Self::Fetch => "Fetch from remote",
             Self::Pull => "Pull from remote",
+            Self::MergeBaseBranch => "Merge base branch into current",
+            Self::ViewTags => "View tags",
+            Self::CreateTag => "Tag current commit",
+            Self::PushTags => "Push tags",
+            Self::CopySha => "Copy commit SHA",
+            Self::CopyBranch => "Copy branch name",
             Self::CreatePullRequest => "Create pull request",
             Self::ViewPullRequest => "View pull request",
+            Self::ViewPullRequestDiff => "View pull request diff",
+            Self::ViewPullRequestComments => "View pull request comments",
             Self::ClosePullRequest => "Close pull request",
             Self::MergePullRequest => "Merge pull request",
             Self::MergePullRequestAndClose => "Merge PR + close session",
@@ -126,12 +454,31 @@ impl SessionAction {
         }
     }
 
+    /// Whether this action only looks at or switches to the session, and
+    /// so stays available in presentation mode
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            Self::SwitchTo
+                | Self::SwitchReadOnly
+                | Self::ViewDiff
+                | Self::ViewTags
+                | Self::CopyPath
+                | Self::CopySha
+                | Self::CopyBranch
+                | Self::ViewPullRequest
+                | Self::ViewPullRequestDiff
+                | Self::ViewPullRequestComments
+        )
+    }
+
     /// Whether this action requires confirmation
     pub fn requires_confirmation(&self) -> bool {
         matches!(
             self,
             Self::Kill
                 | Self::KillAndDeleteWorktree
+                | Self::AbortOperation
                 | Self::ClosePullRequest
                 | Self::MergePullRequest
                 | Self::MergePullRequestAndClose
@@ -139,11 +486,128 @@ impl SessionAction {
     }
 }
 
+impl Mode {
+    /// Typed-input state of a dialog that guards it against Esc
+    pub fn edits(&self) -> Option<Edits> {
+        match *self {
+            Mode::Commit { edits, .. }
+            | Mode::NewWorktree { edits, .. }
+            | Mode::CreatePullRequest { edits, .. } => Some(edits),
+            _ => None,
+        }
+    }
+
+    /// Set the typed-input state of a guarded dialog; other modes ignore it
+    pub fn set_edits(&mut self, state: Edits) {
+        match self {
+            Mode::Commit { edits, .. }
+            | Mode::NewWorktree { edits, .. }
+            | Mode::CreatePullRequest { edits, .. } => *edits = state,
+            _ => {}
+        }
+    }
+}
+
+/// Whether a dialog holds input typed since it opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Edits {
+    /// Nothing typed; Esc closes the dialog
+    #[default]
+    Pristine,
+    /// Something typed; Esc asks before discarding it
+    Unsaved,
+    /// Asking whether to discard what was typed
+    ConfirmDiscard,
+}
+
+/// A line of the pull request comments viewer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommentLine {
+    /// A reviewer, heading their comments
+    Reviewer(String),
+    /// When a comment was posted, with the review verdict if any
+    Header(String),
+    /// A line of a comment's body
+    Body(String),
+    /// Gap between comments
+    Blank,
+}
+
+/// A pane as seen by status detection, for the detection debug overlay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaneDebug {
+    /// Pane ID (e.g., "%0")
+    pub id: String,
+    /// Command running in the pane
+    pub command: String,
+    /// Whether the pane counts as a Claude Code pane
+    pub claude: bool,
+    /// Captured lines that detection looks at
+    pub capture: Vec<String>,
+    /// What `detect_status` returns for the capture
+    pub status: ClaudeCodeStatus,
+    /// What each heuristic found
+    pub notes: Vec<String>,
+}
+
+impl PaneDebug {
+    /// Lines the pane takes in the overlay: a header, the notes, the
+    /// capture and a blank separator
+    pub fn height(&self) -> usize {
+        2 + self.notes.len() + self.capture.len()
+    }
+}
+
+/// Order of the session list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortMode {
+    /// Attached sessions first, then by name (as listed by tmux)
+    #[default]
+    Default,
+    /// Sessions with uncommitted changes first, most commits ahead first
+    DirtyFirst,
+    /// By Claude Code status in the configured `status_order`, then by name
+    Status,
+    /// By name alone, attached or not
+    Name,
+    /// Longest running first
+    Uptime,
+    /// Most recently active first
+    Activity,
+}
+
+impl SortMode {
+    /// The next mode in the cycle
+    pub fn next(self) -> Self {
+        match self {
+            Self::Default => Self::DirtyFirst,
+            Self::DirtyFirst => Self::Status,
+            Self::Status => Self::Name,
+            Self::Name => Self::Uptime,
+            Self::Uptime => Self::Activity,
+            Self::Activity => Self::Default,
+        }
+    }
+
+    /// Short name shown in the status bar
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::DirtyFirst => "dirty first",
+            Self::Status => "status",
+            Self::Name => "name",
+            Self::Uptime => "uptime",
+            Self::Activity => "activity",
+        }
+    }
+}
+
 /// Which field is active in the new session dialog
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NewSessionField {
     Name,
     Path,
+    Url,
 }
 
 /// Which field is active in the new worktree dialog
@@ -152,6 +616,7 @@ pub enum NewWorktreeField {
     Branch,
     Path,
     SessionName,
+    DraftPr,
 }
 
 /// Which field is active in the create pull request dialog
@@ -160,4 +625,21 @@ pub enum CreatePullRequestField {
     Title,
     Body,
     BaseBranch,
+    Issue,
+    Draft,
+}
+
+/// Which field is active in the commit dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitField {
+    Message,
+    Author,
+}
+
+/// Which field is active in the create tag dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreateTagField {
+    Name,
+    Message,
+    Push,
 }
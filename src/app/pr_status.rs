@@ -0,0 +1,225 @@
+//! Pull request state for the session list
+//!
+//! With `pr_column` enabled, the pull request of each session's branch is
+//! looked up on background threads, at most `background_jobs` at a time,
+//! and cached per repository and branch. Rows show what is cached and update
+//! as results arrive; entries older than `pr_column_interval` are looked up
+//! again.
+//!
+//! Lookups that fail transiently are retried with backoff: on the lookup
+//! thread for the list, and from the main loop (`PrRetry`) for the action
+//! menu, so the UI never waits on one.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use crate::git::{self, PullRequestInfo};
+use crate::limiter;
+
+/// Pause after each lookup before its slot is freed, to stay well inside
+/// the host's rate limits
+const LOOKUP_GAP: Duration = Duration::from_millis(500);
+
+/// Most attempts at a lookup that keeps failing transiently
+const LOOKUP_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubled for each further retry
+const RETRY_DELAY: Duration = Duration::from_millis(300);
+
+/// Repository working directory and branch
+type Key = (PathBuf, String);
+
+/// A finished lookup, as sent back by its thread
+type Lookup = (Key, Option<PullRequestInfo>);
+
+/// A lookup result; `None` when the branch has no pull request
+#[derive(Debug)]
+struct Entry {
+    info: Option<PullRequestInfo>,
+    fetched: Instant,
+}
+
+/// Cached pull request state, filled in by background lookups
+#[derive(Debug, Default)]
+pub struct PrStatus {
+    entries: HashMap<Key, Entry>,
+    /// Lookups started and not yet answered
+    pending: HashSet<Key>,
+    /// Channel the lookup threads answer on, created on the first lookup
+    results: Option<(Sender<Lookup>, Receiver<Lookup>)>,
+}
+
+impl PrStatus {
+    /// Cached pull request of `branch` in the repository at `workdir`
+    pub fn get(&self, workdir: &Path, branch: &str) -> Option<&PullRequestInfo> {
+        self.entries
+            .get(&(workdir.to_path_buf(), branch.to_string()))
+            .and_then(|entry| entry.info.as_ref())
+    }
+
+    /// Queue a lookup unless one is pending or the cached entry is younger
+    /// than `max_age`
+    pub fn request(&mut self, workdir: &Path, branch: &str, max_age: Duration) {
+        let key = (workdir.to_path_buf(), branch.to_string());
+        if self.pending.contains(&key)
+            || self
+                .entries
+                .get(&key)
+                .is_some_and(|entry| entry.fetched.elapsed() < max_age)
+        {
+            return;
+        }
+
+        let (results, _) = self.results.get_or_insert_with(mpsc::channel);
+        let results = results.clone();
+        self.pending.insert(key.clone());
+
+        std::thread::spawn(move || {
+            let _permit = limiter::acquire();
+            let info = git::detect_provider(&key.0)
+                .filter(|provider| provider.is_available())
+                .and_then(|provider| {
+                    // This thread may wait, so transient failures are retried here
+                    let mut delay = RETRY_DELAY;
+                    for _ in 1..LOOKUP_ATTEMPTS {
+                        match provider.get_pull_request_info(&key.0) {
+                            Ok(info) => return info,
+                            Err(_) => std::thread::sleep(delay),
+                        }
+                        delay *= 2;
+                    }
+                    provider.get_pull_request_info(&key.0).ok().flatten()
+                });
+            let _ = results.send((key, info));
+            std::thread::sleep(LOOKUP_GAP);
+        });
+    }
+
+    /// Take in finished lookups
+    pub fn poll(&mut self) {
+        let Some((_, results)) = &self.results else {
+            return;
+        };
+        let finished: Vec<Lookup> = results.try_iter().collect();
+        for (key, info) in finished {
+            self.pending.remove(&key);
+            self.store_key(key, info);
+        }
+    }
+
+    /// Record a result fetched elsewhere (e.g. for the action menu)
+    pub fn store(&mut self, workdir: &Path, branch: &str, info: Option<PullRequestInfo>) {
+        self.store_key((workdir.to_path_buf(), branch.to_string()), info);
+    }
+
+    /// Drop cached entries for the repository containing `path`, e.g. after
+    /// changing its PR
+    pub fn forget(&mut self, path: &Path) {
+        self.entries
+            .retain(|(workdir, _), _| !path.starts_with(workdir));
+    }
+
+    /// Drop all cached entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn store_key(&mut self, key: Key, info: Option<PullRequestInfo>) {
+        let fetched = Instant::now();
+        self.entries.insert(key, Entry { info, fetched });
+    }
+}
+
+/// A transiently failed lookup for the action menu, due to be retried
+#[derive(Debug)]
+pub struct PrRetry {
+    key: Key,
+    /// Failed lookups so far
+    attempts: u32,
+    due: Instant,
+}
+
+impl PrRetry {
+    /// Schedule a retry after a failed lookup of `branch` in `workdir`,
+    /// counting on from `previous` if it was for the same branch. `None`
+    /// once the attempts are used up.
+    pub fn after(previous: Option<&PrRetry>, workdir: &Path, branch: &str) -> Option<Self> {
+        let key = (workdir.to_path_buf(), branch.to_string());
+        let attempts = previous
+            .filter(|retry| retry.key == key)
+            .map_or(0, |retry| retry.attempts)
+            + 1;
+        (attempts < LOOKUP_ATTEMPTS).then(|| Self {
+            key,
+            attempts,
+            due: Instant::now() + RETRY_DELAY * 2u32.pow(attempts - 1),
+        })
+    }
+
+    /// Whether the retry should run now
+    pub fn is_due(&self) -> bool {
+        Instant::now() >= self.due
+    }
+
+    /// Whether this retries the lookup of `branch` in `workdir`
+    pub fn is_for(&self, workdir: &Path, branch: &str) -> bool {
+        self.key.0 == workdir && self.key.1 == branch
+    }
+}
+
+/// Compact label for the list, e.g. `PR#123 open`
+pub fn pr_label(info: &PullRequestInfo) -> String {
+    format!("PR#{} {}", info.number, info.state.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_forget() {
+        let info = PullRequestInfo {
+            number: 123,
+            state: "OPEN".to_string(),
+            mergeable: "MERGEABLE".to_string(),
+            commits: None,
+            additions: None,
+            deletions: None,
+        };
+        assert_eq!(pr_label(&info), "PR#123 open");
+
+        let mut status = PrStatus::default();
+        let repo = Path::new("/repo");
+        status.store(repo, "feat", Some(info));
+        status.store(repo, "main", None);
+        assert_eq!(status.get(repo, "feat").map(|i| i.number), Some(123));
+        assert!(status.get(repo, "main").is_none());
+        assert!(status.get(Path::new("/other"), "feat").is_none());
+
+        // A fresh entry is not looked up again
+        status.request(repo, "feat", Duration::from_secs(60));
+        assert!(status.pending.is_empty());
+
+        status.forget(&repo.join("src"));
+        assert!(status.get(repo, "feat").is_none());
+    }
+
+    #[test]
+    fn test_retry_attempts() {
+        let repo = Path::new("/repo");
+        let first = PrRetry::after(None, repo, "feat").unwrap();
+        assert!(first.is_for(repo, "feat"));
+        assert!(!first.is_due());
+
+        let second = PrRetry::after(Some(&first), repo, "feat").unwrap();
+        assert_eq!(second.attempts, 2);
+        assert!(second.due > first.due);
+        // The last attempt is not followed by another retry
+        assert!(PrRetry::after(Some(&second), repo, "feat").is_none());
+        // Another branch starts counting again
+        let other = PrRetry::after(Some(&second), repo, "main").unwrap();
+        assert_eq!(other.attempts, 1);
+    }
+}
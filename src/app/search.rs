@@ -0,0 +1,110 @@
+//! Search within the preview, and matching of the session filter
+//!
+//! Preview matching is ASCII case-insensitive and done on the preview text
+//! with ANSI styling removed, one line at a time. The session filter matches
+//! case-insensitively by Unicode lowercasing (`filter_ranges`).
+
+use std::ops::Range;
+
+/// An active search in the preview buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewSearch {
+    /// Text being searched for
+    pub query: String,
+    /// Indices of the preview lines containing the query, top to bottom
+    pub matches: Vec<usize>,
+    /// Index into `matches` of the match jumped to last
+    pub current: usize,
+}
+
+impl PreviewSearch {
+    /// Preview line of the current match
+    pub fn current_line(&self) -> Option<usize> {
+        self.matches.get(self.current).copied()
+    }
+}
+
+/// Indices of the lines containing `query`
+pub fn matching_lines(lines: &[String], query: &str) -> Vec<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !match_ranges(line, query).is_empty())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Byte ranges of the non-overlapping occurrences of `query` in `line`
+pub fn match_ranges(line: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    // ASCII lowercasing keeps byte offsets, so ranges index into `line`
+    let haystack = line.to_ascii_lowercase();
+    let needle = query.to_ascii_lowercase();
+    haystack
+        .match_indices(&needle)
+        .map(|(start, m)| start..start + m.len())
+        .collect()
+}
+
+/// Byte ranges of the non-overlapping occurrences of `query` in `text`,
+/// compared Unicode-lowercased as the session filter does. Ranges index into
+/// `text` and cover whole characters, even where lowercasing changes a
+/// character's length.
+pub fn filter_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    // The original character's byte range for each byte of the lowercased text
+    let mut lowered = String::new();
+    let mut origin = Vec::new();
+    for (start, c) in text.char_indices() {
+        let range = start..start + c.len_utf8();
+        for lower in c.to_lowercase() {
+            lowered.push(lower);
+            origin.extend(std::iter::repeat_n(range.clone(), lower.len_utf8()));
+        }
+    }
+
+    lowered
+        .match_indices(&query.to_lowercase())
+        .map(|(start, m)| origin[start].start..origin[start + m.len() - 1].end)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_ranges_case_insensitive() {
+        assert_eq!(match_ranges("Error: error", "ERROR"), vec![0..5, 7..12]);
+        assert_eq!(match_ranges("héllo wörld", "wö"), vec![7..10]);
+        assert!(match_ranges("anything", "").is_empty());
+    }
+
+    #[test]
+    fn test_filter_ranges_unicode() {
+        assert_eq!(filter_ranges("Alpha-Feat", "FEAT"), vec![6..10]);
+        // Non-ASCII uppercase only matches with Unicode lowercasing
+        assert_eq!(filter_ranges("ÉTÉ-fix", "été"), vec![0..5]);
+        // 'İ' lowercases to two characters, still mapped back onto 'İ'
+        assert_eq!(filter_ranges("xİy", "y"), vec![3..4]);
+        assert!(filter_ranges("alpha", "").is_empty());
+        assert!(filter_ranges("alpha", "beta").is_empty());
+    }
+
+    #[test]
+    fn test_matching_lines() {
+        let lines = vec![
+            "cargo build".to_string(),
+            "".to_string(),
+            "cargo test".to_string(),
+        ];
+        assert_eq!(matching_lines(&lines, "Cargo"), vec![0, 2]);
+        assert!(matching_lines(&lines, "clippy").is_empty());
+    }
+}
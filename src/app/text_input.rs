@@ -0,0 +1,201 @@
+//! Editable single-field text buffer
+//!
+//! Holds the text of a dialog field together with a cursor, and provides the
+//! editing operations bound to keys in `input.rs`.
+
+/// Text being edited in a dialog field
+///
+/// The cursor is a character index into the value, so it is always on a
+/// `char` boundary regardless of multi-byte input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    /// Create an empty input
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current text
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// Whether the text is empty
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Whether the cursor is after the last character
+    pub fn is_at_end(&self) -> bool {
+        self.cursor == self.value.chars().count()
+    }
+
+    /// Text before and after the cursor
+    pub fn split_at_cursor(&self) -> (&str, &str) {
+        self.value.split_at(self.byte_index())
+    }
+
+    /// Replace the text and move the cursor to the end
+    pub fn set(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.chars().count();
+    }
+
+    /// Insert a character at the cursor
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte_index();
+        self.value.insert(at, c);
+        self.cursor += 1;
+    }
+
+    /// Insert a string at the cursor
+    pub fn insert_str(&mut self, s: &str) {
+        let at = self.byte_index();
+        self.value.insert_str(at, s);
+        self.cursor += s.chars().count();
+    }
+
+    /// Delete the character before the cursor. Returns true if text changed.
+    pub fn backspace(&mut self) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        let at = self.byte_index();
+        self.value.remove(at);
+        true
+    }
+
+    /// Delete the character under the cursor. Returns true if text changed.
+    pub fn delete(&mut self) -> bool {
+        if self.is_at_end() {
+            return false;
+        }
+        let at = self.byte_index();
+        self.value.remove(at);
+        true
+    }
+
+    /// Delete the word before the cursor, like Ctrl-W in a shell.
+    /// Returns true if text changed.
+    pub fn delete_word(&mut self) -> bool {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut start = self.cursor;
+        while start > 0 && chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        while start > 0 && !chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        if start == self.cursor {
+            return false;
+        }
+
+        let from = self.byte_index_of(start);
+        let to = self.byte_index();
+        self.value.replace_range(from..to, "");
+        self.cursor = start;
+        true
+    }
+
+    /// Move the cursor one character left
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Move the cursor one character right
+    pub fn move_right(&mut self) {
+        if !self.is_at_end() {
+            self.cursor += 1;
+        }
+    }
+
+    /// Move the cursor to the start of the text
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Move the cursor to the end of the text
+    pub fn move_end(&mut self) {
+        self.cursor = self.value.chars().count();
+    }
+
+    fn byte_index(&self) -> usize {
+        self.byte_index_of(self.cursor)
+    }
+
+    fn byte_index_of(&self, char_index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(char_index)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+}
+
+impl From<String> for TextInput {
+    fn from(value: String) -> Self {
+        let mut input = Self::new();
+        input.set(value);
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_at_cursor() {
+        let mut input = TextInput::from("helo".to_string());
+        input.move_left();
+        input.insert('l');
+        assert_eq!(input.as_str(), "hello");
+        assert_eq!(input.split_at_cursor(), ("hell", "o"));
+
+        input.move_home();
+        input.insert_str("¡¡");
+        assert_eq!(input.as_str(), "¡¡hello");
+        assert_eq!(input.split_at_cursor(), ("¡¡", "hello"));
+    }
+
+    #[test]
+    fn test_backspace_and_delete() {
+        let mut input = TextInput::from("añb".to_string());
+        input.move_left();
+        assert!(input.backspace());
+        assert_eq!(input.as_str(), "ab");
+        assert!(input.delete());
+        assert_eq!(input.as_str(), "a");
+        assert!(!input.delete());
+
+        input.move_home();
+        assert!(!input.backspace());
+    }
+
+    #[test]
+    fn test_delete_word() {
+        let mut input = TextInput::from("fix the  bug".to_string());
+        assert!(input.delete_word());
+        assert_eq!(input.as_str(), "fix the  ");
+        assert!(input.delete_word());
+        assert_eq!(input.as_str(), "fix ");
+
+        input.move_home();
+        assert!(!input.delete_word());
+    }
+
+    #[test]
+    fn test_cursor_bounds() {
+        let mut input = TextInput::from("ab".to_string());
+        input.move_right();
+        assert!(input.is_at_end());
+        input.move_home();
+        input.move_left();
+        assert_eq!(input.split_at_cursor(), ("", "ab"));
+    }
+}
@@ -0,0 +1,257 @@
+//! Command-line interface
+//!
+//! Parses command-line arguments and runs the non-interactive subcommands.
+//! With no arguments the TUI is started.
+
+use anyhow::Result;
+
+use crate::app::{canonical_path, expand_path};
+use crate::config::Config;
+use crate::tmux::Tmux;
+
+const USAGE: &str = "\
+Usage: claude-tmux [OPTIONS]
+       claude-tmux --attach-if-exists-else-create <NAME> <PATH> [--no-claude] [--read-only]
+
+Options:
+  --profile <NAME>
+                  Use the config profile ~/.config/claude-tmux/profiles/NAME.toml
+                  (also set by CLAUDE_TMUX_PROFILE)
+  --attach-if-exists-else-create <NAME> <PATH>
+                  Switch to (or attach) session NAME, creating it in PATH first
+                  if it does not exist
+  --no-claude     Do not start claude in a newly created session (the command
+                  sent is startup_command, or CLAUDE_TMUX_CMD if set)
+  --read-only     Switch or attach as a read-only client
+  --quit-after-action
+                  Quit as soon as an action succeeds (errors keep it open)
+  --presentation  Read-only mode for demos: only navigating, previewing,
+                  viewing and switching; actions that change anything are
+                  refused
+  --debug         Enable debugging aids, e.g. D shows how status detection
+                  sees the selected session's panes
+  -h, --help      Print this help
+  -V, --version   Print the version";
+
+/// What the program should do, as requested on the command line
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Run the interactive TUI
+    Tui,
+    /// Switch to or attach a session, creating it first if needed
+    AttachOrCreate {
+        name: String,
+        path: String,
+        start_claude: bool,
+        read_only: bool,
+    },
+    /// Print usage and exit
+    Help,
+    /// Print the version and exit
+    Version,
+}
+
+/// Parsed command-line arguments
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    /// Config profile from `--profile`
+    pub profile: Option<String>,
+    /// Whether `--quit-after-action` was given
+    pub quit_after_action: bool,
+    /// Whether `--presentation` was given
+    pub presentation: bool,
+    /// Whether `--debug` was given
+    pub debug: bool,
+}
+
+impl Cli {
+    /// Parse the process arguments
+    pub fn from_env() -> Result<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Parse an argument list (without the program name)
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut command = Command::Tui;
+        let mut start_claude = true;
+        let mut read_only = false;
+        let mut profile = None;
+        let mut quit_after_action = false;
+        let mut presentation = false;
+        let mut debug = false;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--attach-if-exists-else-create" => {
+                    let (Some(name), Some(path)) = (args.next(), args.next()) else {
+                        anyhow::bail!("{} requires <NAME> <PATH>\n\n{}", arg, USAGE);
+                    };
+                    command = Command::AttachOrCreate {
+                        name,
+                        path,
+                        start_claude: true,
+                        read_only: false,
+                    };
+                }
+                "--profile" => {
+                    let Some(name) = args.next() else {
+                        anyhow::bail!("{} requires <NAME>\n\n{}", arg, USAGE);
+                    };
+                    profile = Some(name);
+                }
+                "--no-claude" => start_claude = false,
+                "--read-only" => read_only = true,
+                "--quit-after-action" => quit_after_action = true,
+                "--presentation" => presentation = true,
+                "--debug" => debug = true,
+                "-h" | "--help" => command = Command::Help,
+                "-V" | "--version" => command = Command::Version,
+                other => anyhow::bail!("Unknown argument '{}'\n\n{}", other, USAGE),
+            }
+        }
+
+        if let Command::AttachOrCreate {
+            start_claude: ref mut start,
+            read_only: ref mut ro,
+            ..
+        } = command
+        {
+            *start = start_claude;
+            *ro = read_only;
+        }
+
+        Ok(Self {
+            command,
+            profile,
+            quit_after_action,
+            presentation,
+            debug,
+        })
+    }
+}
+
+/// Run a non-interactive command. Returns `false` if the TUI should start.
+pub fn run(command: &Command) -> Result<bool> {
+    match command {
+        Command::Tui => Ok(false),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+        Command::Version => {
+            println!("claude-tmux {}", env!("CARGO_PKG_VERSION"));
+            Ok(true)
+        }
+        Command::AttachOrCreate {
+            name,
+            path,
+            start_claude,
+            read_only,
+        } => {
+            attach_or_create(name, path, *start_claude, *read_only)?;
+            Ok(true)
+        }
+    }
+}
+
+/// Switch to session `name`, creating it in `path` first if it does not exist
+fn attach_or_create(name: &str, path: &str, start_claude: bool, read_only: bool) -> Result<()> {
+    if Tmux::session_exists(name) {
+        println!("Session '{}' exists", name);
+    } else {
+        let dir = canonical_path(&expand_path(path));
+        let config = Config::load()?.for_path(&dir)?;
+        let startup_command =
+            (start_claude && config.start_claude).then_some(config.startup_command.as_str());
+        Tmux::new_session(name, &dir, startup_command)?;
+        println!("Created session '{}' in {}", name, path);
+    }
+
+    if std::env::var_os("TMUX").is_some() {
+        println!("Switching to '{}'", name);
+        if read_only {
+            Tmux::switch_to_session_read_only(name)
+        } else {
+            Tmux::switch_to_session(name)
+        }
+    } else {
+        println!("Attaching to '{}'", name);
+        Tmux::attach_session(name, read_only)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Command {
+        Cli::parse(args.iter().map(|s| s.to_string()))
+            .unwrap()
+            .command
+    }
+
+    #[test]
+    fn test_no_args_runs_tui() {
+        assert_eq!(parse(&[]), Command::Tui);
+    }
+
+    #[test]
+    fn test_attach_or_create() {
+        assert_eq!(
+            parse(&["--attach-if-exists-else-create", "proj", "~/proj"]),
+            Command::AttachOrCreate {
+                name: "proj".to_string(),
+                path: "~/proj".to_string(),
+                start_claude: true,
+                read_only: false,
+            }
+        );
+        assert_eq!(
+            parse(&["--no-claude", "--attach-if-exists-else-create", "p", "/p"]),
+            Command::AttachOrCreate {
+                name: "p".to_string(),
+                path: "/p".to_string(),
+                start_claude: false,
+                read_only: false,
+            }
+        );
+        assert_eq!(
+            parse(&["--attach-if-exists-else-create", "p", "/p", "--read-only"]),
+            Command::AttachOrCreate {
+                name: "p".to_string(),
+                path: "/p".to_string(),
+                start_claude: true,
+                read_only: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_profile() {
+        let cli = Cli::parse(["--profile", "work"].map(String::from)).unwrap();
+        assert_eq!(cli.command, Command::Tui);
+        assert_eq!(cli.profile.as_deref(), Some("work"));
+        assert!(!cli.debug);
+        assert!(!cli.presentation);
+        assert!(Cli::parse(["--debug".to_string()]).unwrap().debug);
+        assert!(
+            Cli::parse(["--presentation".to_string()])
+                .unwrap()
+                .presentation
+        );
+        assert!(
+            Cli::parse(["--quit-after-action".to_string()])
+                .unwrap()
+                .quit_after_action
+        );
+        assert!(Cli::parse(["--profile".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert!(Cli::parse(["--bogus".to_string()]).is_err());
+        assert!(Cli::parse(["--attach-if-exists-else-create".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! System clipboard access
+//!
+//! Shells out to the platform clipboard tool (pbcopy, wl-copy, xclip, xsel),
+//! falling back to the tmux paste buffer, which tmux forwards to the outer
+//! terminal's clipboard when `set-clipboard` is enabled.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+/// Copy text to the system clipboard
+pub fn copy(text: &str) -> Result<()> {
+    for (program, args) in candidates() {
+        if pipe_to(program, args, text).is_ok() {
+            return Ok(());
+        }
+    }
+
+    anyhow::bail!("No clipboard backend available (install wl-copy, xclip or xsel)")
+}
+
+/// Clipboard commands to try, in order of preference for this platform
+fn candidates() -> Vec<(&'static str, &'static [&'static str])> {
+    let mut candidates: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+
+    if cfg!(target_os = "macos") {
+        candidates.push(("pbcopy", &[]));
+    }
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        candidates.push(("wl-copy", &[]));
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        candidates.push(("xclip", &["-selection", "clipboard"]));
+        candidates.push(("xsel", &["--clipboard", "--input"]));
+    }
+    candidates.push(("tmux", &["load-buffer", "-w", "-"]));
+
+    candidates
+}
+
+/// Run a command and write `text` to its stdin
+fn pipe_to(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+
+    child
+        .stdin
+        .take()
+        .context("Failed to open stdin")?
+        .write_all(text.as_bytes())
+        .with_context(|| format!("Failed to write to {}", program))?;
+
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("{} exited with {}", program, status);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Session color tags
+//!
+//! A color tag groups related sessions visually: the session's name is drawn
+//! in the tag's color. Tags are keyed by session name and persisted to
+//! `~/.local/share/claude-tmux/color_tags.toml`, follow renames, and are
+//! dropped for sessions that no longer exist when claude-tmux starts.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Palette a tag is picked from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl TagColor {
+    /// Every color, in picker order
+    pub const ALL: [TagColor; 6] = [
+        Self::Red,
+        Self::Green,
+        Self::Yellow,
+        Self::Blue,
+        Self::Magenta,
+        Self::Cyan,
+    ];
+
+    /// Name shown in the picker and stored in the file
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Red => "red",
+            Self::Green => "green",
+            Self::Yellow => "yellow",
+            Self::Blue => "blue",
+            Self::Magenta => "magenta",
+            Self::Cyan => "cyan",
+        }
+    }
+}
+
+/// Color tags by session name
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ColorTags {
+    #[serde(default)]
+    tags: BTreeMap<String, TagColor>,
+}
+
+impl ColorTags {
+    /// Location of the persisted tags file
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("claude-tmux/color_tags.toml"))
+    }
+
+    /// Load the persisted tags, or none if there is no file yet
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("Invalid color tags {}", path.display()))
+    }
+
+    /// Write the tags to their file, creating the directory if needed
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let contents = toml::to_string(self).context("Failed to serialize color tags")?;
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Tag of the session, if any
+    pub fn get(&self, session: &str) -> Option<TagColor> {
+        self.tags.get(session).copied()
+    }
+
+    /// Tag the session, or remove its tag with `None`
+    pub fn set(&mut self, session: &str, color: Option<TagColor>) {
+        match color {
+            Some(color) => {
+                self.tags.insert(session.to_string(), color);
+            }
+            None => {
+                self.tags.remove(session);
+            }
+        }
+    }
+
+    /// Move a renamed session's tag to its new name. Returns true if it
+    /// had one.
+    pub fn rename(&mut self, old: &str, new: &str) -> bool {
+        match self.tags.remove(old) {
+            Some(color) => {
+                self.tags.insert(new.to_string(), color);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop the tags of sessions not in `sessions`. Returns true if any
+    /// were dropped.
+    pub fn prune<'a>(&mut self, sessions: impl IntoIterator<Item = &'a str>) -> bool {
+        let existing: Vec<&str> = sessions.into_iter().collect();
+        let before = self.tags.len();
+        self.tags
+            .retain(|name, _| existing.contains(&name.as_str()));
+        self.tags.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_and_prune() {
+        let mut tags = ColorTags::default();
+        tags.set("api", Some(TagColor::Blue));
+        tags.set("web", Some(TagColor::Red));
+        tags.set("web", None);
+        assert_eq!(tags.get("web"), None);
+
+        assert!(tags.rename("api", "api-v2"));
+        assert!(!tags.rename("api", "api-v3"));
+        assert_eq!(tags.get("api"), None);
+        assert_eq!(tags.get("api-v2"), Some(TagColor::Blue));
+
+        tags.set("old", Some(TagColor::Cyan));
+        assert!(tags.prune(["api-v2", "docs"]));
+        assert!(!tags.prune(["api-v2"]));
+        assert_eq!(tags.get("old"), None);
+
+        let toml = toml::to_string(&tags).unwrap();
+        assert_eq!(toml, "[tags]\napi-v2 = \"blue\"\n");
+        let loaded: ColorTags = toml::from_str(&toml).unwrap();
+        assert_eq!(loaded.get("api-v2"), Some(TagColor::Blue));
+    }
+}
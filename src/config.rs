@@ -0,0 +1,604 @@
+//! User configuration
+//!
+//! Loads optional settings from `~/.config/claude-tmux/config.toml`.
+//! Every field has a built-in default, so a missing file or a partial
+//! file is fine; only a malformed file is reported as an error.
+//!
+//! A named profile (`--profile <name>` or `CLAUDE_TMUX_PROFILE`) loads
+//! `~/.config/claude-tmux/profiles/<name>.toml` instead, falling back to the
+//! default file when the profile does not exist.
+//!
+//! Repositories can also commit a `.claude-tmux.toml` with project-specific
+//! overrides (`RepoConfig`), which is merged over the global config for
+//! actions scoped to sessions inside that repository. Its `startup_command`
+//! and `start_claude` run commands, so they only apply to repositories
+//! listed in `trusted_repos`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::hooks::HookEvent;
+use crate::session::ClaudeCodeStatus;
+
+/// Default template for new worktree paths: a sibling `repo-branch` directory
+pub const DEFAULT_WORKTREE_PATH_TEMPLATE: &str = "{parent}/{repo}-{branch}";
+
+/// Default template for worktree session names: `repo-branch`
+pub const DEFAULT_WORKTREE_SESSION_NAME_TEMPLATE: &str = "{repo}-{branch}";
+
+/// File name of the per-repository config
+pub const REPO_CONFIG_FILE: &str = ".claude-tmux.toml";
+
+/// Environment variable naming the config profile, overridden by `--profile`
+pub const PROFILE_ENV: &str = "CLAUDE_TMUX_PROFILE";
+
+/// Environment variable that replaces the config file's `startup_command`
+pub const STARTUP_COMMAND_ENV: &str = "CLAUDE_TMUX_CMD";
+
+/// Startup command used when none is configured
+const DEFAULT_STARTUP_COMMAND: &str = "claude";
+
+/// Profile chosen with `--profile`, set once at startup
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// User configuration loaded from the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Template used to suggest the path of a new worktree.
+    ///
+    /// Placeholders: `{parent}` (directory containing the repo), `{repo}`
+    /// (repo directory name), `{branch}` (sanitized branch name). A leading
+    /// `~` expands to the home directory.
+    pub worktree_path_template: String,
+    /// Template used to suggest the session name of a new worktree.
+    ///
+    /// Placeholders: `{repo}` and `{branch}`, as for `worktree_path_template`.
+    pub worktree_session_name_template: String,
+    /// Truncate suggested session names to this many characters (0 for no limit)
+    pub session_name_max_length: Option<usize>,
+    /// Start with the dense session list (no status labels, tighter spacing)
+    pub dense_list: bool,
+    /// Start with sessions grouped under repository headers
+    pub group_by_repo: bool,
+    /// Tint the background of working and waiting rows in the session list
+    pub status_tint: bool,
+    /// Show the metadata, git and PR rows above the action menu
+    pub action_menu_details: bool,
+    /// Command sent to new sessions to start Claude Code
+    pub startup_command: String,
+    /// Whether new sessions start the startup command automatically
+    pub start_claude: bool,
+    /// Look at new sessions shortly after the startup command is sent and
+    /// warn if the shell could not run it
+    pub check_startup_command: bool,
+    /// Base branch for new pull requests (defaults to the remote's default branch)
+    pub default_base_branch: Option<String>,
+    /// Identity for commits, as `Name <email>` (defaults to git config)
+    pub commit_author: Option<String>,
+    /// Repositories whose `.claude-tmux.toml` may set `startup_command` and
+    /// `start_claude`; a leading `~` expands to the home directory
+    pub trusted_repos: Vec<String>,
+    /// Allow committing when nothing is staged, creating an empty commit
+    pub allow_empty_commits: bool,
+    /// Stage all changes before committing when nothing is staged
+    pub auto_stage_on_commit: bool,
+    /// Make Enter switch to sessions as a read-only client
+    pub enter_read_only: bool,
+    /// Quit after the first action that succeeds, for single-shot use
+    pub quit_after_action: bool,
+    /// Ask for confirmation before switching to a session whose Claude is
+    /// working
+    pub confirm_switch_to_working: bool,
+    /// Start filtering when a character not bound to a command is typed
+    pub type_to_filter: bool,
+    /// Key that resets the view (filter, sort, preview, selection)
+    pub reset_key: char,
+    /// Extra keys for the session list, each bound to the built-in key
+    /// whose command it runs, from the `[keymap]` table
+    pub keymap: BTreeMap<String, String>,
+    /// Action identifiers listed first in the action menu, in this order
+    pub action_order: Vec<String>,
+    /// Action identifiers confirmed by typing the session name instead of `y`
+    pub type_to_confirm: Vec<String>,
+    /// Glob patterns (`*`, `?`) of session names hidden from the list
+    /// unless hidden sessions are shown
+    pub hidden_sessions: Vec<String>,
+    /// Which pane's directory is used as the session's working directory
+    pub directory_pane: DirectoryPane,
+    /// What to do once a kill leaves no sessions
+    pub on_last_kill: OnLastKill,
+    /// Order of statuses for the status sort, most urgent first
+    pub status_order: Vec<ClaudeCodeStatus>,
+    /// Keep messages and errors shown until Esc or a newer one, instead of
+    /// clearing them on the next key press
+    pub sticky_messages: bool,
+    /// Show working/waiting counts in the terminal title while running
+    pub terminal_title: bool,
+    /// Refresh the session list when the terminal regains focus
+    pub refresh_on_focus: bool,
+    /// Look up each listed session's pull request in the background and show
+    /// its state in the list (runs `gh`/`glab` for every session)
+    pub pr_column: bool,
+    /// Seconds before a listed pull request state is looked up again
+    pub pr_column_interval: u64,
+    /// Most background lookups (e.g. for `pr_column`) run at once
+    /// (defaults to the number of CPUs)
+    pub background_jobs: Option<usize>,
+    /// Most sessions whose Claude Code status is read up front; beyond
+    /// this, a session's status is read once its row is scrolled into view
+    pub eager_sessions: Option<usize>,
+    /// Keep the action history across runs
+    pub persist_history: bool,
+    /// Heuristics for telling Claude's status from its pane, from the
+    /// `[detection]` table
+    pub detection: Detection,
+    /// Shell commands run after actions complete
+    pub hooks: Hooks,
+    /// Named sequences of actions, from `[[macros]]` tables
+    pub macros: Vec<Macro>,
+    /// How the preview captures panes, by pane command, from
+    /// `[[preview_capture]]` tables; the first match wins
+    pub preview_capture: Vec<PreviewCapture>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            worktree_path_template: DEFAULT_WORKTREE_PATH_TEMPLATE.to_string(),
+            worktree_session_name_template: DEFAULT_WORKTREE_SESSION_NAME_TEMPLATE.to_string(),
+            session_name_max_length: None,
+            dense_list: false,
+            group_by_repo: false,
+            status_tint: false,
+            action_menu_details: true,
+            startup_command: DEFAULT_STARTUP_COMMAND.to_string(),
+            start_claude: true,
+            check_startup_command: false,
+            default_base_branch: None,
+            commit_author: None,
+            trusted_repos: Vec::new(),
+            allow_empty_commits: false,
+            auto_stage_on_commit: false,
+            enter_read_only: false,
+            quit_after_action: false,
+            confirm_switch_to_working: false,
+            type_to_filter: false,
+            reset_key: '0',
+            keymap: BTreeMap::new(),
+            action_order: Vec::new(),
+            type_to_confirm: Vec::new(),
+            hidden_sessions: Vec::new(),
+            directory_pane: DirectoryPane::default(),
+            on_last_kill: OnLastKill::default(),
+            status_order: vec![
+                ClaudeCodeStatus::WaitingInput,
+                ClaudeCodeStatus::Working,
+                ClaudeCodeStatus::Idle,
+                ClaudeCodeStatus::Unknown,
+            ],
+            sticky_messages: false,
+            terminal_title: false,
+            refresh_on_focus: true,
+            pr_column: false,
+            pr_column_interval: 300,
+            background_jobs: None,
+            eager_sessions: None,
+            persist_history: false,
+            detection: Detection::default(),
+            hooks: Hooks::default(),
+            macros: Vec::new(),
+            preview_capture: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Use the named profile for the rest of the run
+    pub fn select_profile(name: &str) {
+        let _ = PROFILE.set(name.to_string());
+    }
+
+    /// The active profile: `--profile`, else `CLAUDE_TMUX_PROFILE`
+    pub fn profile() -> Option<String> {
+        PROFILE
+            .get()
+            .cloned()
+            .or_else(|| std::env::var(PROFILE_ENV).ok())
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Location of a profile's config file
+    /// (`~/.config/claude-tmux/profiles/<name>.toml`)
+    fn profile_path(name: &str) -> Option<PathBuf> {
+        dirs::home_dir().map(|home| {
+            home.join(".config/claude-tmux/profiles")
+                .join(format!("{}.toml", name))
+        })
+    }
+
+    /// The active profile, if its config file does not exist
+    pub fn missing_profile() -> Option<String> {
+        Self::profile().filter(|name| !Self::profile_path(name).is_some_and(|p| p.exists()))
+    }
+
+    /// Location of the config file: the active profile's if it exists,
+    /// else `~/.config/claude-tmux/config.toml`
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = Self::profile()
+            .and_then(|name| Self::profile_path(&name))
+            .filter(|path| path.exists())
+        {
+            return Some(path);
+        }
+        dirs::home_dir().map(|home| home.join(".config/claude-tmux/config.toml"))
+    }
+
+    /// Load the config file, falling back to defaults if it does not exist,
+    /// with `CLAUDE_TMUX_CMD` applied over it
+    pub fn load() -> Result<Self> {
+        let mut config = Self::load_file()?;
+        let env_command = std::env::var(STARTUP_COMMAND_ENV).ok();
+        config.startup_command =
+            effective_startup_command(env_command.as_deref(), &config.startup_command);
+        Ok(config)
+    }
+
+    /// Parse the config file, or the defaults if there is none
+    fn load_file() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("Invalid config {}", path.display()))
+    }
+
+    /// Effective config for `path`: this config merged with the nearest repo config
+    pub fn for_path(&self, path: &Path) -> Result<Self> {
+        match RepoConfig::find(path) {
+            Some(file) => Ok(self.merged_with(&RepoConfig::load(&file)?, self.trusts(&file))),
+            None => Ok(self.clone()),
+        }
+    }
+
+    /// Whether the repo config `file` is in a repository listed in
+    /// `trusted_repos`
+    pub fn trusts(&self, file: &Path) -> bool {
+        let Some(dir) = file.parent().and_then(|dir| dir.canonicalize().ok()) else {
+            return false;
+        };
+        self.trusted_repos.iter().any(|repo| {
+            let path = match (repo.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(repo),
+            };
+            path.canonicalize().is_ok_and(|path| path == dir)
+        })
+    }
+
+    /// Return a copy of this config with the repo-local overrides applied.
+    /// The overrides that run commands are only applied when `trusted`.
+    pub fn merged_with(&self, repo: &RepoConfig, trusted: bool) -> Self {
+        let mut merged = self.clone();
+        if trusted {
+            if let Some(command) = repo
+                .startup_command
+                .as_ref()
+                .filter(|c| !c.trim().is_empty())
+            {
+                merged.startup_command = command.clone();
+            }
+            if let Some(start) = repo.start_claude {
+                merged.start_claude = start;
+            }
+        }
+        if let Some(ref branch) = repo.default_base_branch {
+            merged.default_base_branch = Some(branch.clone());
+        }
+        if let Some(ref template) = repo.worktree_path_template {
+            merged.worktree_path_template = template.clone();
+        }
+        if let Some(ref author) = repo.commit_author {
+            merged.commit_author = Some(author.clone());
+        }
+        merged
+    }
+}
+
+/// Pane whose current path determines a session's working directory (and
+/// so its git context)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirectoryPane {
+    /// The Claude Code pane the row represents, else the first pane
+    #[default]
+    Claude,
+    /// The focused pane of the session's active window
+    Active,
+    /// The first pane of the first window
+    First,
+}
+
+/// What happens when the last session is killed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnLastKill {
+    /// Show the empty list
+    #[default]
+    Stay,
+    /// Quit claude-tmux
+    Quit,
+    /// Open the new session dialog
+    NewSession,
+}
+
+/// Status detection heuristics from the `[detection]` table
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Detection {
+    /// Trailing non-empty lines of the pane that are inspected
+    pub status_lines: usize,
+    /// Text marking Claude's prompt line; a visible prompt means Idle
+    pub prompt_markers: Vec<String>,
+    /// Text of the input box border expected above the prompt
+    pub border_marker: String,
+    /// How many lines above the prompt the border may be; 0 accepts a
+    /// prompt without any border
+    pub border_distance: usize,
+    /// Text showing Claude is asking a question (WaitingInput)
+    pub waiting_markers: Vec<String>,
+}
+
+impl Default for Detection {
+    fn default() -> Self {
+        Self {
+            status_lines: 15,
+            prompt_markers: vec!["❯".to_string()],
+            border_marker: "─".to_string(),
+            border_distance: 1,
+            waiting_markers: vec!["[y/n]".to_string(), "[Y/n]".to_string()],
+        }
+    }
+}
+
+/// Shell hooks from the `[hooks]` table, each run with `sh -c`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pub after_new_session: Option<String>,
+    pub after_commit: Option<String>,
+    pub after_merge_pr: Option<String>,
+}
+
+impl Hooks {
+    /// The command configured for an event, if any
+    pub fn command(&self, event: HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::NewSession => self.after_new_session.as_deref(),
+            HookEvent::Commit => self.after_commit.as_deref(),
+            HookEvent::MergePullRequest => self.after_merge_pr.as_deref(),
+        }
+        .filter(|command| !command.trim().is_empty())
+    }
+}
+
+/// A named sequence of actions from a `[[macros]]` table
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Macro {
+    pub name: String,
+    /// Action identifiers (as in `action_order`), run in order
+    pub steps: Vec<String>,
+    /// Message for `commit` steps, with `{branch}` and `{session}`
+    /// placeholders. Without it the commit dialog asks for one.
+    pub commit_message: Option<String>,
+    /// Ask the usual confirmation before destructive steps
+    pub confirm: bool,
+}
+
+impl Default for Macro {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            steps: Vec::new(),
+            commit_message: None,
+            confirm: true,
+        }
+    }
+}
+
+/// How the preview captures panes whose command matches, from a
+/// `[[preview_capture]]` table
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct PreviewCapture {
+    /// Glob pattern (`*`, `?`) matched against the pane's current command
+    pub command: String,
+    /// Join lines the terminal wrapped (`capture-pane -J`)
+    pub join_lines: bool,
+    /// Keep colors and other escape sequences (`capture-pane -e`)
+    pub escapes: bool,
+    /// Lines of scrollback captured above the visible area
+    pub history: usize,
+    /// Fall back to the saved normal screen while a full-screen program's
+    /// alternate screen is blank
+    pub alternate_screen: bool,
+}
+
+impl Default for PreviewCapture {
+    fn default() -> Self {
+        Self {
+            command: "*".to_string(),
+            join_lines: true,
+            escapes: true,
+            history: 1000,
+            alternate_screen: true,
+        }
+    }
+}
+
+/// Project-specific overrides from a repository's `.claude-tmux.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RepoConfig {
+    pub startup_command: Option<String>,
+    pub start_claude: Option<bool>,
+    pub default_base_branch: Option<String>,
+    pub worktree_path_template: Option<String>,
+    pub commit_author: Option<String>,
+}
+
+impl RepoConfig {
+    /// Find the nearest `.claude-tmux.toml` at or above `path`
+    pub fn find(path: &Path) -> Option<PathBuf> {
+        path.ancestors()
+            .map(|dir| dir.join(REPO_CONFIG_FILE))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Load a repo config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("Invalid config {}", path.display()))
+    }
+}
+
+/// Startup command to use: `env` (the value of `CLAUDE_TMUX_CMD`) if set,
+/// else `configured`, else `claude`; blank values count as unset
+fn effective_startup_command(env: Option<&str>, configured: &str) -> String {
+    env.into_iter()
+        .chain([configured])
+        .map(str::trim)
+        .find(|command| !command.is_empty())
+        .unwrap_or(DEFAULT_STARTUP_COMMAND)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_uses_defaults() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(
+            config.worktree_path_template,
+            DEFAULT_WORKTREE_PATH_TEMPLATE
+        );
+    }
+
+    #[test]
+    fn test_repo_config_overrides_global() {
+        let global = Config::default();
+        let repo: RepoConfig =
+            toml::from_str("startup_command = \"claude --continue\"\nstart_claude = false")
+                .unwrap();
+
+        let merged = global.merged_with(&repo, true);
+        assert_eq!(merged.startup_command, "claude --continue");
+        assert!(!merged.start_claude);
+        // Unset repo fields keep the global value
+        assert_eq!(merged.worktree_path_template, global.worktree_path_template);
+    }
+
+    #[test]
+    fn test_untrusted_repo_config_runs_nothing() {
+        let global = Config::default();
+        let repo: RepoConfig = toml::from_str(
+            "startup_command = \"./setup.sh\"\nstart_claude = false\n\
+             default_base_branch = \"develop\"",
+        )
+        .unwrap();
+
+        let merged = global.merged_with(&repo, false);
+        assert_eq!(merged.startup_command, global.startup_command);
+        assert!(merged.start_claude);
+        // Overrides that run nothing still apply
+        assert_eq!(merged.default_base_branch.as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn test_effective_startup_command() {
+        let command = effective_startup_command;
+        assert_eq!(command(None, "claude --continue"), "claude --continue");
+        assert_eq!(
+            command(Some(" ./bin/claude-wrapper --fast "), "claude"),
+            "./bin/claude-wrapper --fast"
+        );
+        // Blank values are as good as unset
+        assert_eq!(command(Some(""), "claude --continue"), "claude --continue");
+        assert_eq!(command(Some("  "), " "), "claude");
+        assert_eq!(command(None, ""), "claude");
+    }
+
+    #[test]
+    fn test_directory_pane() {
+        let config: Config = toml::from_str("directory_pane = \"active\"").unwrap();
+        assert_eq!(config.directory_pane, DirectoryPane::Active);
+        assert!(toml::from_str::<Config>("directory_pane = \"focused\"").is_err());
+    }
+
+    #[test]
+    fn test_on_last_kill() {
+        assert_eq!(Config::default().on_last_kill, OnLastKill::Stay);
+        let config: Config = toml::from_str("on_last_kill = \"new_session\"").unwrap();
+        assert_eq!(config.on_last_kill, OnLastKill::NewSession);
+        assert!(toml::from_str::<Config>("on_last_kill = \"exit\"").is_err());
+    }
+
+    #[test]
+    fn test_status_order() {
+        let config: Config =
+            toml::from_str("status_order = [\"idle\", \"waiting_input\"]").unwrap();
+        assert_eq!(
+            config.status_order,
+            vec![ClaudeCodeStatus::Idle, ClaudeCodeStatus::WaitingInput]
+        );
+        assert!(toml::from_str::<Config>("status_order = [\"busy\"]").is_err());
+    }
+
+    #[test]
+    fn test_hooks_table() {
+        let config: Config =
+            toml::from_str("[hooks]\nafter_commit = \"make fmt\"\nafter_merge_pr = \"\"").unwrap();
+        assert_eq!(config.hooks.command(HookEvent::Commit), Some("make fmt"));
+        assert_eq!(config.hooks.command(HookEvent::MergePullRequest), None);
+        assert_eq!(config.hooks.command(HookEvent::NewSession), None);
+    }
+
+    #[test]
+    fn test_worktree_template_override() {
+        let config: Config =
+            toml::from_str("worktree_path_template = \"~/worktrees/{repo}/{branch}\"").unwrap();
+        assert_eq!(config.worktree_path_template, "~/worktrees/{repo}/{branch}");
+    }
+
+    #[test]
+    fn test_preview_capture_tables() {
+        let config: Config =
+            toml::from_str("[[preview_capture]]\ncommand = \"tail\"\nescapes = false\nhistory = 0")
+                .unwrap();
+        assert_eq!(
+            config.preview_capture,
+            vec![PreviewCapture {
+                command: "tail".to_string(),
+                escapes: false,
+                history: 0,
+                ..PreviewCapture::default()
+            }]
+        );
+    }
+}
@@ -1,14 +1,15 @@
+use crate::config::Detection;
 use crate::session::ClaudeCodeStatus;
 
 /// Detect Claude Code status when content has NOT changed since the last check.
 ///
 /// Working is determined externally by content-change detection. This function
 /// only distinguishes Idle, WaitingInput, and Unknown from static content.
-pub fn detect_static_status(content: &str) -> ClaudeCodeStatus {
-    if content.contains("[y/n]") || content.contains("[Y/n]") {
+pub fn detect_static_status(content: &str, detection: &Detection) -> ClaudeCodeStatus {
+    if is_waiting(content, detection) {
         return ClaudeCodeStatus::WaitingInput;
     }
-    if has_input_field(content) {
+    if has_input_field(content, detection) {
         return ClaudeCodeStatus::Idle;
     }
     ClaudeCodeStatus::Unknown
@@ -19,8 +20,8 @@ pub fn detect_static_status(content: &str) -> ClaudeCodeStatus {
 /// Used as a fallback when no previous capture is available for comparison.
 /// Prefer content-change detection (see `App::tick_status`) for reliable
 /// Working vs Idle discrimination.
-pub fn detect_status(content: &str) -> ClaudeCodeStatus {
-    if has_input_field(content) {
+pub fn detect_status(content: &str, detection: &Detection) -> ClaudeCodeStatus {
+    if has_input_field(content, detection) {
         if content.contains("ctrl+c") && content.contains("to interrupt") {
             return ClaudeCodeStatus::Working;
         }
@@ -31,27 +32,131 @@ pub fn detect_status(content: &str) -> ClaudeCodeStatus {
         return ClaudeCodeStatus::Working;
     }
 
-    if content.contains("[y/n]") || content.contains("[Y/n]") {
+    if is_waiting(content, detection) {
         return ClaudeCodeStatus::WaitingInput;
     }
 
     ClaudeCodeStatus::Unknown
 }
 
-/// Detect input field: prompt line (❯) with border directly above it.
-fn has_input_field(content: &str) -> bool {
-    let lines: Vec<&str> = content.lines().collect();
+/// Explain what `detect_status` saw in `content`, one note per heuristic,
+/// for the `--debug` detection overlay. Line numbers are 1-based.
+pub fn explain_status(content: &str, detection: &Detection) -> Vec<String> {
+    let mut notes = Vec::new();
 
-    for (i, line) in lines.iter().enumerate() {
-        if line.contains('❯') {
-            // Check if line above is a border
-            if i > 0 && lines[i - 1].contains('─') {
-                return true;
+    match find_input_field(content, detection) {
+        Some((prompt, Some(border))) => notes.push(format!(
+            "input field: prompt on line {}, border on line {}",
+            prompt + 1,
+            border + 1
+        )),
+        Some((prompt, None)) => notes.push(format!(
+            "input field: prompt on line {} (no border needed)",
+            prompt + 1
+        )),
+        None => {
+            let prompts: Vec<usize> = prompt_lines(content, detection).collect();
+            if prompts.is_empty() {
+                notes.push(format!(
+                    "input field: no prompt marker ({})",
+                    detection.prompt_markers.join(" ")
+                ));
+            } else {
+                let lines: Vec<String> = prompts.iter().map(|i| (i + 1).to_string()).collect();
+                notes.push(format!(
+                    "input field: prompt on line {} but no '{}' within {} line(s) above",
+                    lines.join(", "),
+                    detection.border_marker,
+                    detection.border_distance
+                ));
             }
         }
     }
 
-    false
+    if content.contains("ctrl+c") && content.contains("to interrupt") {
+        notes.push("interrupt hint: found".to_string());
+    } else {
+        notes.push("interrupt hint: not found".to_string());
+    }
+
+    match detection
+        .waiting_markers
+        .iter()
+        .find(|marker| content.contains(marker.as_str()))
+    {
+        Some(marker) => notes.push(format!("waiting marker: '{}' found", marker)),
+        None => notes.push("waiting marker: none found".to_string()),
+    }
+
+    notes
+}
+
+/// Shell messages printed when a command cannot be started (bash/zsh, fish,
+/// dash, and a missing executable path)
+const LAUNCH_FAILURES: &[&str] = &[
+    "command not found",
+    "Unknown command",
+    ": not found",
+    "No such file or directory",
+];
+
+/// Find the line of a freshly started pane reporting that its startup
+/// command could not be launched, e.g. `zsh: command not found: claude`.
+pub fn detect_launch_failure(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| LAUNCH_FAILURES.iter().any(|failure| line.contains(failure)))
+}
+
+/// Detect a question: any of the waiting markers, e.g. `[y/n]`
+fn is_waiting(content: &str, detection: &Detection) -> bool {
+    detection
+        .waiting_markers
+        .iter()
+        .any(|marker| content.contains(marker.as_str()))
+}
+
+/// Detect input field: prompt line (❯) with a border at most
+/// `border_distance` lines above it (by default, directly above).
+fn has_input_field(content: &str, detection: &Detection) -> bool {
+    find_input_field(content, detection).is_some()
+}
+
+/// Indices of the first prompt line with a border close enough above it,
+/// and of that border (`None` when `border_distance` is 0)
+fn find_input_field(content: &str, detection: &Detection) -> Option<(usize, Option<usize>)> {
+    let lines: Vec<&str> = content.lines().collect();
+
+    for i in prompt_lines(content, detection) {
+        if detection.border_distance == 0 {
+            return Some((i, None));
+        }
+        // Check the lines above for a border, nearest first
+        let start = i.saturating_sub(detection.border_distance);
+        if let Some(border) = (start..i)
+            .rev()
+            .find(|&j| lines[j].contains(detection.border_marker.as_str()))
+        {
+            return Some((i, Some(border)));
+        }
+    }
+
+    None
+}
+
+/// Indices of the lines containing a prompt marker
+fn prompt_lines<'a>(
+    content: &'a str,
+    detection: &'a Detection,
+) -> impl Iterator<Item = usize> + 'a {
+    content.lines().enumerate().filter_map(|(i, line)| {
+        detection
+            .prompt_markers
+            .iter()
+            .any(|marker| line.contains(marker.as_str()))
+            .then_some(i)
+    })
 }
 
 #[cfg(test)]
@@ -62,32 +167,129 @@ mod tests {
     fn test_working() {
         // Border directly above prompt
         let content = "* (ctrl+c to interrupt)\n─────\n❯ hello";
-        assert_eq!(detect_status(content), ClaudeCodeStatus::Working);
+        assert_eq!(
+            detect_status(content, &Detection::default()),
+            ClaudeCodeStatus::Working
+        );
     }
 
     #[test]
     fn test_idle() {
         // Border directly above prompt
         let content = "● Done\n─────\n❯ hello";
-        assert_eq!(detect_status(content), ClaudeCodeStatus::Idle);
+        assert_eq!(
+            detect_status(content, &Detection::default()),
+            ClaudeCodeStatus::Idle
+        );
     }
 
     #[test]
     fn test_no_border_above_prompt() {
         // Border exists but not directly above prompt - should be unknown
         let content = "─────\nsome text\n❯ hello";
-        assert_eq!(detect_status(content), ClaudeCodeStatus::Unknown);
+        assert_eq!(
+            detect_status(content, &Detection::default()),
+            ClaudeCodeStatus::Unknown
+        );
     }
 
     #[test]
     fn test_waiting_input() {
         let content = "Delete files? [y/n]";
-        assert_eq!(detect_status(content), ClaudeCodeStatus::WaitingInput);
+        assert_eq!(
+            detect_status(content, &Detection::default()),
+            ClaudeCodeStatus::WaitingInput
+        );
+    }
+
+    #[test]
+    fn test_detect_launch_failure() {
+        let content = "~/proj $ claude\nzsh: command not found: claude\n~/proj $";
+        assert_eq!(
+            detect_launch_failure(content),
+            Some("zsh: command not found: claude")
+        );
+        assert_eq!(
+            detect_launch_failure("fish: Unknown command: claude"),
+            Some("fish: Unknown command: claude")
+        );
+        assert_eq!(detect_launch_failure("~/proj $ claude\n╭───╮\n│ ❯ │"), None);
+    }
+
+    #[test]
+    fn test_borderline_captures() {
+        // Status line between the border and the prompt
+        let gap = "● Done\n─────\n  ⎿ tip: press tab\n❯ hello";
+        assert_eq!(
+            detect_status(gap, &Detection::default()),
+            ClaudeCodeStatus::Unknown
+        );
+        let loose = Detection {
+            border_distance: 2,
+            ..Detection::default()
+        };
+        assert_eq!(detect_status(gap, &loose), ClaudeCodeStatus::Idle);
+
+        // Borderless prompt with a different marker, e.g. an older UI
+        let plain = "● Done\n> hello";
+        assert_eq!(
+            detect_status(plain, &Detection::default()),
+            ClaudeCodeStatus::Unknown
+        );
+        let plain_prompt = Detection {
+            prompt_markers: vec!["> ".to_string()],
+            border_distance: 0,
+            ..Detection::default()
+        };
+        assert_eq!(detect_status(plain, &plain_prompt), ClaudeCodeStatus::Idle);
+        assert_eq!(
+            detect_static_status(plain, &plain_prompt),
+            ClaudeCodeStatus::Idle
+        );
+
+        // A question phrased without [y/n]
+        let question = "Do you want to proceed?\n  1. Yes\n  2. No";
+        assert_eq!(
+            detect_static_status(question, &Detection::default()),
+            ClaudeCodeStatus::Unknown
+        );
+        let asking = Detection {
+            waiting_markers: vec!["Do you want to proceed?".to_string()],
+            ..Detection::default()
+        };
+        assert_eq!(
+            detect_static_status(question, &asking),
+            ClaudeCodeStatus::WaitingInput
+        );
+    }
+
+    #[test]
+    fn test_explain_status() {
+        let detection = Detection::default();
+        assert_eq!(
+            explain_status("● Done\n─────\n❯ hello", &detection),
+            vec![
+                "input field: prompt on line 3, border on line 2",
+                "interrupt hint: not found",
+                "waiting marker: none found",
+            ]
+        );
+        assert_eq!(
+            explain_status("─────\nsome text\n❯ hello\nOk? [y/n]", &detection)[..1],
+            ["input field: prompt on line 3 but no '─' within 1 line(s) above"]
+        );
+        assert_eq!(
+            explain_status("random stuff", &detection)[0],
+            "input field: no prompt marker (❯)"
+        );
     }
 
     #[test]
     fn test_unknown() {
         let content = "random stuff";
-        assert_eq!(detect_status(content), ClaudeCodeStatus::Unknown);
+        assert_eq!(
+            detect_status(content, &Detection::default()),
+            ClaudeCodeStatus::Unknown
+        );
     }
 }
@@ -0,0 +1,51 @@
+//! External editor for long dialog text
+//!
+//! Opens `$VISUAL` (or `$EDITOR`, falling back to `vi`) on a temporary file
+//! seeded with the field's current text and reads the saved file back. The
+//! caller is responsible for handing the terminal over to the editor while
+//! it runs.
+
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+/// Edit `initial` in the user's editor and return the saved text, without
+/// its trailing newlines
+pub fn edit(initial: &str) -> Result<String> {
+    let path = std::env::temp_dir().join(format!("claude-tmux-{}.md", std::process::id()));
+    std::fs::write(&path, initial)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let result = run_editor(&path).and_then(|_| {
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+    });
+    let _ = std::fs::remove_file(&path);
+
+    Ok(result?.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Editor command to run, which may include arguments (e.g. `code --wait`)
+fn command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Run the editor on `path` and wait for it to exit
+fn run_editor(path: &std::path::Path) -> Result<()> {
+    let editor = command();
+
+    // Through the shell, so an editor configured with arguments works
+    let status = Command::new("sh")
+        .args(["-c", &format!("{} \"$1\"", editor), "sh"])
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to run {}", editor))?;
+
+    if !status.success() {
+        anyhow::bail!("{} exited with {}", editor, status);
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Cloning repositories
+//!
+//! Clones go through libgit2 with the same credential lookup as push and
+//! fetch, reporting transfer progress as objects arrive.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::build::RepoBuilder;
+use git2::FetchOptions;
+
+use super::operations::create_callbacks;
+
+/// Clone `url` into `dest`, which must not exist or be empty. `progress`
+/// is called with the objects received so far and the total to fetch.
+pub fn clone_repository(
+    url: &str,
+    dest: &Path,
+    mut progress: impl FnMut(usize, usize) + 'static,
+) -> Result<()> {
+    let mut callbacks = create_callbacks();
+    callbacks.transfer_progress(move |stats| {
+        progress(stats.received_objects(), stats.total_objects());
+        true
+    });
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+
+    RepoBuilder::new()
+        .fetch_options(fetch_options)
+        .clone(url, dest)
+        .with_context(|| format!("Failed to clone {}", url))?;
+
+    Ok(())
+}
+
+/// Directory name `git clone` picks for `url`: its last path segment
+/// without a `.git` suffix
+pub fn repo_name_from_url(url: &str) -> Option<String> {
+    let trimmed = url.trim().trim_end_matches('/');
+    let last = trimmed.rsplit(['/', ':']).next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_name_from_url() {
+        let name = |url| repo_name_from_url(url).unwrap_or_default();
+        assert_eq!(name("https://github.com/me/proj.git"), "proj");
+        assert_eq!(name("https://github.com/me/proj/"), "proj");
+        assert_eq!(name("git@github.com:me/proj.git"), "proj");
+        assert_eq!(name("git@host:proj"), "proj");
+        assert_eq!(name("/srv/git/proj.git"), "proj");
+        assert_eq!(name(".git"), "");
+        assert_eq!(name(""), "");
+    }
+}
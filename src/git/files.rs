@@ -0,0 +1,231 @@
+//! Per-file status and operations
+//!
+//! Lists changed files, summarizes their size, and stages, unstages or
+//! discards them one at a time.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{build::CheckoutBuilder, DiffFormat, DiffOptions, Repository, Status, StatusOptions};
+
+use super::GitContext;
+
+/// A file with uncommitted changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Whether the file has changes in the index
+    pub staged: bool,
+    /// Whether the file has changes in the working tree (including untracked)
+    pub unstaged: bool,
+    /// Whether the file is untracked
+    pub untracked: bool,
+}
+
+impl ChangedFile {
+    /// Two-letter status code in the style of `git status --short`
+    pub fn status_code(&self) -> &'static str {
+        match (self.untracked, self.staged, self.unstaged) {
+            (true, _, _) => "??",
+            (false, true, true) => "MM",
+            (false, true, false) => "M ",
+            (false, false, _) => " M",
+        }
+    }
+}
+
+/// Size of the uncommitted changes, like `git diff --shortstat HEAD`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffStat {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl GitContext {
+    /// Diffstat of the working tree (staged and unstaged changes, including
+    /// untracked files) against HEAD
+    pub fn diff_stat(path: &Path) -> Result<DiffStat> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+
+        // Before the first commit everything is compared to an empty tree
+        let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+
+        let stats = repo
+            .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))
+            .and_then(|diff| diff.stats())
+            .context("Failed to diff the working tree")?;
+
+        Ok(DiffStat {
+            files_changed: stats.files_changed(),
+            insertions: stats.insertions(),
+            deletions: stats.deletions(),
+        })
+    }
+
+    /// Patch of the working tree (staged and unstaged changes, including
+    /// untracked files) against HEAD, like `git diff HEAD`
+    pub fn working_diff(path: &Path) -> Result<String> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+
+        // Before the first commit everything is compared to an empty tree
+        let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+
+        let diff = repo
+            .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))
+            .context("Failed to diff the working tree")?;
+
+        let mut patch = String::new();
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            // Content lines carry their +/-/space marker separately
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin());
+            }
+            patch.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })
+        .context("Failed to print the diff")?;
+
+        Ok(patch)
+    }
+
+    /// List files with staged or unstaged changes, sorted by path
+    pub fn changed_files(path: &Path) -> Result<Vec<ChangedFile>> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+
+        let mut status_opts = StatusOptions::new();
+        status_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false)
+            .exclude_submodules(true);
+
+        let statuses = repo
+            .statuses(Some(&mut status_opts))
+            .context("Failed to get status")?;
+
+        let mut files: Vec<ChangedFile> = statuses
+            .iter()
+            .filter_map(|entry| {
+                let s = entry.status();
+                let staged = s.intersects(
+                    Status::INDEX_NEW
+                        | Status::INDEX_MODIFIED
+                        | Status::INDEX_DELETED
+                        | Status::INDEX_RENAMED
+                        | Status::INDEX_TYPECHANGE,
+                );
+                let unstaged = s.intersects(
+                    Status::WT_NEW
+                        | Status::WT_MODIFIED
+                        | Status::WT_DELETED
+                        | Status::WT_RENAMED
+                        | Status::WT_TYPECHANGE
+                        | Status::CONFLICTED,
+                );
+                if !staged && !unstaged {
+                    return None;
+                }
+                Some(ChangedFile {
+                    path: entry.path()?.to_string(),
+                    staged,
+                    unstaged,
+                    untracked: s.contains(Status::WT_NEW),
+                })
+            })
+            .collect();
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Stage a single file (like git add -- <file>), including deletions
+    pub fn stage_file(path: &Path, file: &str) -> Result<()> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+        let workdir = repo.workdir().context("Repository has no working tree")?;
+
+        let mut index = repo.index().context("Failed to get index")?;
+        if workdir.join(file).exists() {
+            index.add_path(Path::new(file))
+        } else {
+            index.remove_path(Path::new(file))
+        }
+        .with_context(|| format!("Failed to stage {}", file))?;
+
+        index.write().context("Failed to write index")?;
+
+        Ok(())
+    }
+
+    /// Unstage a single file (like git restore --staged -- <file>)
+    pub fn unstage_file(path: &Path, file: &str) -> Result<()> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+
+        match repo
+            .head()
+            .and_then(|head| head.peel(git2::ObjectType::Commit))
+        {
+            Ok(head) => repo
+                .reset_default(Some(&head), [file])
+                .with_context(|| format!("Failed to unstage {}", file))?,
+            Err(_) => {
+                // No commits yet: unstaging means removing from the index
+                let mut index = repo.index().context("Failed to get index")?;
+                index
+                    .remove_path(Path::new(file))
+                    .with_context(|| format!("Failed to unstage {}", file))?;
+                index.write().context("Failed to write index")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Discard all changes to a single file, restoring it from HEAD
+    /// (like git checkout HEAD -- <file>). Untracked files are deleted.
+    pub fn discard_file(path: &Path, file: &str) -> Result<()> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+
+        let mut checkout = CheckoutBuilder::new();
+        checkout
+            .force()
+            .remove_untracked(true)
+            .disable_pathspec_match(true)
+            .path(file);
+
+        repo.checkout_head(Some(&mut checkout))
+            .with_context(|| format!("Failed to discard changes to {}", file))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_code() {
+        let file = |staged, unstaged, untracked| ChangedFile {
+            path: "a".to_string(),
+            staged,
+            unstaged,
+            untracked,
+        };
+        assert_eq!(file(false, true, true).status_code(), "??");
+        assert_eq!(file(true, true, false).status_code(), "MM");
+        assert_eq!(file(true, false, false).status_code(), "M ");
+        assert_eq!(file(false, true, false).status_code(), " M");
+    }
+}
@@ -3,31 +3,72 @@
 //! Provides pull request management through the GitHub CLI tool.
 
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Output};
 use std::sync::OnceLock;
 
 use anyhow::{Context, Result};
 use git2::Repository;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+
+use super::provider::{
+    MergeStrategy, PullRequestComment, PullRequestInfo, PullRequestProvider, PullRequestResult,
+};
 
 /// Cached result of gh CLI availability check
 static GH_AVAILABLE: OnceLock<bool> = OnceLock::new();
 
-/// Result of creating a pull request
-#[derive(Debug)]
-pub struct PullRequestResult {
-    /// The URL of the created pull request
-    pub url: String,
-}
+/// GitHub, through the gh CLI
+pub struct GitHub;
+
+impl PullRequestProvider for GitHub {
+    fn cli_name(&self) -> &'static str {
+        "gh"
+    }
+
+    fn is_available(&self) -> bool {
+        is_gh_available()
+    }
+
+    fn create_pull_request(
+        &self,
+        path: &Path,
+        title: &str,
+        body: &str,
+        base_branch: &str,
+        draft: bool,
+    ) -> Result<PullRequestResult> {
+        create_pull_request(path, title, body, base_branch, draft)
+    }
+
+    fn get_pull_request_info(&self, path: &Path) -> Result<Option<PullRequestInfo>> {
+        get_pull_request_info(path)
+    }
 
-/// Information about an existing pull request
-#[derive(Debug, Clone)]
-pub struct PullRequestInfo {
-    /// PR number
-    pub number: u64,
-    /// PR state (OPEN, CLOSED, MERGED)
-    pub state: String,
-    /// Whether the PR is mergeable (MERGEABLE, CONFLICTING, UNKNOWN)
-    pub mergeable: String,
+    fn view_pull_request(&self, path: &Path) -> Result<()> {
+        view_pull_request(path)
+    }
+
+    fn pull_request_diff(&self, path: &Path) -> Result<String> {
+        pull_request_diff(path)
+    }
+
+    fn pull_request_comments(&self, path: &Path) -> Result<Vec<PullRequestComment>> {
+        pull_request_comments(path)
+    }
+
+    fn merge_pull_request(
+        &self,
+        path: &Path,
+        strategy: MergeStrategy,
+        delete_branch: bool,
+    ) -> Result<()> {
+        merge_pull_request(path, strategy, delete_branch)
+    }
+
+    fn close_pull_request(&self, path: &Path) -> Result<()> {
+        close_pull_request(path)
+    }
 }
 
 /// Check if the GitHub CLI (gh) is available and authenticated.
@@ -50,13 +91,6 @@ pub fn is_gh_available() -> bool {
     })
 }
 
-/// Check if the remote URL points to GitHub
-pub fn is_github_remote(path: &Path) -> bool {
-    get_remote_url(path)
-        .map(|url| url.contains("github.com"))
-        .unwrap_or(false)
-}
-
 /// Get the remote URL for the repository (first remote, usually "origin")
 pub fn get_remote_url(path: &Path) -> Option<String> {
     let repo = Repository::discover(path).ok()?;
@@ -105,6 +139,7 @@ pub fn create_pull_request(
     title: &str,
     body: &str,
     base_branch: &str,
+    draft: bool,
 ) -> Result<PullRequestResult> {
     if !is_gh_available() {
         anyhow::bail!("GitHub CLI (gh) is not available or not authenticated");
@@ -116,6 +151,10 @@ pub fn create_pull_request(
     cmd.args(["--title", title]);
     cmd.args(["--base", base_branch]);
 
+    if draft {
+        cmd.arg("--draft");
+    }
+
     if !body.is_empty() {
         cmd.args(["--body", body]);
     } else {
@@ -133,35 +172,67 @@ pub fn create_pull_request(
     }
 }
 
-/// Get information about a PR for the current branch (if one exists)
-pub fn get_pull_request_info(path: &Path) -> Option<PullRequestInfo> {
+/// Get information about a PR for the current branch (if one exists).
+/// Errors only when gh failed transiently, so the caller can retry.
+pub fn get_pull_request_info(path: &Path) -> Result<Option<PullRequestInfo>> {
     if !is_gh_available() {
-        return None;
+        return Ok(None);
     }
 
-    let output = Command::new("gh")
-        .current_dir(path)
-        .args(["pr", "view", "--json", "number,url,state,mergeable"])
-        .output()
-        .ok()?;
+    let Ok(output) = gh_read(
+        path,
+        &[
+            "pr",
+            "view",
+            "--json",
+            "number,state,mergeable,commits,additions,deletions",
+        ],
+    ) else {
+        return Ok(None);
+    };
 
     if !output.status.success() {
-        return None;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_transient_gh_error(&stderr) {
+            anyhow::bail!("gh pr view failed: {}", stderr.trim());
+        }
+        return Ok(None);
     }
 
-    let json_str = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_pull_request(&String::from_utf8_lossy(&output.stdout)))
+}
 
-    // Simple JSON parsing without adding a dependency
-    // Format: {"number":123,"state":"OPEN","mergeable":"MERGEABLE"}
-    let number = extract_json_u64(&json_str, "number")?;
-    let state = extract_json_string(&json_str, "state")?;
-    let mergeable =
-        extract_json_string(&json_str, "mergeable").unwrap_or_else(|| "UNKNOWN".to_string());
+/// The fields of `gh pr view --json` we ask for
+#[derive(Debug, Deserialize)]
+struct GhPullRequest {
+    number: u64,
+    state: String,
+    #[serde(default)]
+    mergeable: Option<String>,
+    /// Only counted, so the commit objects are skipped over
+    #[serde(default)]
+    commits: Option<Vec<IgnoredAny>>,
+    #[serde(default)]
+    additions: Option<u64>,
+    #[serde(default)]
+    deletions: Option<u64>,
+}
+
+/// Parse `gh pr view --json` output
+fn parse_pull_request(json: &str) -> Option<PullRequestInfo> {
+    let pr: GhPullRequest = serde_json::from_str(json).ok()?;
+    let mergeable = pr
+        .mergeable
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| "UNKNOWN".to_string());
 
     Some(PullRequestInfo {
-        number,
-        state,
+        number: pr.number,
+        state: pr.state,
         mergeable,
+        commits: pr.commits.map(|commits| commits.len() as u64),
+        additions: pr.additions,
+        deletions: pr.deletions,
     })
 }
 
@@ -185,15 +256,121 @@ pub fn view_pull_request(path: &Path) -> Result<()> {
     }
 }
 
+/// Diff of the PR for the current branch, as GitHub computes it
+pub fn pull_request_diff(path: &Path) -> Result<String> {
+    if !is_gh_available() {
+        anyhow::bail!("GitHub CLI (gh) is not available or not authenticated");
+    }
+
+    let output = gh_read(path, &["pr", "diff", "--color", "never"])?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh pr diff failed: {}", stderr.trim())
+    }
+}
+
+/// Comments and reviews on the PR for the current branch, oldest first
+pub fn pull_request_comments(path: &Path) -> Result<Vec<PullRequestComment>> {
+    if !is_gh_available() {
+        anyhow::bail!("GitHub CLI (gh) is not available or not authenticated");
+    }
+
+    let output = gh_read(path, &["pr", "view", "--json", "comments,reviews"])?;
+
+    if output.status.success() {
+        parse_comments(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh pr view failed: {}", stderr.trim())
+    }
+}
+
+/// An author as gh reports it; null for deleted accounts
+#[derive(Debug, Deserialize)]
+struct GhAuthor {
+    login: String,
+}
+
+/// A conversation comment from `gh pr view --json comments`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhComment {
+    author: Option<GhAuthor>,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    created_at: String,
+}
+
+/// A review from `gh pr view --json reviews`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReview {
+    author: Option<GhAuthor>,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    submitted_at: Option<String>,
+}
+
+/// The fields of `gh pr view --json comments,reviews` we ask for
+#[derive(Debug, Deserialize)]
+struct GhDiscussion {
+    #[serde(default)]
+    comments: Vec<GhComment>,
+    #[serde(default)]
+    reviews: Vec<GhReview>,
+}
+
+/// Parse `gh pr view --json comments,reviews` into one list, oldest first
+fn parse_comments(json: &str) -> Result<Vec<PullRequestComment>> {
+    let discussion: GhDiscussion =
+        serde_json::from_str(json).context("Unexpected gh pr view output")?;
+    let login = |author: Option<GhAuthor>| author.map_or_else(|| "ghost".to_string(), |a| a.login);
+
+    let comments = discussion.comments.into_iter().map(|c| PullRequestComment {
+        author: login(c.author),
+        state: None,
+        body: c.body,
+        created_at: c.created_at,
+    });
+    // Inline review comments arrive as bodiless COMMENTED reviews, which say
+    // nothing on their own; pending reviews are not visible to others yet
+    let reviews = discussion
+        .reviews
+        .into_iter()
+        .filter(|r| r.state != "PENDING" && !(r.state == "COMMENTED" && r.body.is_empty()))
+        .map(|r| PullRequestComment {
+            author: login(r.author),
+            state: Some(r.state).filter(|s| s != "COMMENTED"),
+            body: r.body,
+            created_at: r.submitted_at.unwrap_or_default(),
+        });
+
+    let mut all: Vec<PullRequestComment> = comments.chain(reviews).collect();
+    all.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(all)
+}
+
 /// Merge the PR for the current branch
-pub fn merge_pull_request(path: &Path, delete_branch: bool) -> Result<()> {
+pub fn merge_pull_request(path: &Path, strategy: MergeStrategy, delete_branch: bool) -> Result<()> {
     if !is_gh_available() {
         anyhow::bail!("GitHub CLI (gh) is not available or not authenticated");
     }
 
     let mut cmd = Command::new("gh");
     cmd.current_dir(path);
-    cmd.args(["pr", "merge", "--merge"]); // Use merge commit strategy
+    cmd.args(["pr", "merge"]);
+    cmd.arg(match strategy {
+        MergeStrategy::Merge => "--merge",
+        MergeStrategy::Squash => "--squash",
+        MergeStrategy::Rebase => "--rebase",
+    });
 
     if delete_branch {
         cmd.arg("--delete-branch");
@@ -229,22 +406,146 @@ pub fn close_pull_request(path: &Path) -> Result<()> {
     }
 }
 
-/// Simple helper to extract a string value from JSON
-fn extract_json_string(json: &str, key: &str) -> Option<String> {
-    let pattern = format!("\"{}\":\"", key);
-    let start = json.find(&pattern)? + pattern.len();
-    let rest = &json[start..];
-    let end = rest.find('"')?;
-    Some(rest[..end].to_string())
+/// Run a read-only gh command once. Retrying transient failures is left to
+/// the caller, which knows whether it may wait (see `get_pull_request_info`).
+fn gh_read(path: &Path, args: &[&str]) -> Result<Output> {
+    Command::new("gh")
+        .current_dir(path)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to execute gh {}", args.join(" ")))
 }
 
-/// Simple helper to extract a u64 value from JSON
-fn extract_json_u64(json: &str, key: &str) -> Option<u64> {
-    let pattern = format!("\"{}\":", key);
-    let start = json.find(&pattern)? + pattern.len();
-    let rest = &json[start..].trim_start();
-    let end = rest
-        .find(|c: char| !c.is_ascii_digit())
-        .unwrap_or(rest.len());
-    rest[..end].parse().ok()
+/// Whether gh's stderr looks like a transient network or rate-limit failure
+/// rather than a permanent one (auth, not found, no PR for the branch)
+fn is_transient_gh_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+
+    const PERMANENT: &[&str] = &[
+        "no pull requests found",
+        "not found",
+        "authentication",
+        "http 401",
+        "http 403",
+        "gh auth login",
+    ];
+    // Secondary rate limits are reported with a 403, so check them first
+    if stderr.contains("rate limit") {
+        return true;
+    }
+    if PERMANENT.iter().any(|s| stderr.contains(s)) {
+        return false;
+    }
+
+    const TRANSIENT: &[&str] = &[
+        "timeout",
+        "timed out",
+        "connection reset",
+        "connection refused",
+        "could not resolve host",
+        "temporary failure",
+        "tls handshake",
+        "unexpected eof",
+        "http 500",
+        "http 502",
+        "http 503",
+        "http 504",
+        "bad gateway",
+        "service unavailable",
+    ];
+    TRANSIENT.iter().any(|s| stderr.contains(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient_gh_error() {
+        assert!(is_transient_gh_error(
+            "Post \"https://api.github.com/graphql\": dial tcp: i/o timeout"
+        ));
+        assert!(is_transient_gh_error("HTTP 502: Bad Gateway"));
+        assert!(is_transient_gh_error(
+            "HTTP 403: You have exceeded a secondary rate limit"
+        ));
+        assert!(!is_transient_gh_error(
+            "no pull requests found for branch \"feat\""
+        ));
+        assert!(!is_transient_gh_error("HTTP 401: Bad credentials"));
+        assert!(!is_transient_gh_error(
+            "To get started with GitHub CLI, please run:  gh auth login"
+        ));
+    }
+
+    #[test]
+    fn test_parse_pull_request() {
+        let json = r#"{
+  "additions": 120,
+  "commits": [
+    {"oid": "a1b2c3", "messageHeadline": "Quote \"state\": \"CLOSED\"", "authors": [{"login": "x"}]},
+    {"oid": "d4e5f6", "messageHeadline": "Second", "authors": []},
+    {"oid": "0718ab", "messageHeadline": "Third {\"number\": 1}", "authors": []}
+  ],
+  "deletions": 45,
+  "mergeable": "CONFLICTING",
+  "number": 57,
+  "state": "OPEN"
+}"#;
+        let info = parse_pull_request(json).unwrap();
+        assert_eq!(info.number, 57);
+        assert_eq!(info.state, "OPEN");
+        assert_eq!(info.mergeable, "CONFLICTING");
+        assert_eq!(info.commits, Some(3));
+        assert_eq!(info.additions, Some(120));
+        assert_eq!(info.deletions, Some(45));
+
+        // gh leaves mergeable empty while GitHub is still computing it
+        let minimal = r#"{"number":9,"state":"MERGED","mergeable":""}"#;
+        let info = parse_pull_request(minimal).unwrap();
+        assert_eq!(info.mergeable, "UNKNOWN");
+        assert_eq!(info.commits, None);
+        assert_eq!(info.additions, None);
+
+        assert!(parse_pull_request(r#"{"state":"OPEN"}"#).is_none());
+        assert!(parse_pull_request("").is_none());
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let json = r#"{
+  "comments": [
+    {"author": {"login": "bob"}, "body": "Looks close", "createdAt": "2024-05-02T09:00:00Z"},
+    {"author": null, "body": "First!", "createdAt": "2024-05-01T08:00:00Z"}
+  ],
+  "reviews": [
+    {"author": {"login": "alice"}, "body": "", "state": "APPROVED",
+     "submittedAt": "2024-05-03T10:00:00Z"},
+    {"author": {"login": "alice"}, "body": "", "state": "COMMENTED",
+     "submittedAt": "2024-05-02T11:00:00Z"},
+    {"author": {"login": "carol"}, "body": "Nit", "state": "COMMENTED",
+     "submittedAt": "2024-05-02T12:00:00Z"},
+    {"author": {"login": "dave"}, "body": "draft", "state": "PENDING", "submittedAt": null}
+  ]
+}"#;
+        let comments = parse_comments(json).unwrap();
+        let summary: Vec<_> = comments
+            .iter()
+            .map(|c| (c.author.as_str(), c.state.as_deref(), c.body.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ghost", None, "First!"),
+                ("bob", None, "Looks close"),
+                ("carol", None, "Nit"),
+                ("alice", Some("APPROVED"), ""),
+            ]
+        );
+
+        assert!(parse_comments(r#"{"comments":[],"reviews":[]}"#)
+            .unwrap()
+            .is_empty());
+        assert!(parse_comments("no pull requests found").is_err());
+    }
 }
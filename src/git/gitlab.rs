@@ -0,0 +1,284 @@
+//! GitLab CLI (glab) operations
+//!
+//! Provides merge request management through the GitLab CLI tool. Merge
+//! requests are reported with GitHub's state names so the rest of the app
+//! can treat both hosts alike.
+
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::provider::{
+    MergeStrategy, PullRequestComment, PullRequestInfo, PullRequestProvider, PullRequestResult,
+};
+
+/// Cached result of glab CLI availability check
+static GLAB_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+/// GitLab, through the glab CLI
+pub struct GitLab;
+
+impl PullRequestProvider for GitLab {
+    fn cli_name(&self) -> &'static str {
+        "glab"
+    }
+
+    fn is_available(&self) -> bool {
+        *GLAB_AVAILABLE.get_or_init(|| {
+            let installed = Command::new("glab")
+                .arg("--version")
+                .output()
+                .is_ok_and(|output| output.status.success());
+
+            installed
+                && Command::new("glab")
+                    .args(["auth", "status"])
+                    .output()
+                    .is_ok_and(|output| output.status.success())
+        })
+    }
+
+    fn create_pull_request(
+        &self,
+        path: &Path,
+        title: &str,
+        body: &str,
+        base_branch: &str,
+        draft: bool,
+    ) -> Result<PullRequestResult> {
+        let mut args = vec![
+            "mr",
+            "create",
+            "--title",
+            title,
+            "--description",
+            body,
+            "--target-branch",
+            base_branch,
+            "--yes",
+        ];
+        if draft {
+            args.push("--draft");
+        }
+        let output = run_glab(path, &args)?;
+
+        // glab prints progress lines before the merge request URL
+        let url = output
+            .lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| line.starts_with("http"))
+            .unwrap_or_else(|| output.trim())
+            .to_string();
+        Ok(PullRequestResult { url })
+    }
+
+    fn get_pull_request_info(&self, path: &Path) -> Result<Option<PullRequestInfo>> {
+        if !self.is_available() {
+            return Ok(None);
+        }
+
+        let output = Command::new("glab")
+            .current_dir(path)
+            .args(["mr", "view", "--output", "json"])
+            .output();
+
+        Ok(output
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| parse_merge_request(&String::from_utf8_lossy(&output.stdout))))
+    }
+
+    fn view_pull_request(&self, path: &Path) -> Result<()> {
+        run_glab(path, &["mr", "view", "--web"]).map(|_| ())
+    }
+
+    fn pull_request_diff(&self, path: &Path) -> Result<String> {
+        run_glab(path, &["mr", "diff", "--color", "never"])
+    }
+
+    fn pull_request_comments(&self, path: &Path) -> Result<Vec<PullRequestComment>> {
+        let mr = run_glab(path, &["mr", "view", "--output", "json"])?;
+        let iid = parse_merge_request(&mr)
+            .context("Unexpected glab mr view output")?
+            .number;
+        let endpoint = format!(
+            "projects/:fullpath/merge_requests/{}/notes?sort=asc&per_page=100",
+            iid
+        );
+        parse_notes(&run_glab(path, &["api", &endpoint])?)
+    }
+
+    fn merge_pull_request(
+        &self,
+        path: &Path,
+        strategy: MergeStrategy,
+        delete_branch: bool,
+    ) -> Result<()> {
+        let mut args = vec!["mr", "merge", "--yes"];
+        match strategy {
+            MergeStrategy::Merge => {}
+            MergeStrategy::Squash => args.push("--squash"),
+            MergeStrategy::Rebase => args.push("--rebase"),
+        }
+        if delete_branch {
+            args.push("--remove-source-branch");
+        }
+        run_glab(path, &args).map(|_| ())
+    }
+
+    fn close_pull_request(&self, path: &Path) -> Result<()> {
+        run_glab(path, &["mr", "close"]).map(|_| ())
+    }
+}
+
+/// Run a glab command in `path`, returning its stdout
+fn run_glab(path: &Path, args: &[&str]) -> Result<String> {
+    let name = format!("glab {} {}", args[0], args[1]);
+    let output = Command::new("glab")
+        .current_dir(path)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to execute {}", name))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{} failed: {}", name, stderr.trim())
+    }
+}
+
+/// The fields of `glab mr view --output json` we use
+#[derive(Debug, Deserialize)]
+struct GlabMergeRequest {
+    iid: u64,
+    state: String,
+    #[serde(default)]
+    merge_status: Option<String>,
+}
+
+/// Parse `glab mr view --output json`, mapping GitLab's state and merge
+/// status onto GitHub's names
+fn parse_merge_request(json: &str) -> Option<PullRequestInfo> {
+    let mr: GlabMergeRequest = serde_json::from_str(json).ok()?;
+    let number = mr.iid;
+    let state = match mr.state.as_str() {
+        "opened" => "OPEN",
+        "merged" => "MERGED",
+        _ => "CLOSED",
+    };
+    let mergeable = match mr.merge_status.as_deref() {
+        Some("can_be_merged") => "MERGEABLE",
+        Some("cannot_be_merged") => "CONFLICTING",
+        _ => "UNKNOWN",
+    };
+
+    // `glab mr view` reports neither the commit count nor line changes
+    Some(PullRequestInfo {
+        number,
+        state: state.to_string(),
+        mergeable: mergeable.to_string(),
+        commits: None,
+        additions: None,
+        deletions: None,
+    })
+}
+
+/// A merge request note from the GitLab API
+#[derive(Debug, Deserialize)]
+struct GlabNote {
+    #[serde(default)]
+    body: String,
+    author: GlabAuthor,
+    #[serde(default)]
+    created_at: String,
+    /// Notes GitLab writes itself ("added 1 commit", "approved this ...")
+    #[serde(default)]
+    system: bool,
+}
+
+/// A note's author
+#[derive(Debug, Deserialize)]
+struct GlabAuthor {
+    username: String,
+}
+
+/// Parse the merge request notes API response, dropping system notes
+fn parse_notes(json: &str) -> Result<Vec<PullRequestComment>> {
+    let notes: Vec<GlabNote> = serde_json::from_str(json).context("Unexpected glab api output")?;
+    Ok(notes
+        .into_iter()
+        .filter(|note| !note.system)
+        .map(|note| PullRequestComment {
+            author: note.author.username,
+            state: None,
+            body: note.body,
+            created_at: note.created_at,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_merge_request() {
+        let json = r#"{
+  "id": 9001,
+  "iid": 42,
+  "state": "opened",
+  "merge_status": "cannot_be_merged"
+}"#;
+        let info = parse_merge_request(json).unwrap();
+        assert_eq!(info.number, 42);
+        assert_eq!(info.state, "OPEN");
+        assert_eq!(info.mergeable, "CONFLICTING");
+
+        let merged = r#"{"iid":3,"state":"merged","merge_status":"can_be_merged"}"#;
+        let info = parse_merge_request(merged).unwrap();
+        assert_eq!(info.state, "MERGED");
+        assert_eq!(info.mergeable, "MERGEABLE");
+
+        // Nested objects carrying the same keys don't shadow the top level
+        let nested = r#"{
+  "author": {"id": 5, "state": "active"},
+  "description": "fixes \"iid\": 1",
+  "state": "closed",
+  "iid": 8
+}"#;
+        let info = parse_merge_request(nested).unwrap();
+        assert_eq!(info.number, 8);
+        assert_eq!(info.state, "CLOSED");
+        assert_eq!(info.mergeable, "UNKNOWN");
+
+        assert!(parse_merge_request("{\"state\": \"opened\"}").is_none());
+        assert!(parse_merge_request("no merge request found").is_none());
+    }
+
+    #[test]
+    fn test_parse_notes() {
+        let json = r#"[
+  {"id": 1, "body": "added 2 commits", "author": {"username": "bot"},
+   "created_at": "2024-05-01T08:00:00.000Z", "system": true},
+  {"id": 2, "body": "Why not a `match`?", "author": {"username": "alice"},
+   "created_at": "2024-05-01T09:00:00.000Z", "system": false}
+]"#;
+        let notes = parse_notes(json).unwrap();
+        assert_eq!(
+            notes,
+            vec![PullRequestComment {
+                author: "alice".to_string(),
+                state: None,
+                body: "Why not a `match`?".to_string(),
+                created_at: "2024-05-01T09:00:00.000Z".to_string(),
+            }]
+        );
+        assert!(parse_notes("[]").unwrap().is_empty());
+        assert!(parse_notes("404 Not Found").is_err());
+    }
+}
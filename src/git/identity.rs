@@ -0,0 +1,116 @@
+//! Commit identities
+//!
+//! An identity is written and shown as `Name <email>`, the same form git
+//! uses. Commits use the repository's git config identity unless another
+//! one is given.
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{Repository, Signature};
+
+/// Name and email used to author and commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+impl Identity {
+    /// The identity git would use for commits in the repository at `path`
+    pub fn from_git_config(path: &Path) -> Option<Self> {
+        let repo = Repository::discover(path).ok()?;
+        let signature = repo.signature().ok()?;
+        Some(Self {
+            name: signature.name()?.to_string(),
+            email: signature.email()?.to_string(),
+        })
+    }
+
+    /// Parse and validate `Name <email>`
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (name, rest) = s
+            .split_once('<')
+            .context("Expected an identity like 'Name <email>'")?;
+        let email = rest
+            .strip_suffix('>')
+            .context("Expected an identity like 'Name <email>'")?;
+
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Identity name cannot be empty");
+        }
+        if !is_valid_email(email) {
+            anyhow::bail!("Invalid email address '{}'", email);
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            email: email.to_string(),
+        })
+    }
+
+    /// Signature for this identity, timestamped now
+    pub fn signature(&self) -> Result<Signature<'static>> {
+        Signature::now(&self.name, &self.email).context("Invalid commit identity")
+    }
+
+    /// Signature to commit as in the repository at `path` for an author
+    /// field. None (commit as git would) when the field is empty or names
+    /// the git config identity.
+    pub fn commit_signature(path: &Path, author: &str) -> Result<Option<Signature<'static>>> {
+        if author.trim().is_empty() {
+            return Ok(None);
+        }
+        let identity = Self::parse(author)?;
+        if Some(&identity) == Self::from_git_config(path).as_ref() {
+            return Ok(None);
+        }
+        identity.signature().map(Some)
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// Loose check for `local@domain`: one `@`, both sides non-empty, and no
+/// whitespace or angle brackets. Hosts without a dot (`bot@localhost`)
+/// are allowed.
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.is_empty()
+        && !domain.contains('@')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !email
+            .chars()
+            .any(|c| c.is_whitespace() || c == '<' || c == '>')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_identity() {
+        let identity = Identity::parse(" Review Bot <bot@example.com> ").unwrap();
+        assert_eq!(identity.name, "Review Bot");
+        assert_eq!(identity.email, "bot@example.com");
+        assert_eq!(identity.to_string(), "Review Bot <bot@example.com>");
+
+        assert!(Identity::parse("bot@localhost").is_err());
+        assert!(Identity::parse("<bot@example.com>").is_err());
+        assert!(Identity::parse("Bot <bot>").is_err());
+        assert!(Identity::parse("Bot <a@b@c>").is_err());
+        assert!(Identity::parse("Bot <bot@example.com.>").is_err());
+        assert!(Identity::parse("Bot <b ot@example.com>").is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! Merging the base branch into the current branch
+//!
+//! The merge-based alternative to rebasing: fetches the base branch from the
+//! remote and merges it into HEAD, fast-forwarding when possible. Conflicts
+//! are left in the working tree for the user to resolve.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{AutotagOption, FetchOptions, Repository, Signature};
+
+use super::operations::create_callbacks;
+use super::GitContext;
+
+/// What merging the base branch did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeOutcome {
+    /// The branch already contains the base branch
+    UpToDate,
+    /// The branch was moved forward to the base branch
+    FastForward,
+    /// A merge commit was created
+    Merged,
+    /// The merge stopped with this many conflicted files
+    Conflicts(usize),
+}
+
+impl GitContext {
+    /// Fetch `base_branch` from the first remote and merge it into the
+    /// current branch, committing as `signature` if given, otherwise as the
+    /// identity from git config.
+    ///
+    /// Requires a branch checkout with no uncommitted changes to tracked
+    /// files. On conflicts the repository is left mid-merge, with the
+    /// conflicts in the working tree.
+    pub fn merge_base_branch(
+        path: &Path,
+        base_branch: &str,
+        signature: Option<&Signature>,
+    ) -> Result<MergeOutcome> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+
+        let head = repo.head().context("Failed to get HEAD")?;
+        if !head.is_branch() {
+            anyhow::bail!("Cannot merge: HEAD is detached");
+        }
+        let branch_name = head
+            .shorthand()
+            .ok_or_else(|| anyhow::anyhow!("Invalid branch name"))?
+            .to_string();
+        if branch_name == base_branch {
+            anyhow::bail!("Already on the base branch '{}'", base_branch);
+        }
+
+        let mut status_opts = git2::StatusOptions::new();
+        status_opts
+            .include_untracked(false)
+            .exclude_submodules(true);
+        let dirty = repo
+            .statuses(Some(&mut status_opts))
+            .context("Failed to read status")?
+            .iter()
+            .any(|entry| entry.status() != git2::Status::CURRENT);
+        if dirty {
+            anyhow::bail!("Commit or stash your changes before merging");
+        }
+
+        // Fetch the base branch
+        let remotes = repo.remotes().context("Failed to list remotes")?;
+        let remote_name = remotes
+            .get(0)
+            .ok_or_else(|| anyhow::anyhow!("No remotes configured"))?
+            .to_string();
+        let mut remote = repo
+            .find_remote(&remote_name)
+            .context("Failed to find remote")?;
+
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(create_callbacks());
+        fetch_options.download_tags(AutotagOption::Auto);
+        remote
+            .fetch(&[base_branch], Some(&mut fetch_options), None)
+            .context("Fetch failed")?;
+
+        let fetch_head = repo
+            .find_reference("FETCH_HEAD")
+            .context("Failed to find FETCH_HEAD")?;
+        let fetch_commit = repo
+            .reference_to_annotated_commit(&fetch_head)
+            .context("Failed to get fetch commit")?;
+
+        let (analysis, _) = repo
+            .merge_analysis(&[&fetch_commit])
+            .context("Merge analysis failed")?;
+
+        if analysis.is_up_to_date() {
+            return Ok(MergeOutcome::UpToDate);
+        }
+
+        let branch_ref = format!("refs/heads/{}", branch_name);
+        if analysis.is_fast_forward() {
+            let mut reference = repo.find_reference(&branch_ref)?;
+            reference.set_target(fetch_commit.id(), "fast-forward merge of base branch")?;
+            repo.set_head(&branch_ref)?;
+            repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+            return Ok(MergeOutcome::FastForward);
+        }
+
+        // Merge into the index and working tree; this records MERGE_HEAD
+        repo.merge(&[&fetch_commit], None, None)
+            .context("Merge failed")?;
+
+        let mut index = repo.index().context("Failed to get index")?;
+        if index.has_conflicts() {
+            let conflicts = index
+                .conflicts()
+                .context("Failed to read conflicts")?
+                .count();
+            return Ok(MergeOutcome::Conflicts(conflicts));
+        }
+
+        let tree_oid = index.write_tree().context("Failed to write tree")?;
+        let tree = repo.find_tree(tree_oid).context("Failed to find tree")?;
+        let head_commit = head.peel_to_commit().context("Failed to get HEAD commit")?;
+        let base_commit = repo
+            .find_commit(fetch_commit.id())
+            .context("Failed to find fetched commit")?;
+
+        let signature = match signature {
+            Some(signature) => signature.to_owned(),
+            None => repo.signature().context("Failed to get signature")?,
+        };
+        let message = format!(
+            "Merge branch '{}' of {} into {}",
+            base_branch, remote_name, branch_name
+        );
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &[&head_commit, &base_commit],
+        )
+        .context("Failed to create merge commit")?;
+        repo.cleanup_state()
+            .context("Failed to clean up merge state")?;
+
+        Ok(MergeOutcome::Merged)
+    }
+}
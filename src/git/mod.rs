@@ -1,24 +1,51 @@
-//! Git operations and GitHub CLI integration
+//! Git operations and pull request host integration
 //!
-//! This module provides git functionality through libgit2 and GitHub CLI:
+//! This module provides git functionality through libgit2 and the GitHub and
+//! GitLab CLIs:
 //! - `GitContext`: Detects and caches git state for a working directory
+//! - `clone`: Cloning a repository with transfer progress
+//! - `files`: Per-file status, staging and discarding
 //! - `github`: GitHub CLI operations (PR management)
+//! - `gitlab`: GitLab CLI operations (MR management)
+//! - `identity`: Commit identities (`Name <email>`)
+//! - `merge`: Merging the base branch into the current branch
 //! - `operations`: Core git operations (push, pull, fetch, commit, stage)
+//! - `provider`: The `PullRequestProvider` trait and host detection
+//! - `state`: In-progress merges, rebases, cherry-picks and bisects
+//! - `squash`: Squashing a branch's commits since its base into one
+//! - `tags`: Tag listing, creation and pushing
 //! - `worktree`: Worktree and branch management
 
+mod clone;
+mod files;
 mod github;
+mod gitlab;
+mod identity;
+mod merge;
 mod operations;
+mod provider;
+mod squash;
+mod state;
+mod tags;
 mod worktree;
 
 use std::path::{Path, PathBuf};
 
+use anyhow::{Context, Result};
 use git2::{Repository, StatusOptions};
 
 // Re-export public API
-pub use github::{
-    close_pull_request, create_pull_request, get_default_branch, get_pull_request_info,
-    is_gh_available, is_github_remote, merge_pull_request, view_pull_request, PullRequestInfo,
+pub use clone::{clone_repository, repo_name_from_url};
+pub use files::{ChangedFile, DiffStat};
+pub use github::get_default_branch;
+pub use identity::Identity;
+pub use merge::MergeOutcome;
+pub use operations::is_auth_error;
+pub use provider::{
+    compact_count, detect_provider, provider, MergeStrategy, PullRequestComment, PullRequestInfo,
 };
+pub use state::RepoState;
+pub use tags::is_valid_tag_name;
 
 /// Git context for a session's working directory
 #[derive(Debug, Clone)]
@@ -27,12 +54,19 @@ pub struct GitContext {
     pub branch: String,
     /// Whether there are staged changes ready to commit
     pub has_staged: bool,
-    /// Whether there are unstaged changes in the working directory
+    /// Whether tracked files have unstaged changes in the working directory
     pub has_unstaged: bool,
+    /// Whether there are untracked files in the working directory
+    pub has_untracked: bool,
     /// Whether this directory is a worktree (not the main checkout)
     pub is_worktree: bool,
     /// Path to the main repository (if this is a worktree)
     pub main_repo_path: Option<PathBuf>,
+    /// Root of the main repository's working tree, shared by all of its worktrees
+    pub repo_root: PathBuf,
+    /// Root of this checkout's working tree (the worktree itself for a
+    /// linked worktree)
+    pub workdir: PathBuf,
     /// Whether the branch has an upstream configured
     pub has_upstream: bool,
     /// Whether any remote is configured
@@ -41,12 +75,15 @@ pub struct GitContext {
     pub ahead: usize,
     /// Commits behind upstream
     pub behind: usize,
+    /// Merge, rebase, cherry-pick, revert or bisect in progress
+    pub state: Option<RepoState>,
 }
 
 impl GitContext {
-    /// Returns true if there are any uncommitted changes (staged or unstaged)
+    /// Returns true if there are any uncommitted changes (staged, unstaged
+    /// or untracked)
     pub fn is_dirty(&self) -> bool {
-        self.has_staged || self.has_unstaged
+        self.has_staged || self.has_unstaged || self.has_untracked
     }
 
     /// Detect git context for a given path. Returns None if not a git repo.
@@ -80,11 +117,12 @@ impl GitContext {
             .include_ignored(false)
             .exclude_submodules(true);
 
-        let (has_staged, has_unstaged) = repo
+        let (has_staged, has_unstaged, has_untracked) = repo
             .statuses(Some(&mut status_opts))
             .map(|statuses| {
                 let mut staged = false;
                 let mut unstaged = false;
+                let mut untracked = false;
                 for entry in statuses.iter() {
                     let s = entry.status();
                     // Index (staged) changes
@@ -97,20 +135,24 @@ impl GitContext {
                     ) {
                         staged = true;
                     }
-                    // Worktree (unstaged) changes
+                    // Worktree (unstaged) changes, including unresolved conflicts
                     if s.intersects(
-                        git2::Status::WT_NEW
-                            | git2::Status::WT_MODIFIED
+                        git2::Status::WT_MODIFIED
                             | git2::Status::WT_DELETED
                             | git2::Status::WT_RENAMED
-                            | git2::Status::WT_TYPECHANGE,
+                            | git2::Status::WT_TYPECHANGE
+                            | git2::Status::CONFLICTED,
                     ) {
                         unstaged = true;
                     }
+                    // New files git does not track yet
+                    if s.contains(git2::Status::WT_NEW) {
+                        untracked = true;
+                    }
                 }
-                (staged, unstaged)
+                (staged, unstaged, untracked)
             })
-            .unwrap_or((false, false));
+            .unwrap_or((false, false, false));
 
         // Check if worktree
         let is_worktree = repo.is_worktree();
@@ -120,24 +162,64 @@ impl GitContext {
             None
         };
 
+        // The common dir is the main repo's `.git`, so its parent is the main
+        // working tree for both the main checkout and any linked worktree
+        let common_dir = repo.commondir();
+        let repo_root = if common_dir.file_name().is_some_and(|n| n == ".git") {
+            common_dir.parent().unwrap_or(common_dir).to_path_buf()
+        } else {
+            repo.workdir().unwrap_or(common_dir).to_path_buf()
+        };
+
+        let workdir = repo
+            .workdir()
+            .map(|dir| dir.components().collect::<PathBuf>())
+            .unwrap_or_else(|| repo_root.clone());
+
         // Check if any remote is configured
         let has_remote = repo.remotes().map(|r| !r.is_empty()).unwrap_or(false);
 
         // Check if upstream is configured and get ahead/behind
         let (has_upstream, ahead, behind) = get_upstream_info(&repo);
 
+        let state = RepoState::from_repository_state(repo.state());
+
         Some(GitContext {
             branch,
             has_staged,
             has_unstaged,
+            has_untracked,
             is_worktree,
             main_repo_path,
+            repo_root,
+            workdir,
             has_upstream,
             has_remote,
             ahead,
             behind,
+            state,
         })
     }
+
+    /// Full SHA of the commit HEAD points at
+    pub fn head_sha(path: &Path) -> Result<String> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+        let head = repo.head().context("The repository has no commits yet")?;
+        let oid = head.target().context("HEAD does not point at a commit")?;
+        Ok(oid.to_string())
+    }
+
+    /// Name of the checked out branch; an error when HEAD is detached
+    pub fn head_branch(path: &Path) -> Result<String> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+        let head = repo.head().context("The repository has no commits yet")?;
+        if !head.is_branch() {
+            anyhow::bail!("HEAD is detached, not on a branch");
+        }
+        head.shorthand()
+            .map(str::to_string)
+            .context("Branch name is not valid UTF-8")
+    }
 }
 
 /// Get upstream info: (has_upstream, ahead, behind)
@@ -1,12 +1,13 @@
 //! Core git operations using libgit2
 //!
-//! Provides stage, commit, push, pull, and fetch operations.
+//! Provides stage, commit, stash, push, pull, and fetch operations.
 
 use std::path::Path;
 
 use anyhow::{Context, Result};
 use git2::{
     AutotagOption, Cred, CredentialType, FetchOptions, PushOptions, RemoteCallbacks, Repository,
+    Signature, StashFlags,
 };
 
 use super::GitContext;
@@ -32,15 +33,35 @@ impl GitContext {
         Ok(())
     }
 
-    /// Commit staged changes with a message
-    pub fn commit(path: &Path, message: &str) -> Result<()> {
+    /// Whether the index differs from HEAD's tree, i.e. a commit would not
+    /// be empty. Any staged file counts before the first commit.
+    pub fn has_staged_changes(path: &Path) -> Result<bool> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+
+        let mut index = repo.index().context("Failed to get index")?;
+        let index_tree = index.write_tree().context("Failed to write tree")?;
+
+        let head_tree = match repo.head() {
+            Ok(head) => head.peel_to_tree().context("Failed to get HEAD tree")?.id(),
+            Err(_) => return Ok(!index.is_empty()),
+        };
+
+        Ok(index_tree != head_tree)
+    }
+
+    /// Commit staged changes with a message, as `signature` if given,
+    /// otherwise as the identity from git config
+    pub fn commit(path: &Path, message: &str, signature: Option<&Signature>) -> Result<()> {
         let repo = Repository::discover(path).context("Failed to open repository")?;
 
         let mut index = repo.index().context("Failed to get index")?;
         let tree_oid = index.write_tree().context("Failed to write tree")?;
         let tree = repo.find_tree(tree_oid).context("Failed to find tree")?;
 
-        let signature = repo.signature().context("Failed to get signature")?;
+        let signature = match signature {
+            Some(signature) => signature.to_owned(),
+            None => repo.signature().context("Failed to get signature")?,
+        };
 
         let parent_commit = match repo.head() {
             Ok(head) => Some(head.peel_to_commit().context("Failed to get HEAD commit")?),
@@ -62,6 +83,42 @@ impl GitContext {
         Ok(())
     }
 
+    /// Stash all uncommitted changes, untracked files included (like git
+    /// stash -u)
+    pub fn stash(path: &Path) -> Result<()> {
+        let mut repo = Repository::discover(path).context("Failed to open repository")?;
+        let signature = repo.signature().context("Failed to get signature")?;
+
+        repo.stash_save2(&signature, None, Some(StashFlags::INCLUDE_UNTRACKED))
+            .context("Failed to stash changes")?;
+
+        Ok(())
+    }
+
+    /// Apply the most recent stash and drop it (like git stash pop)
+    pub fn stash_pop(path: &Path) -> Result<()> {
+        let mut repo = Repository::discover(path).context("Failed to open repository")?;
+
+        repo.stash_pop(0, None)
+            .context("Failed to apply stash (stashed changes may conflict)")?;
+
+        Ok(())
+    }
+
+    /// Whether the repository has any stash entries
+    pub fn has_stash(path: &Path) -> bool {
+        let Ok(mut repo) = Repository::discover(path) else {
+            return false;
+        };
+
+        let mut found = false;
+        let _ = repo.stash_foreach(|_, _, _| {
+            found = true;
+            false
+        });
+        found
+    }
+
     /// Push and set upstream (like git push -u origin branch)
     pub fn push_set_upstream(path: &Path) -> Result<()> {
         let repo = Repository::discover(path).context("Failed to open repository")?;
@@ -264,8 +321,24 @@ impl GitContext {
     }
 }
 
+/// Whether a remote operation failed to authenticate, e.g. because no SSH
+/// agent was running. Such failures are worth retrying once fixed.
+pub fn is_auth_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(git_error) = cause.downcast_ref::<git2::Error>() {
+            if git_error.code() == git2::ErrorCode::Auth
+                || git_error.class() == git2::ErrorClass::Ssh
+            {
+                return true;
+            }
+        }
+        let message = cause.to_string().to_lowercase();
+        message.contains("credentials") || message.contains("authentication")
+    })
+}
+
 /// Create remote callbacks for authentication
-fn create_callbacks() -> RemoteCallbacks<'static> {
+pub(super) fn create_callbacks() -> RemoteCallbacks<'static> {
     let mut callbacks = RemoteCallbacks::new();
 
     callbacks.credentials(|url, username_from_url, allowed_types| {
@@ -315,3 +388,26 @@ fn create_callbacks() -> RemoteCallbacks<'static> {
 
     callbacks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_auth_error() {
+        let auth = anyhow::Error::new(git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Net,
+            "authentication required",
+        ))
+        .context("Push failed");
+        assert!(is_auth_error(&auth));
+
+        let callback = anyhow::Error::new(git2::Error::from_str("No valid credentials found"))
+            .context("Fetch failed");
+        assert!(is_auth_error(&callback));
+
+        let conflict = anyhow::anyhow!("Cannot fast-forward; manual merge required");
+        assert!(!is_auth_error(&conflict));
+    }
+}
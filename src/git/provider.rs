@@ -0,0 +1,240 @@
+//! Pull request hosting providers
+//!
+//! Pull (or merge) request operations go through the `PullRequestProvider`
+//! trait, implemented by each supported host's CLI integration. The provider
+//! is picked from the host of the repository's remote URL.
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::github::{get_remote_url, GitHub};
+use super::gitlab::GitLab;
+
+/// Result of creating a pull request
+#[derive(Debug)]
+pub struct PullRequestResult {
+    /// The URL of the created pull request
+    pub url: String,
+}
+
+/// Information about an existing pull request
+#[derive(Debug, Clone)]
+pub struct PullRequestInfo {
+    /// PR number
+    pub number: u64,
+    /// PR state (OPEN, CLOSED, MERGED)
+    pub state: String,
+    /// Whether the PR is mergeable (MERGEABLE, CONFLICTING, UNKNOWN)
+    pub mergeable: String,
+    /// Number of commits, if the host reports it
+    pub commits: Option<u64>,
+    /// Lines added, if the host reports it
+    pub additions: Option<u64>,
+    /// Lines deleted, if the host reports it
+    pub deletions: Option<u64>,
+}
+
+/// A comment, or a review, on a pull request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestComment {
+    /// Who wrote it
+    pub author: String,
+    /// Review verdict (APPROVED, CHANGES_REQUESTED, ...), for reviews
+    pub state: Option<String>,
+    /// Markdown body, empty for a review left without a comment
+    pub body: String,
+    /// When it was posted, as an RFC 3339 timestamp
+    pub created_at: String,
+}
+
+/// How a pull request's commits land on the base branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Keep the commits and add a merge commit
+    #[default]
+    Merge,
+    /// Combine the commits into one
+    Squash,
+    /// Replay the commits onto the base branch
+    Rebase,
+}
+
+impl MergeStrategy {
+    /// Every strategy, in the order they are cycled through
+    const ALL: [MergeStrategy; 3] = [Self::Merge, Self::Squash, Self::Rebase];
+
+    /// Name shown in the merge confirmation
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Merge => "merge commit",
+            Self::Squash => "squash",
+            Self::Rebase => "rebase",
+        }
+    }
+
+    /// The next (or previous) strategy, wrapping around
+    pub fn cycle(self, forward: bool) -> Self {
+        let index = Self::ALL.iter().position(|&s| s == self).unwrap_or(0);
+        let len = Self::ALL.len();
+        let next = if forward {
+            (index + 1) % len
+        } else {
+            (index + len - 1) % len
+        };
+        Self::ALL[next]
+    }
+}
+
+/// Short form of a count for tight rows: 999, 1.2k, 45k, 3.1M
+pub fn compact_count(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=9_999 => format!("{:.1}k", n as f64 / 1_000.0),
+        10_000..=999_999 => format!("{}k", n / 1_000),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+/// Pull request operations for one hosting service, all scoped to the
+/// branch checked out at `path`
+pub trait PullRequestProvider: Sync {
+    /// Name of the CLI the provider drives, for error messages
+    fn cli_name(&self) -> &'static str;
+
+    /// Whether the provider's CLI is installed and authenticated
+    fn is_available(&self) -> bool;
+
+    /// Create a pull request for the current branch, optionally as a draft
+    fn create_pull_request(
+        &self,
+        path: &Path,
+        title: &str,
+        body: &str,
+        base_branch: &str,
+        draft: bool,
+    ) -> Result<PullRequestResult>;
+
+    /// Information about the current branch's pull request, if one exists.
+    /// An error means the lookup failed transiently (network, rate limit)
+    /// and is worth retrying; any other failure is reported as no PR.
+    fn get_pull_request_info(&self, path: &Path) -> Result<Option<PullRequestInfo>>;
+
+    /// Open the current branch's pull request in the browser
+    fn view_pull_request(&self, path: &Path) -> Result<()>;
+
+    /// Unified diff of the current branch's pull request
+    fn pull_request_diff(&self, path: &Path) -> Result<String>;
+
+    /// Comments and reviews on the current branch's pull request, oldest first
+    fn pull_request_comments(&self, path: &Path) -> Result<Vec<PullRequestComment>>;
+
+    /// Merge the current branch's pull request
+    fn merge_pull_request(
+        &self,
+        path: &Path,
+        strategy: MergeStrategy,
+        delete_branch: bool,
+    ) -> Result<()>;
+
+    /// Close the current branch's pull request without merging
+    fn close_pull_request(&self, path: &Path) -> Result<()>;
+}
+
+/// Provider for the repository's first remote, if its host is supported
+pub fn detect_provider(path: &Path) -> Option<&'static dyn PullRequestProvider> {
+    provider_for_url(&get_remote_url(path)?)
+}
+
+/// Like `detect_provider`, but an error if the host is not supported or its
+/// CLI is not available
+pub fn provider(path: &Path) -> Result<&'static dyn PullRequestProvider> {
+    let Some(provider) = detect_provider(path) else {
+        anyhow::bail!("The remote is not hosted on GitHub or GitLab");
+    };
+    if !provider.is_available() {
+        anyhow::bail!(
+            "{} is not available or not authenticated",
+            provider.cli_name()
+        );
+    }
+    Ok(provider)
+}
+
+/// Provider for a remote URL, by host name. Self-hosted GitLab instances
+/// are recognized by a `gitlab` in the host name.
+fn provider_for_url(url: &str) -> Option<&'static dyn PullRequestProvider> {
+    let host = remote_host(url)?;
+    if host == "github.com" {
+        Some(&GitHub)
+    } else if host.contains("gitlab") {
+        Some(&GitLab)
+    } else {
+        None
+    }
+}
+
+/// Host name of an HTTPS, SSH or scp-style (`git@host:path`) remote URL
+fn remote_host(url: &str) -> Option<String> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        None => url,
+    };
+    // Drop the user, then anything after the host
+    let rest = rest.rsplit_once('@').map_or(rest, |(_, host)| host);
+    let host = rest.split([':', '/']).next()?;
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_host() {
+        assert_eq!(
+            remote_host("git@github.com:owner/repo.git").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(
+            remote_host("https://gitlab.example.com/group/repo.git").as_deref(),
+            Some("gitlab.example.com")
+        );
+        assert_eq!(
+            remote_host("ssh://git@GitHub.com:22/owner/repo").as_deref(),
+            Some("github.com")
+        );
+    }
+
+    #[test]
+    fn test_merge_strategy_cycle() {
+        assert_eq!(MergeStrategy::default(), MergeStrategy::Merge);
+        assert_eq!(MergeStrategy::Merge.cycle(true), MergeStrategy::Squash);
+        assert_eq!(MergeStrategy::Rebase.cycle(true), MergeStrategy::Merge);
+        assert_eq!(MergeStrategy::Merge.cycle(false), MergeStrategy::Rebase);
+    }
+
+    #[test]
+    fn test_provider_for_url() {
+        assert_eq!(
+            provider_for_url("git@github.com:o/r.git").map(|p| p.cli_name()),
+            Some("gh")
+        );
+        assert_eq!(
+            provider_for_url("https://gitlab.com/o/r").map(|p| p.cli_name()),
+            Some("glab")
+        );
+        assert!(provider_for_url("https://bitbucket.org/o/r").is_none());
+        // A repo merely named "github.com" elsewhere is not GitHub
+        assert!(provider_for_url("https://example.com/github.com").is_none());
+    }
+
+    #[test]
+    fn test_compact_count() {
+        assert_eq!(compact_count(0), "0");
+        assert_eq!(compact_count(999), "999");
+        assert_eq!(compact_count(1_234), "1.2k");
+        assert_eq!(compact_count(45_678), "45k");
+        assert_eq!(compact_count(3_100_000), "3.1M");
+    }
+}
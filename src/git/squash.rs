@@ -0,0 +1,182 @@
+//! Squashing a branch into a single commit
+//!
+//! Collapses every commit since the merge-base with a base branch into one,
+//! by soft-resetting to the merge-base and committing the unchanged tree.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{Oid, Repository, ResetType, Signature};
+
+use super::GitContext;
+
+impl GitContext {
+    /// Summaries of the commits on HEAD since its merge-base with
+    /// `base_branch`, oldest first
+    pub fn commits_since_base(path: &Path, base_branch: &str) -> Result<Vec<String>> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+        let head = repo.head().context("Failed to get HEAD")?;
+        let head_oid = head.target().context("HEAD has no commit")?;
+        let merge_base = merge_base_with(&repo, head_oid, base_branch)?;
+
+        let mut revwalk = repo.revwalk().context("Failed to walk history")?;
+        revwalk.push(head_oid)?;
+        revwalk.hide(merge_base)?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+
+        revwalk
+            .map(|oid| {
+                let commit = repo.find_commit(oid?)?;
+                Ok(commit.summary().unwrap_or_default().to_string())
+            })
+            .collect()
+    }
+
+    /// Squash all commits since the merge-base with `base_branch` into one
+    /// commit with `message`, committed as `signature` if given, otherwise
+    /// as the identity from git config.
+    ///
+    /// Requires a branch checkout with no uncommitted changes to tracked
+    /// files and at least two commits to squash. If committing fails after
+    /// the reset, the branch is moved back to its original commit.
+    pub fn squash(
+        path: &Path,
+        base_branch: &str,
+        message: &str,
+        signature: Option<&Signature>,
+    ) -> Result<()> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+
+        let head = repo.head().context("Failed to get HEAD")?;
+        if !head.is_branch() {
+            anyhow::bail!("Cannot squash: HEAD is detached");
+        }
+        if head.shorthand() == Some(base_branch) {
+            anyhow::bail!("Cannot squash the base branch '{}' itself", base_branch);
+        }
+        let head_oid = head.target().context("HEAD has no commit")?;
+
+        let mut status_opts = git2::StatusOptions::new();
+        status_opts
+            .include_untracked(false)
+            .exclude_submodules(true);
+        let dirty = repo
+            .statuses(Some(&mut status_opts))
+            .context("Failed to read status")?
+            .iter()
+            .any(|entry| entry.status() != git2::Status::CURRENT);
+        if dirty {
+            anyhow::bail!("Cannot squash with uncommitted changes");
+        }
+
+        let count = Self::commits_since_base(path, base_branch)?.len();
+        if count < 2 {
+            anyhow::bail!(
+                "Nothing to squash: {} commit(s) ahead of '{}'",
+                count,
+                base_branch
+            );
+        }
+
+        let merge_base = merge_base_with(&repo, head_oid, base_branch)?;
+        let base_commit = repo.find_commit(merge_base)?;
+        let head_commit = repo.find_commit(head_oid)?;
+
+        repo.reset(base_commit.as_object(), ResetType::Soft, None)
+            .context("Failed to reset to the merge-base")?;
+
+        let result = (|| -> Result<()> {
+            let signature = match signature {
+                Some(signature) => signature.to_owned(),
+                None => repo.signature().context("Failed to get signature")?,
+            };
+            // A soft reset keeps the index, so HEAD's tree is committed as-is
+            let tree = head_commit.tree().context("Failed to read HEAD tree")?;
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &[&base_commit],
+            )
+            .context("Failed to create squashed commit")?;
+            Ok(())
+        })();
+
+        if result.is_err() {
+            let _ = repo.reset(head_commit.as_object(), ResetType::Soft, None);
+        }
+        result
+    }
+}
+
+/// Merge-base of `head` with the base branch, preferring its remote-tracking
+/// branch (usually more up to date) over the local one
+fn merge_base_with(repo: &Repository, head: Oid, base_branch: &str) -> Result<Oid> {
+    let remote_ref = repo.remotes().ok().and_then(|remotes| {
+        remotes
+            .get(0)
+            .map(|r| format!("refs/remotes/{}/{}", r, base_branch))
+    });
+    let local_ref = format!("refs/heads/{}", base_branch);
+
+    let base = remote_ref
+        .iter()
+        .chain(std::iter::once(&local_ref))
+        .find_map(|name| repo.refname_to_id(name).ok())
+        .with_context(|| format!("Base branch '{}' not found", base_branch))?;
+
+    repo.merge_base(head, base)
+        .with_context(|| format!("No common history with '{}'", base_branch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_squash_commits_as_signature() {
+        let dir = std::env::temp_dir().join(format!("claude-tmux-squash-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        let original = Signature::now("Original", "original@example.com").unwrap();
+
+        // A base commit on `main`, then two commits on `feature`
+        let mut parent = None;
+        for (i, branch) in ["main", "feature", "feature"].into_iter().enumerate() {
+            std::fs::write(dir.join("file.txt"), i.to_string()).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("file.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<_> = parent.iter().collect();
+            let oid = repo
+                .commit(
+                    Some("HEAD"),
+                    &original,
+                    &original,
+                    "change",
+                    &tree,
+                    &parents,
+                )
+                .unwrap();
+            let commit = repo.find_commit(oid).unwrap();
+            if branch == "main" {
+                repo.branch("main", &commit, true).unwrap();
+                repo.branch("feature", &commit, false).unwrap();
+                repo.set_head("refs/heads/feature").unwrap();
+            }
+            parent = Some(commit);
+        }
+
+        let author = Signature::now("Review Bot", "bot@example.com").unwrap();
+        GitContext::squash(&dir, "main", "Squashed", Some(&author)).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("Squashed"));
+        assert_eq!(head.author().name(), Some("Review Bot"));
+        assert_eq!(head.committer().email(), Some("bot@example.com"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,116 @@
+//! In-progress repository operations
+//!
+//! Detects merges, rebases, cherry-picks, reverts and bisects left in
+//! progress (usually by git commands run outside the tool), and aborts or
+//! continues them through the git CLI.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use git2::RepositoryState;
+
+use super::GitContext;
+
+/// A multi-step git operation the repository is in the middle of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoState {
+    Merging,
+    Rebasing,
+    CherryPicking,
+    Reverting,
+    Bisecting,
+}
+
+impl RepoState {
+    /// The operation in progress, if any, from libgit2's repository state
+    pub fn from_repository_state(state: RepositoryState) -> Option<Self> {
+        match state {
+            RepositoryState::Clean | RepositoryState::ApplyMailbox => None,
+            RepositoryState::Merge => Some(Self::Merging),
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge
+            | RepositoryState::ApplyMailboxOrRebase => Some(Self::Rebasing),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                Some(Self::CherryPicking)
+            }
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some(Self::Reverting),
+            RepositoryState::Bisect => Some(Self::Bisecting),
+        }
+    }
+
+    /// Badge shown next to the branch
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Merging => "MERGING",
+            Self::Rebasing => "REBASING",
+            Self::CherryPicking => "CHERRY-PICKING",
+            Self::Reverting => "REVERTING",
+            Self::Bisecting => "BISECTING",
+        }
+    }
+
+    /// The git subcommand driving the operation, e.g. `cherry-pick`
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Merging => "merge",
+            Self::Rebasing => "rebase",
+            Self::CherryPicking => "cherry-pick",
+            Self::Reverting => "revert",
+            Self::Bisecting => "bisect",
+        }
+    }
+
+    /// Whether the operation can be continued (a bisect can only be ended)
+    pub fn can_continue(self) -> bool {
+        self != Self::Bisecting
+    }
+}
+
+impl GitContext {
+    /// Abort the operation in progress (`git <op> --abort`, or
+    /// `git bisect reset`)
+    pub fn abort_operation(path: &Path, state: RepoState) -> Result<()> {
+        let flag = match state {
+            RepoState::Bisecting => "reset",
+            _ => "--abort",
+        };
+        run_git(path, &[state.command(), flag])
+    }
+
+    /// Continue the operation in progress once conflicts are resolved and
+    /// staged, keeping git's prepared commit messages
+    pub fn continue_operation(path: &Path, state: RepoState) -> Result<()> {
+        if !state.can_continue() {
+            anyhow::bail!("A bisect cannot be continued, only reset");
+        }
+        run_git(path, &[state.command(), "--continue"])
+    }
+}
+
+/// Run a git command in `path` without an interactive editor
+fn run_git(path: &Path, args: &[&str]) -> Result<()> {
+    let name = format!("git {}", args.join(" "));
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .env("GIT_EDITOR", "true")
+        .output()
+        .with_context(|| format!("Failed to execute {}", name))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        // git reports some failures, such as unresolved conflicts, on stdout
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let reason = if stderr.trim().is_empty() {
+            stdout.trim()
+        } else {
+            stderr.trim()
+        };
+        anyhow::bail!("{} failed: {}", name, reason)
+    }
+}
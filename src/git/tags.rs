@@ -0,0 +1,174 @@
+//! Git tag management
+//!
+//! Provides listing, creating and pushing annotated tags.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{Direction, PushOptions, Reference, Remote, Repository, Signature};
+
+use super::operations::create_callbacks;
+use super::GitContext;
+
+impl GitContext {
+    /// List all tag names in the repository
+    pub fn list_tags(path: &Path) -> Result<Vec<String>> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+        let names = repo.tag_names(None).context("Failed to list tags")?;
+
+        Ok(names.iter().flatten().map(|s| s.to_string()).collect())
+    }
+
+    /// Create an annotated tag on HEAD, tagged by `signature` if given,
+    /// otherwise by the identity from git config
+    pub fn create_tag(
+        path: &Path,
+        name: &str,
+        message: &str,
+        signature: Option<&Signature>,
+    ) -> Result<()> {
+        if !is_valid_tag_name(name) {
+            anyhow::bail!("'{}' is not a valid tag name", name);
+        }
+
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+        let head = repo.head().context("Failed to get HEAD")?;
+        let target = head
+            .peel(git2::ObjectType::Commit)
+            .context("Failed to get HEAD commit")?;
+        let signature = match signature {
+            Some(signature) => signature.to_owned(),
+            None => repo.signature().context("Failed to get signature")?,
+        };
+
+        repo.tag(name, &target, &signature, message, false)
+            .with_context(|| format!("Failed to create tag '{}'", name))?;
+
+        Ok(())
+    }
+
+    /// Push a single tag to the current branch's remote (usually "origin")
+    pub fn push_tag(path: &Path, name: &str) -> Result<()> {
+        Self::push_tags(path, &[name.to_string()])
+    }
+
+    /// Push tags to the current branch's remote in one push
+    pub fn push_tags(path: &Path, names: &[String]) -> Result<()> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+        let mut remote = tag_remote(&repo)?;
+
+        let callbacks = create_callbacks();
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+
+        let refspecs: Vec<String> = names
+            .iter()
+            .map(|name| format!("refs/tags/{}:refs/tags/{}", name, name))
+            .collect();
+
+        remote
+            .push(&refspecs, Some(&mut push_options))
+            .context("Push failed")?;
+
+        Ok(())
+    }
+
+    /// Local tags the tag remote does not have, by asking the remote
+    /// for its tags (`git ls-remote --tags`)
+    pub fn unpushed_tags(path: &Path) -> Result<Vec<String>> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+        let mut remote = tag_remote(&repo)?;
+
+        let connection = remote
+            .connect_auth(Direction::Fetch, Some(create_callbacks()), None)
+            .context("Failed to connect to remote")?;
+        let remote_tags: HashSet<String> = connection
+            .list()
+            .context("Failed to list remote tags")?
+            .iter()
+            .filter_map(|head| head.name().strip_prefix("refs/tags/"))
+            .map(|name| name.trim_end_matches("^{}").to_string())
+            .collect();
+
+        let local_tags = Self::list_tags(path)?;
+        Ok(local_tags
+            .into_iter()
+            .filter(|tag| !remote_tags.contains(tag))
+            .collect())
+    }
+}
+
+/// The remote tags are pushed to: the one the current branch tracks,
+/// falling back to "origin" like a plain `git push --tags`
+fn tag_remote(repo: &Repository) -> Result<Remote<'_>> {
+    let remote_name = upstream_remote_name(repo).unwrap_or_else(|| "origin".to_string());
+
+    repo.find_remote(&remote_name)
+        .with_context(|| format!("Failed to find remote '{}'", remote_name))
+}
+
+/// Remote name of the current branch's upstream (e.g. "origin/main" -> "origin")
+fn upstream_remote_name(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    let branch = repo
+        .find_branch(head.shorthand()?, git2::BranchType::Local)
+        .ok()?;
+    let upstream = branch.upstream().ok()?;
+    let upstream_name = upstream.name().ok()??;
+
+    upstream_name.split('/').next().map(str::to_string)
+}
+
+/// Check a tag name against git's ref naming rules
+pub fn is_valid_tag_name(name: &str) -> bool {
+    !name.is_empty() && Reference::is_valid_name(&format!("refs/tags/{}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_tag_name() {
+        assert!(is_valid_tag_name("v1.0.0"));
+        assert!(is_valid_tag_name("release/2024-01"));
+        assert!(!is_valid_tag_name(""));
+        assert!(!is_valid_tag_name("has space"));
+        assert!(!is_valid_tag_name("bad..dots"));
+        assert!(!is_valid_tag_name("ends.lock"));
+    }
+
+    #[test]
+    fn test_tag_remote_follows_upstream() {
+        let dir = std::env::temp_dir().join(format!("claude-tmux-tags-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        let signature = Signature::now("Tagger", "tagger@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        repo.remote("fork", "https://example.com/fork.git").unwrap();
+        repo.remote("origin", "https://example.com/origin.git")
+            .unwrap();
+
+        // Without an upstream, tags go to origin even if it isn't listed first
+        assert_eq!(tag_remote(&repo).unwrap().name(), Some("origin"));
+
+        repo.reference("refs/remotes/fork/main", oid, false, "test")
+            .unwrap();
+        let head = repo.head().unwrap();
+        let mut branch = repo
+            .find_branch(head.shorthand().unwrap(), git2::BranchType::Local)
+            .unwrap();
+        branch.set_upstream(Some("fork/main")).unwrap();
+        assert_eq!(tag_remote(&repo).unwrap().name(), Some("fork"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -123,6 +123,35 @@ impl GitContext {
         Ok(())
     }
 
+    /// Whether the current branch has been merged into `base_branch`, i.e.
+    /// its tip is an ancestor of the local base branch or of the remote's
+    /// copy of it. A branch still at the base branch's tip, e.g. one just
+    /// created, does not count.
+    pub fn is_merged_into(path: &Path, base_branch: &str) -> Result<bool> {
+        let repo = Repository::discover(path).context("Failed to open repository")?;
+        let tip = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("Failed to get HEAD commit")?
+            .id();
+
+        let mut bases = vec![format!("refs/heads/{}", base_branch)];
+        let remotes = repo.remotes().ok();
+        if let Some(remote) = remotes.as_ref().and_then(|remotes| remotes.get(0)) {
+            bases.push(format!("refs/remotes/{}/{}", remote, base_branch));
+        }
+
+        for name in bases {
+            let Ok(base) = repo.find_reference(&name).and_then(|r| r.peel_to_commit()) else {
+                continue;
+            };
+            if base.id() != tip && repo.graph_descendant_of(base.id(), tip).unwrap_or(false) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     /// Delete the worktree at the given path using `git worktree remove`
     /// Returns an error if the worktree has uncommitted changes (unless force=true)
     pub fn delete_worktree(worktree_path: &Path, force: bool) -> Result<()> {
@@ -0,0 +1,172 @@
+//! Action history
+//!
+//! Records the actions claude-tmux performs (commits, pushes, PRs, killed
+//! sessions, ...) so they can be reviewed in the history overlay. The log
+//! is kept in memory for the current run, and optionally persisted to
+//! `~/.local/share/claude-tmux/history.toml` when `persist_history` is set.
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of entries kept; older entries are dropped first
+const MAX_ENTRIES: usize = 200;
+
+/// A single performed action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Unix timestamp when the action finished
+    pub timestamp: u64,
+    /// Action label, e.g. "Push to remote"
+    pub action: String,
+    /// Session the action targeted
+    pub session: String,
+    /// Whether the action succeeded
+    pub ok: bool,
+    /// Result message or error shown at the time
+    pub detail: String,
+}
+
+impl HistoryEntry {
+    /// Human-readable age of the entry relative to `now` (unix seconds)
+    pub fn age(&self, now: u64) -> String {
+        let secs = now.saturating_sub(self.timestamp);
+        if secs < 60 {
+            format!("{}s ago", secs)
+        } else if secs < 3600 {
+            format!("{}m ago", secs / 60)
+        } else if secs < 86400 {
+            format!("{}h ago", secs / 3600)
+        } else {
+            format!("{}d ago", secs / 86400)
+        }
+    }
+}
+
+/// Ordered log of performed actions, oldest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Location of the persisted history file
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("claude-tmux/history.toml"))
+    }
+
+    /// Load the persisted history, or an empty one if there is none yet
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("Invalid history {}", path.display()))
+    }
+
+    /// Write the history to its file, creating the directory if needed
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let contents = toml::to_string(self).context("Failed to serialize history")?;
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Delete the persisted history file, if there is one
+    pub fn remove_file() -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to delete {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Drop every entry
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Append an entry stamped with the current time
+    pub fn record(&mut self, action: &str, session: &str, ok: bool, detail: &str) {
+        self.entries.push(HistoryEntry {
+            timestamp: now(),
+            action: action.to_string(),
+            session: session.to_string(),
+            ok,
+            detail: detail.to_string(),
+        });
+
+        if self.entries.len() > MAX_ENTRIES {
+            let excess = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// Entries, oldest first
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+}
+
+/// Current time as unix seconds
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_age() {
+        let entry = HistoryEntry {
+            timestamp: 1_000,
+            action: "Push to remote".to_string(),
+            session: "api".to_string(),
+            ok: true,
+            detail: "Pushed to remote".to_string(),
+        };
+        assert_eq!(entry.age(1_042), "42s ago");
+        assert_eq!(entry.age(1_000 + 5 * 60), "5m ago");
+        assert_eq!(entry.age(1_000 + 3 * 3600 + 59), "3h ago");
+        assert_eq!(entry.age(1_000 + 2 * 86400), "2d ago");
+        // Clock skew never underflows
+        assert_eq!(entry.age(0), "0s ago");
+    }
+
+    #[test]
+    fn test_record_drops_oldest() {
+        let mut history = History::default();
+        for i in 0..MAX_ENTRIES + 5 {
+            history.record("Kill session", &format!("s{}", i), true, "");
+        }
+        assert_eq!(history.entries().len(), MAX_ENTRIES);
+        assert_eq!(history.entries()[0].session, "s5");
+    }
+}
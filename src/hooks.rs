@@ -0,0 +1,70 @@
+//! User-configured shell hooks
+//!
+//! Runs the optional commands from the `[hooks]` config table after actions
+//! complete. Hooks are spawned detached through `sh -c`, with details about
+//! the action in `CLAUDE_TMUX_*` environment variables. Their output is
+//! discarded, and a hook that fails to start never undoes the action itself.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+/// A point at which a hook can run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// A session was created (plain or from a worktree)
+    NewSession,
+    /// Staged changes were committed
+    Commit,
+    /// A pull request was merged
+    MergePullRequest,
+}
+
+impl HookEvent {
+    /// Config key and `CLAUDE_TMUX_EVENT` value for this event
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::NewSession => "after_new_session",
+            Self::Commit => "after_commit",
+            Self::MergePullRequest => "after_merge_pr",
+        }
+    }
+}
+
+/// Details about the action passed to the hook
+#[derive(Debug)]
+pub struct HookContext<'a> {
+    pub session: &'a str,
+    pub path: &'a Path,
+    pub branch: Option<&'a str>,
+}
+
+/// Spawn a hook command without waiting for it to finish
+pub fn run(command: &str, event: HookEvent, context: &HookContext) -> Result<()> {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command])
+        .env("CLAUDE_TMUX_EVENT", event.name())
+        .env("CLAUDE_TMUX_SESSION", context.session)
+        .env("CLAUDE_TMUX_PATH", context.path)
+        .env("CLAUDE_TMUX_BRANCH", context.branch.unwrap_or(""))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // The directory may already be gone (e.g. a removed worktree)
+    if context.path.is_dir() {
+        cmd.current_dir(context.path);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Failed to run {} hook", event.name()))?;
+
+    // Reap the child in the background so it never blocks the UI
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+
+    Ok(())
+}
@@ -1,28 +1,242 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use crate::app::{App, CreatePullRequestField, Mode, NewSessionField, NewWorktreeField};
+use crate::app::{
+    is_session_name_char, App, CommitField, CreatePullRequestField, CreateTagField, Edits, Mode,
+    NewSessionField, NewWorktreeField, TextInput,
+};
 
 /// Handle a key event and update the application state
 pub fn handle_key(app: &mut App, key: KeyEvent) {
-    // Clear messages on any key press
-    app.clear_messages();
+    // Clear messages on any key press, or only on Esc when they are sticky
+    if !app.config.sticky_messages {
+        app.clear_messages();
+    } else if key.code == KeyCode::Esc && (app.error.is_some() || app.message.is_some()) {
+        app.clear_messages();
+        // In normal mode this Esc only dismisses, rather than quitting
+        if app.mode == Mode::Normal {
+            return;
+        }
+    }
 
     match &app.mode {
-        Mode::Normal => handle_normal_mode(app, key),
+        Mode::Normal => handle_normal_mode(app, app.keymap.translate(key)),
         Mode::ActionMenu => handle_action_menu_mode(app, key),
         Mode::Filter { .. } => handle_filter_mode(app, key),
+        Mode::PreviewSearch { .. } => handle_preview_search_mode(app, key),
         Mode::ConfirmAction => handle_confirm_action_mode(app, key),
+        Mode::TypeToConfirm { .. } => handle_type_to_confirm_mode(app, key),
         Mode::NewSession { .. } => handle_new_session_mode(app, key),
         Mode::Rename { .. } => handle_rename_mode(app, key),
+        Mode::RenameWindow { .. } => handle_rename_window_mode(app, key),
         Mode::Commit { .. } => handle_commit_mode(app, key),
+        Mode::Squash { .. } => handle_squash_mode(app, key),
         Mode::NewWorktree { .. } => handle_new_worktree_mode(app, key),
         Mode::CreatePullRequest { .. } => handle_create_pr_mode(app, key),
+        Mode::CreateTag { .. } => handle_create_tag_mode(app, key),
+        Mode::Tags { .. } => handle_tags_mode(app, key),
+        Mode::Diff { .. } | Mode::PrComments { .. } => handle_diff_mode(app, key),
+        Mode::DetectDebug { .. } => handle_detect_debug_mode(app, key),
+        Mode::StageFiles { .. } => handle_stage_files_mode(app, key),
+        Mode::ColorTag { .. } => handle_color_tag_mode(app, key),
+        Mode::Compare { .. } => handle_compare_mode(app, key),
+        Mode::Clients { .. } => handle_clients_mode(app, key),
+        Mode::Macros { .. } => handle_macros_mode(app, key),
+        Mode::History { .. } => handle_history_mode(app, key),
+        Mode::BatchResults { .. } => handle_batch_results_mode(app, key),
         Mode::Help => handle_help_mode(app, key),
     }
+
+    // A macro step waiting on a dialog continues once the dialog closes
+    app.resume_macro();
+}
+
+/// Handle a bracketed paste by inserting the text into the active field
+///
+/// Multi-line text is kept for commit, tag and PR messages; other fields
+/// collapse line breaks and apply the same character filtering as typing.
+pub fn handle_paste(app: &mut App, text: &str) {
+    app.clear_messages();
+
+    // Terminals commonly send \r for line breaks in pasted text
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let single_line = text.trim_end_matches('\n').replace('\n', " ");
+
+    match app.mode {
+        Mode::Filter { ref mut input } | Mode::PreviewSearch { ref mut input } => {
+            input.insert_str(&single_line);
+        }
+        Mode::NewSession {
+            ref mut name,
+            ref mut path,
+            ref mut url,
+            field,
+            ref mut path_selected,
+            ..
+        } => {
+            match field {
+                NewSessionField::Name => insert_filtered(name, &single_line, is_session_name_char),
+                NewSessionField::Url => insert_filtered(url, single_line.trim(), is_url_char),
+                NewSessionField::Path => {
+                    path.insert_str(&single_line);
+                    *path_selected = None;
+                }
+            }
+            if field == NewSessionField::Path {
+                app.update_new_session_path_suggestions();
+            }
+        }
+        Mode::Rename {
+            ref mut new_name, ..
+        } => {
+            insert_filtered(new_name, &single_line, is_session_name_char);
+        }
+        Mode::RenameWindow {
+            ref mut new_name, ..
+        } => {
+            new_name.insert_str(&single_line);
+        }
+        Mode::Commit {
+            ref mut message,
+            ref mut author,
+            field,
+            ..
+        } => match field {
+            CommitField::Message => message.insert_str(&text),
+            CommitField::Author => author.insert_str(&single_line),
+        },
+        Mode::Squash {
+            ref mut message, ..
+        } => {
+            message.insert_str(&text);
+        }
+        Mode::NewWorktree {
+            ref mut branch_input,
+            ref mut worktree_path,
+            ref mut session_name,
+            ref mut path_selected,
+            field,
+            ..
+        } => {
+            match field {
+                NewWorktreeField::Branch => {
+                    insert_filtered(branch_input, &single_line, |c| !c.is_whitespace())
+                }
+                NewWorktreeField::Path => {
+                    worktree_path.insert_str(&single_line);
+                    *path_selected = None;
+                }
+                NewWorktreeField::SessionName => {
+                    insert_filtered(session_name, &single_line, is_session_name_char)
+                }
+                NewWorktreeField::DraftPr => {}
+            }
+            match field {
+                NewWorktreeField::Branch => app.update_worktree_suggestions(),
+                NewWorktreeField::Path => app.update_worktree_path_suggestions(),
+                NewWorktreeField::SessionName | NewWorktreeField::DraftPr => {}
+            }
+        }
+        Mode::CreatePullRequest {
+            ref mut title,
+            ref mut body,
+            ref mut base_branch,
+            ref mut issue,
+            field,
+            ..
+        } => match field {
+            CreatePullRequestField::Title => title.insert_str(&single_line),
+            CreatePullRequestField::Body => body.insert_str(&text),
+            CreatePullRequestField::BaseBranch => {
+                insert_filtered(base_branch, &single_line, is_branch_char)
+            }
+            CreatePullRequestField::Issue => insert_filtered(issue, &single_line, is_issue_char),
+            CreatePullRequestField::Draft => {}
+        },
+        Mode::CreateTag {
+            ref mut name,
+            ref mut message,
+            field,
+            ..
+        } => match field {
+            CreateTagField::Name => insert_filtered(name, &single_line, |c| !c.is_whitespace()),
+            CreateTagField::Message => message.insert_str(&text),
+            CreateTagField::Push => {}
+        },
+        _ => {}
+    }
+
+    if app.mode.edits() == Some(Edits::Pristine) {
+        app.mode.set_edits(Edits::Unsaved);
+    }
+}
+
+/// Insert the characters of `text` accepted by `allow` at the cursor
+fn insert_filtered(input: &mut TextInput, text: &str, allow: impl Fn(char) -> bool) {
+    let filtered: String = text.chars().filter(|&c| allow(c)).collect();
+    input.insert_str(&filtered);
+}
+
+/// Apply a text-editing key to a field. Returns true if the text changed.
+///
+/// Handles ←/→, Home/End (also Ctrl-A/Ctrl-E), Backspace, Delete, Ctrl-W
+/// (delete word) and typing characters accepted by `allow`.
+fn edit_text(input: &mut TextInput, key: KeyEvent, allow: impl Fn(char) -> bool) -> bool {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key.code {
+        KeyCode::Left => input.move_left(),
+        KeyCode::Right => input.move_right(),
+        KeyCode::Home => input.move_home(),
+        KeyCode::End => input.move_end(),
+        KeyCode::Char('a') if ctrl => input.move_home(),
+        KeyCode::Char('e') if ctrl => input.move_end(),
+        KeyCode::Char('w') if ctrl => return input.delete_word(),
+        KeyCode::Backspace => return input.backspace(),
+        KeyCode::Delete => return input.delete(),
+        KeyCode::Char(c) if !ctrl && allow(c) => {
+            input.insert(c);
+            return true;
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Characters allowed in a clone URL
+fn is_url_char(c: char) -> bool {
+    !c.is_whitespace()
+}
+
+/// Characters allowed when typing a base branch name
+fn is_branch_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_' || c == '/'
+}
+
+/// Issue numbers are digits; a leading `#` may be typed out of habit
+fn is_issue_char(c: char) -> bool {
+    c.is_ascii_digit() || c == '#'
 }
 
 fn handle_normal_mode(app: &mut App, key: KeyEvent) {
+    // Columns moved by Shift-←/→ in the preview
+    const PREVIEW_COLUMNS: isize = 8;
+
     match key.code {
+        // Reset the view (configurable key, checked before the fixed bindings)
+        KeyCode::Char(c)
+            if c == app.config.reset_key
+                && !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            app.reset_view();
+        }
+
+        // Esc leaves an active preview search before quitting
+        KeyCode::Esc if app.preview_search.is_some() => {
+            app.clear_preview_search();
+        }
+
         // Quit
         KeyCode::Char('q') | KeyCode::Esc => {
             app.should_quit = true;
@@ -35,32 +249,91 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         KeyCode::Char('k') | KeyCode::Up => {
             app.select_prev();
         }
+        KeyCode::Char(']') => {
+            app.select_waiting(true);
+        }
+        KeyCode::Char('[') => {
+            app.select_waiting(false);
+        }
+
+        // Scroll back through the preview's captured history
+        KeyCode::PageUp => {
+            app.scroll_preview(app.preview_height as isize);
+        }
+        KeyCode::PageDown => {
+            app.scroll_preview(-(app.preview_height as isize));
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.scroll_preview((app.preview_height / 2) as isize);
+        }
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.scroll_preview(-((app.preview_height / 2) as isize));
+        }
+
+        // Scroll wide preview lines sideways
+        KeyCode::Left if key.modifiers.contains(KeyModifiers::SHIFT) => {
+            app.scroll_preview_columns(-PREVIEW_COLUMNS);
+        }
+        KeyCode::Right if key.modifiers.contains(KeyModifiers::SHIFT) => {
+            app.scroll_preview_columns(PREVIEW_COLUMNS);
+        }
 
         // Enter action menu
         KeyCode::Char('l') | KeyCode::Right => {
             app.enter_action_menu();
         }
 
+        // Empty list: quick-start a new session instead
+        KeyCode::Enter if app.sessions.is_empty() => {
+            app.start_new_session();
+        }
+
         // Switch to session (quick action)
         KeyCode::Enter => {
             app.switch_to_selected();
         }
 
+        // Jump between preview search matches; without a search, n and N
+        // fall through to the session list bindings below
+        KeyCode::Char('n') | KeyCode::Char('N') if app.preview_search.is_some() => {
+            app.jump_preview_match(key.code == KeyCode::Char('n'));
+        }
+
         // New session
         KeyCode::Char('n') => {
             app.start_new_session();
         }
 
+        // New worktree session from the selected session's repo
+        KeyCode::Char('w') => {
+            app.start_new_worktree();
+        }
+
+        // Session at the root of the selected session's repo
+        KeyCode::Char('G') => {
+            app.open_repo_root();
+        }
+
         // Kill session (capital K to avoid accidents)
         KeyCode::Char('K') => {
             app.start_kill();
         }
 
+        // Refresh just the selected session
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.refresh_selected();
+        }
+
         // Rename session
         KeyCode::Char('r') => {
             app.start_rename();
         }
 
+        // Search the preview
+        KeyCode::Char('s') => {
+            app.start_preview_search();
+        }
+
         // Filter
         KeyCode::Char('/') => {
             app.start_filter();
@@ -71,6 +344,71 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             app.clear_filter();
         }
 
+        // Copy cd command for the selected session
+        KeyCode::Char('y') => {
+            app.copy_cd_command();
+        }
+
+        // Toggle grouping by repository
+        KeyCode::Char('g') => {
+            app.toggle_group_by_repo();
+        }
+
+        // Show only the selected session's repository
+        KeyCode::Char('p') => {
+            app.toggle_repo_filter();
+        }
+
+        // Show or hide sessions matching `hidden_sessions`
+        KeyCode::Char('.') => {
+            app.toggle_show_hidden();
+        }
+
+        // Toggle live preview following
+        KeyCode::Char('F') => {
+            app.toggle_follow_preview();
+        }
+
+        // Recreate the last killed session
+        KeyCode::Char('u') => {
+            app.recreate_killed_session();
+        }
+
+        // Retry a push/fetch/pull that failed to authenticate
+        KeyCode::Char('t') => {
+            app.retry_failed_action();
+        }
+
+        // Run a macro on the selected session
+        KeyCode::Char('M') => {
+            app.show_macros();
+        }
+
+        // Show action history
+        KeyCode::Char('H') => {
+            app.show_history();
+        }
+
+        // Show the clients attached to the tmux server
+        KeyCode::Char('C') => {
+            app.show_clients();
+        }
+
+        // Show how status detection sees the selected session (--debug)
+        KeyCode::Char('D') if app.debug => {
+            app.show_detect_debug();
+        }
+
+        // Cycle the session sort order
+        KeyCode::Char('o') => {
+            app.cycle_sort_mode();
+        }
+
+        // Toggle dense list layout
+        KeyCode::Char('d') => {
+            app.toggle_dense();
+        }
+
         // Refresh
         KeyCode::Char('R') => {
             app.refresh();
@@ -81,6 +419,17 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             app.show_help();
         }
 
+        // Unbound characters start filtering, when enabled
+        KeyCode::Char(c)
+            if app.config.type_to_filter
+                && !c.is_whitespace()
+                && !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            app.start_filter_with(c);
+        }
+
         _ => {}
     }
 }
@@ -93,17 +442,27 @@ fn handle_filter_mode(app: &mut App, key: KeyEvent) {
         KeyCode::Enter => {
             app.apply_filter();
         }
-        KeyCode::Backspace => {
+        _ => {
             if let Mode::Filter { ref mut input } = app.mode {
-                input.pop();
+                edit_text(input, key, |_| true);
             }
         }
-        KeyCode::Char(c) => {
-            if let Mode::Filter { ref mut input } = app.mode {
-                input.push(c);
+    }
+}
+
+fn handle_preview_search_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.cancel();
+        }
+        KeyCode::Enter => {
+            app.apply_preview_search();
+        }
+        _ => {
+            if let Mode::PreviewSearch { ref mut input } = app.mode {
+                edit_text(input, key, |_| true);
             }
         }
-        _ => {}
     }
 }
 
@@ -127,6 +486,11 @@ fn handle_action_menu_mode(app: &mut App, key: KeyEvent) {
             app.cancel();
         }
 
+        // Show or hide the session details above the actions
+        KeyCode::Char('i') => {
+            app.toggle_menu_details();
+        }
+
         // Quit entirely
         KeyCode::Char('q') => {
             app.should_quit = true;
@@ -144,14 +508,39 @@ fn handle_confirm_action_mode(app: &mut App, key: KeyEvent) {
         KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
             app.cancel();
         }
+        KeyCode::Left | KeyCode::Char('h') => {
+            app.cycle_merge_strategy(false);
+        }
+        KeyCode::Right | KeyCode::Char('l') => {
+            app.cycle_merge_strategy(true);
+        }
         _ => {}
     }
 }
 
+fn handle_type_to_confirm_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.cancel();
+        }
+        KeyCode::Enter => {
+            app.confirm_typed();
+        }
+        _ => {
+            if let Mode::TypeToConfirm { ref mut input, .. } = app.mode {
+                edit_text(input, key, |_| true);
+            }
+        }
+    }
+}
+
 fn handle_new_session_mode(app: &mut App, key: KeyEvent) {
     // Get current field to determine behavior
-    let current_field = if let Mode::NewSession { field, .. } = &app.mode {
-        *field
+    let (current_field, path_at_end) = if let Mode::NewSession {
+        field, ref path, ..
+    } = app.mode
+    {
+        (field, path.is_at_end())
     } else {
         return;
     };
@@ -161,11 +550,12 @@ fn handle_new_session_mode(app: &mut App, key: KeyEvent) {
             app.cancel();
         }
         KeyCode::Tab => {
-            // Toggle between name and path fields
+            // Cycle through the name, path and clone URL fields
             if let Mode::NewSession { ref mut field, .. } = app.mode {
                 *field = match field {
                     NewSessionField::Name => NewSessionField::Path,
-                    NewSessionField::Path => NewSessionField::Name,
+                    NewSessionField::Path => NewSessionField::Url,
+                    NewSessionField::Url => NewSessionField::Name,
                 };
             }
         }
@@ -179,60 +569,38 @@ fn handle_new_session_mode(app: &mut App, key: KeyEvent) {
         KeyCode::Down if current_field == NewSessionField::Path => {
             app.select_next_new_session_path();
         }
-        // Accept completion with Right arrow (only when path field is active)
-        KeyCode::Right if current_field == NewSessionField::Path => {
+        // Accept completion with Right arrow at the end of the path
+        KeyCode::Right if current_field == NewSessionField::Path && path_at_end => {
             app.accept_new_session_path_completion();
         }
-        KeyCode::Backspace => {
-            if let Mode::NewSession {
+        _ => {
+            let edited = if let Mode::NewSession {
                 ref mut name,
                 ref mut path,
+                ref mut url,
                 ref field,
                 ref mut path_selected,
                 ..
             } = app.mode
             {
                 match field {
-                    NewSessionField::Name => {
-                        name.pop();
-                    }
+                    NewSessionField::Name => edit_text(name, key, is_session_name_char),
+                    NewSessionField::Url => edit_text(url, key, is_url_char),
                     NewSessionField::Path => {
-                        path.pop();
-                        *path_selected = None; // Reset selection on edit
-                    }
-                }
-            }
-            if current_field == NewSessionField::Path {
-                app.update_new_session_path_suggestions();
-            }
-        }
-        KeyCode::Char(c) => {
-            if let Mode::NewSession {
-                ref mut name,
-                ref mut path,
-                ref field,
-                ref mut path_selected,
-                ..
-            } = app.mode
-            {
-                match field {
-                    NewSessionField::Name => {
-                        // Only allow valid session name characters
-                        if c.is_alphanumeric() || c == '-' || c == '_' {
-                            name.push(c);
+                        let edited = edit_text(path, key, |_| true);
+                        if edited {
+                            *path_selected = None; // Reset selection on edit
                         }
-                    }
-                    NewSessionField::Path => {
-                        path.push(c);
-                        *path_selected = None; // Reset selection on edit
+                        edited
                     }
                 }
-            }
-            if current_field == NewSessionField::Path {
+            } else {
+                false
+            };
+            if edited && current_field == NewSessionField::Path {
                 app.update_new_session_path_suggestions();
             }
         }
-        _ => {}
     }
 }
 
@@ -244,49 +612,143 @@ fn handle_rename_mode(app: &mut App, key: KeyEvent) {
         KeyCode::Enter => {
             app.confirm_rename();
         }
-        KeyCode::Backspace => {
-            if let Mode::Rename { ref mut new_name, .. } = app.mode {
-                new_name.pop();
+        _ => {
+            if let Mode::Rename {
+                ref mut new_name, ..
+            } = app.mode
+            {
+                edit_text(new_name, key, is_session_name_char);
             }
         }
-        KeyCode::Char(c) => {
-            if let Mode::Rename { ref mut new_name, .. } = app.mode {
-                // Only allow valid session name characters
-                if c.is_alphanumeric() || c == '-' || c == '_' {
-                    new_name.push(c);
-                }
+    }
+}
+
+fn handle_rename_window_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.cancel();
+        }
+        KeyCode::Enter => {
+            app.confirm_rename_window();
+        }
+        KeyCode::Tab => {
+            app.cycle_rename_window(true);
+        }
+        KeyCode::BackTab => {
+            app.cycle_rename_window(false);
+        }
+        _ => {
+            if let Mode::RenameWindow {
+                ref mut new_name, ..
+            } = app.mode
+            {
+                edit_text(new_name, key, |_| true);
             }
         }
-        _ => {}
+    }
+}
+
+/// Guard a dialog's typed input against Esc: the first Esc asks whether to
+/// discard it, which `y` or Enter confirms and any other key takes back.
+/// Returns true if the key was used for this.
+fn guard_unsaved_input(app: &mut App, key: KeyEvent) -> bool {
+    match (app.mode.edits(), key.code) {
+        (Some(Edits::ConfirmDiscard), KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y')) => {
+            app.cancel();
+        }
+        (Some(Edits::ConfirmDiscard), _) => app.mode.set_edits(Edits::Unsaved),
+        (Some(Edits::Unsaved), KeyCode::Esc) => app.mode.set_edits(Edits::ConfirmDiscard),
+        _ => return false,
+    }
+    true
+}
+
+/// Record that a guarded dialog's text was changed
+fn mark_edited(app: &mut App, edited: bool) {
+    if edited {
+        app.mode.set_edits(Edits::Unsaved);
     }
 }
 
 fn handle_commit_mode(app: &mut App, key: KeyEvent) {
+    if guard_unsaved_input(app, key) {
+        return;
+    }
+
     match key.code {
         KeyCode::Esc => {
             app.cancel();
         }
+        KeyCode::Tab | KeyCode::BackTab => {
+            if let Mode::Commit { ref mut field, .. } = app.mode {
+                *field = match field {
+                    CommitField::Message => CommitField::Author,
+                    CommitField::Author => CommitField::Message,
+                };
+            }
+        }
         KeyCode::Enter => {
             app.confirm_commit();
         }
-        KeyCode::Backspace => {
-            if let Mode::Commit { ref mut message } = app.mode {
-                message.pop();
+        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_external_editor();
+        }
+        _ => {
+            if let Mode::Commit {
+                ref mut message,
+                ref mut author,
+                field,
+                ..
+            } = app.mode
+            {
+                let edited = match field {
+                    CommitField::Message => edit_text(message, key, |_| true),
+                    CommitField::Author => edit_text(author, key, |_| true),
+                };
+                mark_edited(app, edited);
             }
         }
-        KeyCode::Char(c) => {
-            if let Mode::Commit { ref mut message } = app.mode {
-                message.push(c);
+    }
+}
+
+fn handle_squash_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.cancel();
+        }
+        KeyCode::Enter => {
+            app.confirm_squash();
+        }
+        _ => {
+            if let Mode::Squash {
+                ref mut message, ..
+            } = app.mode
+            {
+                edit_text(message, key, |_| true);
             }
         }
-        _ => {}
     }
 }
 
 fn handle_new_worktree_mode(app: &mut App, key: KeyEvent) {
+    if guard_unsaved_input(app, key) {
+        return;
+    }
+
     // Get current field to determine behavior
-    let current_field = if let Mode::NewWorktree { field, .. } = &app.mode {
-        *field
+    let (current_field, field_at_end) = if let Mode::NewWorktree {
+        field,
+        ref branch_input,
+        ref worktree_path,
+        ..
+    } = app.mode
+    {
+        let at_end = match field {
+            NewWorktreeField::Branch => branch_input.is_at_end(),
+            NewWorktreeField::Path => worktree_path.is_at_end(),
+            NewWorktreeField::SessionName | NewWorktreeField::DraftPr => false,
+        };
+        (field, at_end)
     } else {
         return;
     };
@@ -301,7 +763,8 @@ fn handle_new_worktree_mode(app: &mut App, key: KeyEvent) {
                 *field = match field {
                     NewWorktreeField::Branch => NewWorktreeField::Path,
                     NewWorktreeField::Path => NewWorktreeField::SessionName,
-                    NewWorktreeField::SessionName => NewWorktreeField::Branch,
+                    NewWorktreeField::SessionName => NewWorktreeField::DraftPr,
+                    NewWorktreeField::DraftPr => NewWorktreeField::Branch,
                 };
             }
         }
@@ -309,78 +772,16 @@ fn handle_new_worktree_mode(app: &mut App, key: KeyEvent) {
             // Cycle backwards through fields
             if let Mode::NewWorktree { ref mut field, .. } = app.mode {
                 *field = match field {
-                    NewWorktreeField::Branch => NewWorktreeField::SessionName,
+                    NewWorktreeField::Branch => NewWorktreeField::DraftPr,
                     NewWorktreeField::Path => NewWorktreeField::Branch,
                     NewWorktreeField::SessionName => NewWorktreeField::Path,
+                    NewWorktreeField::DraftPr => NewWorktreeField::SessionName,
                 };
             }
         }
         KeyCode::Enter => {
             app.confirm_new_worktree();
         }
-        KeyCode::Backspace => {
-            if let Mode::NewWorktree {
-                ref mut branch_input,
-                ref mut worktree_path,
-                ref mut session_name,
-                ref mut path_selected,
-                field,
-                ..
-            } = app.mode
-            {
-                match field {
-                    NewWorktreeField::Branch => {
-                        branch_input.pop();
-                    }
-                    NewWorktreeField::Path => {
-                        worktree_path.pop();
-                        *path_selected = None; // Reset selection on edit
-                    }
-                    NewWorktreeField::SessionName => {
-                        session_name.pop();
-                    }
-                }
-            }
-            // Update suggestions after input changes
-            if current_field == NewWorktreeField::Branch {
-                app.update_worktree_suggestions();
-            } else if current_field == NewWorktreeField::Path {
-                app.update_worktree_path_suggestions();
-            }
-        }
-        KeyCode::Char(c) => {
-            if let Mode::NewWorktree {
-                ref mut branch_input,
-                ref mut worktree_path,
-                ref mut session_name,
-                ref mut path_selected,
-                field,
-                ..
-            } = app.mode
-            {
-                match field {
-                    NewWorktreeField::Branch => {
-                        branch_input.push(c);
-                    }
-                    NewWorktreeField::Path => {
-                        worktree_path.push(c);
-                        *path_selected = None; // Reset selection on edit
-                    }
-                    NewWorktreeField::SessionName => {
-                        // Only allow valid session name characters
-                        if c.is_alphanumeric() || c == '-' || c == '_' {
-                            session_name.push(c);
-                        }
-                    }
-                }
-            }
-            // Update suggestions after input changes
-            if current_field == NewWorktreeField::Branch {
-                app.update_worktree_suggestions();
-            } else if current_field == NewWorktreeField::Path {
-                app.update_worktree_path_suggestions();
-            }
-        }
         // Navigate branch suggestions when in Branch field
         KeyCode::Down if current_field == NewWorktreeField::Branch => {
             let filtered_count = app.filtered_branches().len();
@@ -413,8 +814,8 @@ fn handle_new_worktree_mode(app: &mut App, key: KeyEvent) {
                 app.update_worktree_suggestions();
             }
         }
-        // Accept branch completion with Right arrow
-        KeyCode::Right if current_field == NewWorktreeField::Branch => {
+        // Accept branch completion with Right arrow at the end of the input
+        KeyCode::Right if current_field == NewWorktreeField::Branch && field_at_end => {
             app.accept_branch_completion();
         }
         // Navigate path suggestions when in Path field
@@ -424,15 +825,59 @@ fn handle_new_worktree_mode(app: &mut App, key: KeyEvent) {
         KeyCode::Up if current_field == NewWorktreeField::Path => {
             app.select_prev_worktree_path();
         }
-        // Accept path completion with Right arrow
-        KeyCode::Right if current_field == NewWorktreeField::Path => {
+        // Accept path completion with Right arrow at the end of the path
+        KeyCode::Right if current_field == NewWorktreeField::Path && field_at_end => {
             app.accept_worktree_path_completion();
         }
-        _ => {}
+        _ => {
+            let edited = if let Mode::NewWorktree {
+                ref mut branch_input,
+                ref mut worktree_path,
+                ref mut session_name,
+                ref mut path_selected,
+                ref mut draft_pr,
+                field,
+                ..
+            } = app.mode
+            {
+                match field {
+                    NewWorktreeField::Branch => edit_text(branch_input, key, |_| true),
+                    NewWorktreeField::Path => {
+                        let edited = edit_text(worktree_path, key, |_| true);
+                        if edited {
+                            *path_selected = None; // Reset selection on edit
+                        }
+                        edited
+                    }
+                    NewWorktreeField::SessionName => {
+                        edit_text(session_name, key, is_session_name_char)
+                    }
+                    NewWorktreeField::DraftPr => {
+                        if key.code == KeyCode::Char(' ') {
+                            *draft_pr = !*draft_pr;
+                        }
+                        false
+                    }
+                }
+            } else {
+                false
+            };
+            mark_edited(app, edited);
+            // Update suggestions after input changes
+            if edited && current_field == NewWorktreeField::Branch {
+                app.update_worktree_suggestions();
+            } else if edited && current_field == NewWorktreeField::Path {
+                app.update_worktree_path_suggestions();
+            }
+        }
     }
 }
 
 fn handle_create_pr_mode(app: &mut App, key: KeyEvent) {
+    if guard_unsaved_input(app, key) {
+        return;
+    }
+
     match key.code {
         KeyCode::Esc => {
             app.cancel();
@@ -443,7 +888,9 @@ fn handle_create_pr_mode(app: &mut App, key: KeyEvent) {
                 *field = match field {
                     CreatePullRequestField::Title => CreatePullRequestField::Body,
                     CreatePullRequestField::Body => CreatePullRequestField::BaseBranch,
-                    CreatePullRequestField::BaseBranch => CreatePullRequestField::Title,
+                    CreatePullRequestField::BaseBranch => CreatePullRequestField::Issue,
+                    CreatePullRequestField::Issue => CreatePullRequestField::Draft,
+                    CreatePullRequestField::Draft => CreatePullRequestField::Title,
                 };
             }
         }
@@ -451,60 +898,338 @@ fn handle_create_pr_mode(app: &mut App, key: KeyEvent) {
             // Cycle backwards through fields
             if let Mode::CreatePullRequest { ref mut field, .. } = app.mode {
                 *field = match field {
-                    CreatePullRequestField::Title => CreatePullRequestField::BaseBranch,
+                    CreatePullRequestField::Title => CreatePullRequestField::Draft,
                     CreatePullRequestField::Body => CreatePullRequestField::Title,
                     CreatePullRequestField::BaseBranch => CreatePullRequestField::Body,
+                    CreatePullRequestField::Issue => CreatePullRequestField::BaseBranch,
+                    CreatePullRequestField::Draft => CreatePullRequestField::Issue,
                 };
             }
         }
         KeyCode::Enter => {
             app.confirm_create_pull_request();
         }
-        KeyCode::Backspace => {
+        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_external_editor();
+        }
+        _ => {
             if let Mode::CreatePullRequest {
                 ref mut title,
                 ref mut body,
                 ref mut base_branch,
+                ref mut issue,
+                ref mut draft,
                 field,
+                ..
             } = app.mode
             {
-                match field {
-                    CreatePullRequestField::Title => {
-                        title.pop();
-                    }
-                    CreatePullRequestField::Body => {
-                        body.pop();
-                    }
+                let edited = match field {
+                    CreatePullRequestField::Title => edit_text(title, key, |_| true),
+                    CreatePullRequestField::Body => edit_text(body, key, |_| true),
+                    // Branch names have specific allowed characters
                     CreatePullRequestField::BaseBranch => {
-                        base_branch.pop();
+                        edit_text(base_branch, key, is_branch_char)
                     }
-                }
+                    CreatePullRequestField::Issue => edit_text(issue, key, is_issue_char),
+                    CreatePullRequestField::Draft => {
+                        if key.code == KeyCode::Char(' ') {
+                            *draft = !*draft;
+                        }
+                        false
+                    }
+                };
+                mark_edited(app, edited);
             }
         }
-        KeyCode::Char(c) => {
-            if let Mode::CreatePullRequest {
-                ref mut title,
-                ref mut body,
-                ref mut base_branch,
+    }
+}
+
+fn handle_create_tag_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.cancel();
+        }
+        KeyCode::Tab => {
+            // Cycle through fields
+            if let Mode::CreateTag { ref mut field, .. } = app.mode {
+                *field = match field {
+                    CreateTagField::Name => CreateTagField::Message,
+                    CreateTagField::Message => CreateTagField::Push,
+                    CreateTagField::Push => CreateTagField::Name,
+                };
+            }
+        }
+        KeyCode::BackTab => {
+            // Cycle backwards through fields
+            if let Mode::CreateTag { ref mut field, .. } = app.mode {
+                *field = match field {
+                    CreateTagField::Name => CreateTagField::Push,
+                    CreateTagField::Message => CreateTagField::Name,
+                    CreateTagField::Push => CreateTagField::Message,
+                };
+            }
+        }
+        KeyCode::Enter => {
+            app.confirm_create_tag();
+        }
+        _ => {
+            if let Mode::CreateTag {
+                ref mut name,
+                ref mut message,
+                ref mut push,
                 field,
             } = app.mode
             {
                 match field {
-                    CreatePullRequestField::Title => {
-                        title.push(c);
+                    // Whitespace is never valid in a ref name
+                    CreateTagField::Name => {
+                        edit_text(name, key, |c| !c.is_whitespace());
                     }
-                    CreatePullRequestField::Body => {
-                        body.push(c);
+                    CreateTagField::Message => {
+                        edit_text(message, key, |_| true);
                     }
-                    CreatePullRequestField::BaseBranch => {
-                        // Branch names have specific allowed characters
-                        if c.is_alphanumeric() || c == '-' || c == '_' || c == '/' {
-                            base_branch.push(c);
+                    CreateTagField::Push => {
+                        if key.code == KeyCode::Char(' ') {
+                            *push = !*push;
                         }
                     }
                 }
             }
         }
+    }
+}
+
+fn handle_tags_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.scroll_tags(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.scroll_tags(-1);
+        }
+        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
+            app.cancel();
+        }
+        _ => {}
+    }
+}
+
+fn handle_diff_mode(app: &mut App, key: KeyEvent) {
+    // Lines moved by a page key
+    const PAGE: isize = 20;
+
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.scroll_diff(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.scroll_diff(-1);
+        }
+        KeyCode::PageDown | KeyCode::Char(' ') => {
+            app.scroll_diff(PAGE);
+        }
+        KeyCode::PageUp => {
+            app.scroll_diff(-PAGE);
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.scroll_diff(isize::MIN);
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.scroll_diff(isize::MAX);
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.cancel();
+        }
+        _ => {}
+    }
+}
+
+fn handle_detect_debug_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.scroll_detect_debug(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.scroll_detect_debug(-1);
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.scroll_detect_debug(isize::MIN);
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.scroll_detect_debug(isize::MAX);
+        }
+        KeyCode::Char('r') => {
+            app.show_detect_debug();
+        }
+        KeyCode::Char('D') | KeyCode::Char('q') | KeyCode::Esc => {
+            app.cancel();
+        }
+        _ => {}
+    }
+}
+
+fn handle_color_tag_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.select_color_tag(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.select_color_tag(-1);
+        }
+        KeyCode::Enter => {
+            app.confirm_color_tag();
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.cancel();
+        }
+        _ => {}
+    }
+}
+
+fn handle_compare_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.select_compare(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.select_compare(-1);
+        }
+        KeyCode::Enter => {
+            app.confirm_compare();
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.cancel();
+        }
+        _ => {}
+    }
+}
+
+fn handle_clients_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.select_client(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.select_client(-1);
+        }
+        KeyCode::Enter => {
+            app.switch_highlighted_client();
+        }
+        KeyCode::Char('d') => {
+            app.detach_highlighted_client();
+        }
+        KeyCode::Char('r') => {
+            app.reload_clients();
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.cancel();
+        }
+        _ => {}
+    }
+}
+
+fn handle_macros_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.select_macro(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.select_macro(-1);
+        }
+        KeyCode::Enter => {
+            app.run_selected_macro();
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.cancel();
+        }
+        _ => {}
+    }
+}
+
+fn handle_batch_results_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter | KeyCode::Char('q') | KeyCode::Esc => {
+            app.cancel();
+        }
+        _ => {}
+    }
+}
+
+fn handle_history_mode(app: &mut App, key: KeyEvent) {
+    if matches!(
+        app.mode,
+        Mode::History {
+            confirm_clear: true,
+            ..
+        }
+    ) {
+        match key.code {
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                app.clear_history();
+            }
+            _ => {
+                app.cancel_clear_history();
+            }
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Char('C') => {
+            app.start_clear_history();
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.scroll_history(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.scroll_history(-1);
+        }
+        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
+            app.cancel();
+        }
+        _ => {}
+    }
+}
+
+fn handle_stage_files_mode(app: &mut App, key: KeyEvent) {
+    let confirm_discard = matches!(
+        app.mode,
+        Mode::StageFiles {
+            confirm_discard: true,
+            ..
+        }
+    );
+
+    if confirm_discard {
+        match key.code {
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                app.confirm_discard_highlighted_file();
+            }
+            _ => {
+                app.cancel_discard();
+            }
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.move_stage_files_selection(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.move_stage_files_selection(-1);
+        }
+        // Stage or unstage the highlighted file
+        KeyCode::Char(' ') => {
+            app.toggle_stage_highlighted_file();
+        }
+        // Discard the highlighted file's changes (asks first)
+        KeyCode::Char('x') => {
+            app.start_discard_highlighted_file();
+        }
+        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
+            app.cancel();
+        }
         _ => {}
     }
 }
@@ -0,0 +1,177 @@
+//! Key remapping for the session list
+//!
+//! The `[keymap]` config table binds extra keys to the built-in ones: an
+//! entry `"x" = "K"` makes `x` do what `K` does. Keys are a single character
+//! or a name (`enter`, `esc`, `tab`, `space`, `up`, `pageup`, `f1`, ...),
+//! optionally prefixed by `ctrl-`, `alt-` and `shift-`. Mappings apply to
+//! the session list only, so typing in dialogs is unaffected.
+
+use std::collections::BTreeMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A key with its modifiers, as written in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Parse a key such as `x`, `ctrl-r`, `shift-left` or `pagedown`
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = spec;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl-") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt-") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift-") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            // A lone `-` after a prefix is the minus key itself
+            if rest.len() == len {
+                break;
+            }
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+
+        Some(Self { code, modifiers })
+    }
+
+    /// Whether `event` is this key. Shift is ignored for characters, since
+    /// terminals differ in reporting it alongside an uppercase letter.
+    fn matches(&self, event: &KeyEvent) -> bool {
+        let relevant = |modifiers: KeyModifiers| match self.code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        event.code == self.code && relevant(event.modifiers) == relevant(self.modifiers)
+    }
+
+    /// The key event this key stands for
+    fn event(&self) -> KeyEvent {
+        KeyEvent::new(self.code, self.modifiers)
+    }
+}
+
+/// Extra keys for the session list, each standing in for a built-in key
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: Vec<(Key, Key)>,
+}
+
+impl Keymap {
+    /// Build the keymap from `[keymap]` entries. Entries whose key or
+    /// target does not parse are skipped and returned, to be reported.
+    pub fn from_config(entries: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut bindings = Vec::new();
+        let mut invalid = Vec::new();
+        for (key, target) in entries {
+            match (Key::parse(key), Key::parse(target)) {
+                (Some(key), Some(target)) => bindings.push((key, target)),
+                _ => invalid.push(format!("\"{}\" = \"{}\"", key, target)),
+            }
+        }
+        (Self { bindings }, invalid)
+    }
+
+    /// The built-in key `event` is mapped to, or `event` itself
+    pub fn translate(&self, event: KeyEvent) -> KeyEvent {
+        self.bindings
+            .iter()
+            .find(|(key, _)| key.matches(&event))
+            .map(|(_, target)| target.event())
+            .unwrap_or(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        let key = |code, modifiers| Some(Key { code, modifiers });
+        assert_eq!(Key::parse("x"), key(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert_eq!(Key::parse("K"), key(KeyCode::Char('K'), KeyModifiers::NONE));
+        assert_eq!(
+            Key::parse("ctrl-r"),
+            key(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            Key::parse("Shift-Left"),
+            key(KeyCode::Left, KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            Key::parse("alt--"),
+            key(KeyCode::Char('-'), KeyModifiers::ALT)
+        );
+        assert_eq!(Key::parse("-"), key(KeyCode::Char('-'), KeyModifiers::NONE));
+        assert_eq!(
+            Key::parse("pagedown"),
+            key(KeyCode::PageDown, KeyModifiers::NONE)
+        );
+        assert_eq!(Key::parse("f5"), key(KeyCode::F(5), KeyModifiers::NONE));
+        assert_eq!(Key::parse("ctrl-nope"), None);
+        assert_eq!(Key::parse(""), None);
+    }
+
+    #[test]
+    fn test_translate() {
+        let entries = BTreeMap::from([
+            ("x".to_string(), "K".to_string()),
+            ("ctrl-n".to_string(), "down".to_string()),
+            ("bogus".to_string(), "j".to_string()),
+        ]);
+        let (keymap, invalid) = Keymap::from_config(&entries);
+        assert_eq!(invalid, vec!["\"bogus\" = \"j\"".to_string()]);
+
+        let press = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert_eq!(
+            keymap
+                .translate(press(KeyCode::Char('x'), KeyModifiers::NONE))
+                .code,
+            KeyCode::Char('K')
+        );
+        assert_eq!(
+            keymap
+                .translate(press(KeyCode::Char('n'), KeyModifiers::CONTROL))
+                .code,
+            KeyCode::Down
+        );
+        // Unmapped keys, and mapped ones with other modifiers, pass through
+        let plain_n = press(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(keymap.translate(plain_n), plain_n);
+        let alt_x = press(KeyCode::Char('x'), KeyModifiers::ALT);
+        assert_eq!(keymap.translate(alt_x), alt_x);
+    }
+}
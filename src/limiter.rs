@@ -0,0 +1,100 @@
+//! Cap on concurrent background work
+//!
+//! Background tasks that run subprocesses (e.g. pull request lookups) take a
+//! permit first, so that with many sessions at most `background_jobs` of
+//! them run at once.
+
+use std::sync::{Condvar, Mutex, OnceLock};
+
+/// Limit shared by all background tasks
+static LIMITER: OnceLock<Limiter> = OnceLock::new();
+
+/// A counting semaphore
+#[derive(Debug)]
+pub struct Limiter {
+    max: usize,
+    running: Mutex<usize>,
+    freed: Condvar,
+}
+
+/// A slot in the limiter, given back when dropped
+pub struct Permit<'a> {
+    limiter: &'a Limiter,
+}
+
+impl Limiter {
+    /// A limiter letting `max` tasks run at once (at least one)
+    pub fn new(max: usize) -> Self {
+        Self {
+            max: max.max(1),
+            running: Mutex::new(0),
+            freed: Condvar::new(),
+        }
+    }
+
+    /// Wait for a free slot
+    pub fn acquire(&self) -> Permit<'_> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        while *running >= self.max {
+            running = self.freed.wait(running).unwrap_or_else(|e| e.into_inner());
+        }
+        *running += 1;
+        Permit { limiter: self }
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        let mut running = self
+            .limiter
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *running -= 1;
+        self.limiter.freed.notify_one();
+    }
+}
+
+/// Set the shared limit; `None` uses the number of CPUs. Only the first
+/// call (before any background task starts) has an effect.
+pub fn configure(max: Option<usize>) {
+    let _ = LIMITER.set(Limiter::new(max.unwrap_or_else(cpu_count)));
+}
+
+/// Wait for a slot in the shared limiter
+pub fn acquire() -> Permit<'static> {
+    LIMITER.get_or_init(|| Limiter::new(cpu_count())).acquire()
+}
+
+fn cpu_count() -> usize {
+    std::thread::available_parallelism().map_or(4, |n| n.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_limiter() {
+        let limiter = Limiter::new(2);
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            for _ in 0..6 {
+                scope.spawn(|| {
+                    let _permit = limiter.acquire();
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(*limiter.running.lock().unwrap(), 0);
+    }
+}
@@ -1,49 +1,104 @@
 mod app;
+mod cli;
+mod clipboard;
+mod color_tags;
 mod completion;
+mod config;
 mod detection;
+mod editor;
 mod git;
+mod history;
+mod hooks;
 mod input;
+mod keymap;
+mod limiter;
 mod scroll_state;
 mod session;
 mod tmux;
 mod ui;
 
-use std::io::{self, stdout};
+use std::io::{self, stdout, Write};
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{
+        self, DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange,
+        Event,
+    },
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    },
     ExecutableCommand,
 };
 use ratatui::prelude::*;
 
 use crate::app::App;
+use crate::cli::Cli;
+use crate::config::Config;
+use crate::tmux::Tmux;
 
 fn main() -> Result<()> {
+    let cli = Cli::from_env()?;
+    if let Some(ref profile) = cli.profile {
+        Config::select_profile(profile);
+    }
+    if cli::run(&cli.command)? {
+        return Ok(());
+    }
+
+    let mut app = App::new()?;
+    app.debug = cli.debug;
+    app.presentation = cli.presentation;
+    app.quit_after_action |= cli.quit_after_action;
+    let saved_title = if app.config.terminal_title {
+        Some(save_title()?)
+    } else {
+        None
+    };
+
     // Set up terminal
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableBracketedPaste)?;
+    if app.config.refresh_on_focus {
+        stdout().execute(EnableFocusChange)?;
+    }
 
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
 
     // Run the app
-    let result = run(&mut terminal);
+    let result = run(&mut terminal, &mut app);
 
     // Restore terminal
     disable_raw_mode()?;
+    stdout().execute(DisableBracketedPaste)?;
+    if app.config.refresh_on_focus {
+        stdout().execute(DisableFocusChange)?;
+    }
     stdout().execute(LeaveAlternateScreen)?;
+    if let Some(saved) = saved_title {
+        restore_title(saved)?;
+    }
 
     result
 }
 
-fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
-    let mut app = App::new()?;
+fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) -> Result<()> {
+    let mut title = String::new();
 
     loop {
         // Draw the UI
-        terminal.draw(|frame| ui::render(frame, &mut app))?;
+        terminal.draw(|frame| ui::render(frame, app))?;
+
+        // Keep the terminal title in step with the status counts
+        if app.config.terminal_title {
+            let current = app.terminal_title();
+            if current != title {
+                stdout().execute(SetTitle(&current))?;
+                title = current;
+            }
+        }
 
         // Check if we should quit
         if app.should_quit {
@@ -52,14 +107,83 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
 
         // Handle events
         if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                input::handle_key(&mut app, key);
+            match event::read()? {
+                Event::Key(key) => input::handle_key(app, key),
+                Event::Paste(text) => input::handle_paste(app, &text),
+                Event::FocusGained => app.refresh_on_focus(),
+                _ => {}
             }
         }
 
+        // Hand the terminal to $EDITOR for a dialog's long text field
+        if let Some(text) = app.external_edit.take() {
+            let result = suspend(terminal, || editor::edit(&text))?;
+            app.finish_external_edit(result);
+        }
+
         // Refresh Claude status via content-change detection (self-throttled to 500 ms)
         app.tick_status();
+
+        // Re-capture the preview every tick while following
+        app.tick_preview();
+
+        // Create the session of a clone once it has finished
+        app.tick_clone();
+
+        // Retry a pull request lookup for the action menu that failed transiently
+        app.tick_pr_retry();
+    }
+
+    Ok(())
+}
+
+/// Leave the TUI while `f` runs with the terminal, e.g. an editor, then
+/// restore it and redraw from scratch
+fn suspend<T>(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    f: impl FnOnce() -> T,
+) -> Result<T> {
+    disable_raw_mode()?;
+    stdout().execute(DisableBracketedPaste)?;
+    stdout().execute(LeaveAlternateScreen)?;
+
+    let result = f();
+
+    stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableBracketedPaste)?;
+    enable_raw_mode()?;
+    terminal.clear()?;
+
+    Ok(result)
+}
+
+/// Push the terminal title onto the xterm title stack before changing it.
+///
+/// tmux does not keep a title stack, so inside tmux the current pane title
+/// is also returned to be set back on exit.
+fn save_title() -> io::Result<Option<String>> {
+    let mut out = stdout();
+    out.write_all(b"\x1b[22;0t")?;
+    out.flush()?;
+
+    if std::env::var_os("TMUX").is_some() {
+        Ok(Tmux::pane_title())
+    } else {
+        Ok(None)
     }
+}
 
+/// Restore the title saved by `save_title`
+fn restore_title(saved: Option<String>) -> io::Result<()> {
+    match saved {
+        Some(title) => {
+            stdout().execute(SetTitle(title))?;
+        }
+        None => {
+            let mut out = stdout();
+            out.write_all(b"\x1b[23;0t")?;
+            out.flush()?;
+        }
+    }
     Ok(())
 }
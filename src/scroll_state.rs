@@ -4,6 +4,8 @@
 //! until the bottom items become visible, then the selection moves to the
 //! bottom portion of the view.
 
+use std::ops::Range;
+
 use ratatui::widgets::ListState;
 
 /// Manages scroll state for a list with center-locked scrolling.
@@ -27,7 +29,8 @@ impl ScrollState {
 
     /// Update the scroll state given the current selection and list dimensions.
     ///
-    /// Returns a mutable reference to the underlying ListState for rendering.
+    /// Returns the range of items inside the visible area. Only these need
+    /// to be built: the list state from `list_state` expects exactly them.
     ///
     /// # Arguments
     /// * `selected` - The index of the currently selected item
@@ -38,14 +41,20 @@ impl ScrollState {
         selected: usize,
         total_items: usize,
         visible_height: usize,
-    ) -> &mut ListState {
-        // Set the selected item
-        self.list_state.select(Some(selected));
-
-        // Compute and set centered offset
+    ) -> Range<usize> {
         let offset = Self::compute_centered_offset(selected, total_items, visible_height);
-        *self.list_state.offset_mut() = offset;
 
+        // The widget is only given the visible items, so its selection is
+        // relative to the window and it never scrolls itself
+        self.list_state
+            .select(Some(selected.saturating_sub(offset)));
+        *self.list_state.offset_mut() = 0;
+
+        offset..total_items.min(offset + visible_height)
+    }
+
+    /// The underlying ListState for rendering the items of the last `update`
+    pub fn list_state(&mut self) -> &mut ListState {
         &mut self.list_state
     }
 
@@ -112,6 +121,18 @@ mod tests {
         assert_eq!(ScrollState::compute_centered_offset(19, 20, 10), 10);
     }
 
+    #[test]
+    fn test_update_visible_range() {
+        let mut state = ScrollState::new();
+        // Offset 2: items 2..12 are built, the selection is 5th of them
+        assert_eq!(state.update(7, 20, 10), 2..12);
+        assert_eq!(state.list_state().selected(), Some(5));
+        assert_eq!(state.list_state().offset(), 0);
+        // Short list: everything is visible
+        assert_eq!(state.update(3, 5, 10), 0..5);
+        assert_eq!(state.list_state().selected(), Some(3));
+    }
+
     #[test]
     fn test_edge_cases() {
         // Empty list
@@ -1,9 +1,13 @@
+use std::cell::OnceCell;
 use std::path::PathBuf;
 
+use serde::Deserialize;
+
 use crate::git::GitContext;
 
 /// Status of a Claude Code instance in a pane
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ClaudeCodeStatus {
     /// Waiting at prompt, ready for input
     Idle,
@@ -51,6 +55,32 @@ pub struct Pane {
     pub window_index: String,
     /// Window name this pane belongs to
     pub window_name: String,
+    /// Whether this is the focused pane of the session's active window
+    pub active: bool,
+    /// Whether the pane is showing the alternate screen (full-screen mode)
+    pub alternate_on: bool,
+}
+
+impl Pane {
+    /// Whether the pane is sitting at a shell prompt
+    pub fn is_shell(&self) -> bool {
+        SHELLS.contains(&self.current_command.as_str())
+    }
+}
+
+/// A tmux client: a terminal attached to the tmux server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    /// Terminal the client runs in, which identifies it (e.g., "/dev/pts/3")
+    pub tty: String,
+    /// Session the client is showing
+    pub session: String,
+    /// Terminal size, `WIDTHxHEIGHT`
+    pub size: String,
+    /// Terminal type (e.g., "xterm-256color")
+    pub termname: String,
+    /// Whether the client is read-only
+    pub read_only: bool,
 }
 
 /// A tmux session that may contain a Claude Code instance
@@ -61,9 +91,11 @@ pub struct Session {
     pub name: String,
     /// Unix timestamp when session was created
     pub created: i64,
+    /// Unix timestamp of the session's last activity (output or input)
+    pub activity: i64,
     /// Whether a client is attached to this session
     pub attached: bool,
-    /// Working directory (from the Claude Code pane, or first pane)
+    /// Working directory (from the pane chosen by the `directory_pane` config)
     pub working_directory: PathBuf,
     /// Number of windows in this session
     pub window_count: usize,
@@ -79,8 +111,15 @@ pub struct Session {
     /// Window index to target when switching, if this row represents a
     /// specific claude pane within a multi-pane session.
     pub target_window_index: Option<String>,
-    /// Git context, if the working directory is a git repository
-    pub git_context: Option<GitContext>,
+    /// Git context of the working directory, detected on first use (see
+    /// `git_context`) so that listing many sessions stays cheap
+    pub git: OnceCell<Option<GitContext>>,
+    /// CPU use of the session's processes, in percent of one core. Only
+    /// measured for the session expanded in the action menu.
+    pub cpu_percent: Option<f64>,
+    /// Resident memory of the session's processes, in KiB, measured along
+    /// with `cpu_percent`
+    pub memory_kb: Option<u64>,
 }
 
 impl Session {
@@ -110,6 +149,61 @@ impl Session {
         }
     }
 
+    /// Pane to type a shell command into: the first one at a shell prompt,
+    /// so the command does not end up in Claude Code's input, then the
+    /// Claude Code pane, then the first pane
+    pub fn command_pane(&self) -> Option<&Pane> {
+        self.panes
+            .iter()
+            .find(|p| p.is_shell())
+            .or_else(|| {
+                let claude = self.claude_code_pane.as_ref()?;
+                self.panes.iter().find(|p| p.id == *claude)
+            })
+            .or_else(|| self.panes.first())
+    }
+
+    /// The session's windows as `(index, name)`, in pane order
+    pub fn windows(&self) -> Vec<(String, String)> {
+        let mut windows: Vec<(String, String)> = Vec::new();
+        for pane in &self.panes {
+            if !windows.iter().any(|(index, _)| index == &pane.window_index) {
+                windows.push((pane.window_index.clone(), pane.window_name.clone()));
+            }
+        }
+        windows
+    }
+
+    /// Index of the window this row is about: the claude pane's window for
+    /// multi-claude rows, else the active window, else the first
+    pub fn focused_window_index(&self) -> Option<&str> {
+        self.target_window_index
+            .as_deref()
+            .or_else(|| {
+                self.panes
+                    .iter()
+                    .find(|p| p.active)
+                    .map(|p| p.window_index.as_str())
+            })
+            .or_else(|| self.panes.first().map(|p| p.window_index.as_str()))
+    }
+
+    /// Git context, if the working directory is a git repository.
+    ///
+    /// Detected the first time it is asked for, so rows that are never
+    /// rendered or sorted on never open their repository.
+    pub fn git_context(&self) -> Option<&GitContext> {
+        self.git
+            .get_or_init(|| GitContext::detect(&self.working_directory))
+            .as_ref()
+    }
+
+    /// Returns the root of the repository this session belongs to, used to
+    /// group worktree sessions of the same repo together
+    pub fn repo_key(&self) -> Option<&std::path::Path> {
+        self.git_context().map(|g| g.repo_root.as_path())
+    }
+
     /// Returns a shortened version of the working directory for display
     pub fn display_path(&self) -> String {
         let path = &self.working_directory;
@@ -148,3 +242,65 @@ impl Session {
         }
     }
 }
+
+/// Shells whose panes need no command re-run when a session is recreated
+const SHELLS: &[&str] = &["bash", "zsh", "fish", "sh", "dash", "ksh", "tcsh", "nu"];
+
+/// What is needed to rebuild a killed session: its windows, their layouts,
+/// and each pane's directory and command. Process state is not kept.
+#[derive(Debug, Clone)]
+pub struct SessionSnapshot {
+    /// Session name
+    pub name: String,
+    /// Windows in index order
+    pub windows: Vec<WindowSnapshot>,
+}
+
+/// A window of a `SessionSnapshot`
+#[derive(Debug, Clone)]
+pub struct WindowSnapshot {
+    /// Window name
+    pub name: String,
+    /// tmux layout string (`#{window_layout}`), reapplied after splitting
+    pub layout: String,
+    /// Panes in index order
+    pub panes: Vec<PaneSnapshot>,
+}
+
+/// A pane of a `WindowSnapshot`
+#[derive(Debug, Clone)]
+pub struct PaneSnapshot {
+    /// Current working directory
+    pub path: PathBuf,
+    /// Command that was running in the pane
+    pub command: String,
+}
+
+impl PaneSnapshot {
+    /// Command to send to the recreated pane: `claude_command` for Claude
+    /// Code panes. Other panes are left at a shell, since only the command's
+    /// name (not its arguments) was recorded.
+    pub fn restart_command<'a>(&self, claude_command: &'a str) -> Option<&'a str> {
+        self.command.contains("claude").then_some(claude_command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_command() {
+        let pane = |command: &str| PaneSnapshot {
+            path: PathBuf::from("/repo"),
+            command: command.to_string(),
+        };
+        assert_eq!(
+            pane("claude").restart_command("claude --continue"),
+            Some("claude --continue")
+        );
+        assert_eq!(pane("zsh").restart_command("claude"), None);
+        assert_eq!(pane("nvim").restart_command("claude"), None);
+        assert_eq!(pane("ssh").restart_command("claude"), None);
+    }
+}
@@ -1,114 +1,116 @@
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 
 use anyhow::{Context, Result};
 
+use crate::config::{Detection, DirectoryPane, PreviewCapture};
 use crate::detection::detect_status;
-use crate::git::GitContext;
-use crate::session::{ClaudeCodeStatus, Pane, Session};
+use crate::session::{
+    ClaudeCodeStatus, Client, Pane, PaneSnapshot, Session, SessionSnapshot, WindowSnapshot,
+};
+
+/// Session option marking a worktree session that should be offered a
+/// draft pull request after its first push
+pub const DRAFT_PR_OPTION: &str = "@claude-tmux-draft-pr";
+
+/// Separates fields in `-F` formats. Unlike a tab, the ASCII unit separator
+/// does not turn up in session, window or path names.
+const FIELD_SEPARATOR: char = '\x1f';
+
+/// Clock ticks per second of the times in `/proc/<pid>/stat` (`USER_HZ`),
+/// which is 100 on every architecture Linux exposes it on
+const CLOCK_TICKS: f64 = 100.0;
 
 /// Wrapper for tmux command execution
 pub struct Tmux;
 
+/// Optional `capture-pane` flags
+#[derive(Debug, Clone, Copy)]
+struct CaptureFlags {
+    /// Join wrapped lines (`-J`)
+    join_lines: bool,
+    /// Include escape sequences (`-e`)
+    escapes: bool,
+}
+
+impl CaptureFlags {
+    const DEFAULT: Self = Self {
+        join_lines: true,
+        escapes: true,
+    };
+}
+
 impl Tmux {
     /// List all tmux sessions with their metadata
-    pub fn list_sessions() -> Result<Vec<Session>> {
+    ///
+    /// `directory_pane` selects which pane's path becomes each session's
+    /// working directory, used for git detection. `detection` tunes how
+    /// Claude's status is read from its pane. Only the first `eager`
+    /// sessions (all if `None`) have their status read; the rest are left
+    /// `Unknown` for the caller to fill in when needed.
+    ///
+    /// Returns `None` when no tmux server is running, as told by the error
+    /// tmux gives, so telling that apart from zero sessions costs no call.
+    pub fn list_sessions(
+        directory_pane: DirectoryPane,
+        detection: &Detection,
+        eager: Option<usize>,
+    ) -> Result<Option<Vec<Session>>> {
         let output = Command::new("tmux")
             .args([
                 "list-sessions",
                 "-F",
-                "#{session_name}\t#{session_created}\t#{session_attached}\t#{session_windows}",
+                &format_fields(&[
+                    "#{session_name}",
+                    "#{session_created}",
+                    "#{session_activity}",
+                    "#{session_attached}",
+                    "#{session_windows}",
+                ]),
             ])
             .output()
             .context("Failed to execute tmux list-sessions")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            // No sessions is not an error for us
-            if stderr.contains("no server running") || stderr.contains("no sessions") {
-                return Ok(Vec::new());
+            // No server or no sessions is not an error for us
+            if stderr.contains("no server running") || stderr.contains("error connecting") {
+                return Ok(None);
+            }
+            if stderr.contains("no sessions") {
+                return Ok(Some(Vec::new()));
             }
             anyhow::bail!("tmux list-sessions failed: {}", stderr);
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut sessions = Vec::new();
 
-        for line in stdout.lines() {
-            let parts: Vec<&str> = line.split('\t').collect();
-            if parts.len() >= 4 {
-                let name = parts[0].to_string();
-                let created = parts[1].parse().unwrap_or(0);
-                let attached = parts[2] == "1";
-                let window_count = parts[3].parse().unwrap_or(1);
-
-                // Get panes for this session
-                let panes = Self::list_panes(&name).unwrap_or_default();
-
-                // Find every pane running claude
-                let claude_panes: Vec<&Pane> = panes
-                    .iter()
-                    .filter(|p| p.current_command == "claude" || p.current_command.contains("claude"))
-                    .collect();
-
-                // Emit one Session row per claude pane. Sessions with zero
-                // claude panes still produce a single row with no claude info.
-                let multi = claude_panes.len() > 1;
-
-                if claude_panes.is_empty() {
-                    let working_directory = panes
-                        .first()
-                        .map(|p| p.current_path.clone())
-                        .unwrap_or_default();
-                    let git_context = GitContext::detect(&working_directory);
-
-                    sessions.push(Session {
-                        name: name.clone(),
-                        created,
-                        attached,
-                        working_directory,
-                        window_count,
-                        panes: panes.clone(),
-                        claude_code_pane: None,
-                        claude_code_status: ClaudeCodeStatus::Unknown,
-                        window_label: None,
-                        target_window_index: None,
-                        git_context,
-                    });
-                } else {
-                    for claude_pane in claude_panes {
-                        let status = Self::capture_pane(&claude_pane.id, 15, true)
-                            .map(|content| detect_status(&content))
-                            .unwrap_or(ClaudeCodeStatus::Unknown);
-
-                        let working_directory = claude_pane.current_path.clone();
-                        let git_context = GitContext::detect(&working_directory);
-
-                        let (window_label, target_window_index) = if multi {
-                            (
-                                Some(claude_pane.window_name.clone()),
-                                Some(claude_pane.window_index.clone()),
-                            )
-                        } else {
-                            (None, None)
-                        };
-
-                        sessions.push(Session {
-                            name: name.clone(),
-                            created,
-                            attached,
-                            working_directory,
-                            window_count,
-                            panes: panes.clone(),
-                            claude_code_pane: Some(claude_pane.id.clone()),
-                            claude_code_status: status,
-                            window_label,
-                            target_window_index,
-                            git_context,
-                        });
-                    }
-                }
-            }
+        // Skip lines that do not parse rather than misreading them
+        let mut listed: Vec<Vec<&str>> = stdout
+            .lines()
+            .filter_map(|line| split_fields(line, 5))
+            .collect();
+        // In the order the rows end up in, so `eager` covers the top of the list
+        listed.sort_by(|a, b| {
+            is_attached(b[3])
+                .cmp(&is_attached(a[3]))
+                .then_with(|| a[0].cmp(b[0]))
+        });
+
+        let mut sessions = Vec::new();
+        for (i, parts) in listed.iter().enumerate() {
+            sessions.extend(Self::session_rows(
+                parts[0],
+                parts[1].parse().unwrap_or(0),
+                parts[2].parse().unwrap_or(0),
+                is_attached(parts[3]),
+                parts[4].parse().unwrap_or(1),
+                directory_pane,
+                eager.is_none_or(|n| i < n).then_some(detection),
+            ));
         }
 
         // Sort by attached status, then name, then window label so the rows
@@ -120,7 +122,142 @@ impl Tmux {
                 .then_with(|| a.window_label.cmp(&b.window_label))
         });
 
-        Ok(sessions)
+        Ok(Some(sessions))
+    }
+
+    /// The rows of one session, as `list_sessions` would list them; empty
+    /// if the session no longer exists
+    pub fn list_session(
+        name: &str,
+        directory_pane: DirectoryPane,
+        detection: &Detection,
+    ) -> Result<Vec<Session>> {
+        let output = Command::new("tmux")
+            .args([
+                "display-message",
+                "-p",
+                "-t",
+                &format!("={}:", name),
+                &format_fields(&[
+                    "#{session_created}",
+                    "#{session_activity}",
+                    "#{session_attached}",
+                    "#{session_windows}",
+                ]),
+            ])
+            .output()
+            .context("Failed to execute tmux display-message")?;
+
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let Some(parts) = split_fields(stdout.trim_end_matches('\n'), 4) else {
+            return Ok(Vec::new());
+        };
+        let mut rows = Self::session_rows(
+            name,
+            parts[0].parse().unwrap_or(0),
+            parts[1].parse().unwrap_or(0),
+            is_attached(parts[2]),
+            parts[3].parse().unwrap_or(1),
+            directory_pane,
+            Some(detection),
+        );
+        rows.sort_by(|a, b| a.window_label.cmp(&b.window_label));
+        Ok(rows)
+    }
+
+    /// Build a session's rows: one per claude pane, or a single row without
+    /// claude info if it has none. Claude's status is only read with a
+    /// `detection`; without one it is left `Unknown`.
+    fn session_rows(
+        name: &str,
+        created: i64,
+        activity: i64,
+        attached: bool,
+        window_count: usize,
+        directory_pane: DirectoryPane,
+        detection: Option<&Detection>,
+    ) -> Vec<Session> {
+        let name = name.to_string();
+        let mut rows = Vec::new();
+
+        // Get panes for this session
+        let panes = Self::list_panes(&name).unwrap_or_default();
+
+        // Find every pane running claude
+        let claude_panes: Vec<&Pane> = panes
+            .iter()
+            .filter(|p| p.current_command == "claude" || p.current_command.contains("claude"))
+            .collect();
+
+        // Emit one Session row per claude pane. Sessions with zero
+        // claude panes still produce a single row with no claude info.
+        let multi = claude_panes.len() > 1;
+
+        if claude_panes.is_empty() {
+            let working_directory = directory_pane_path(&panes, None, directory_pane);
+
+            rows.push(Session {
+                name: name.clone(),
+                created,
+                activity,
+                attached,
+                working_directory,
+                window_count,
+                panes: panes.clone(),
+                claude_code_pane: None,
+                claude_code_status: ClaudeCodeStatus::Unknown,
+                window_label: None,
+                target_window_index: None,
+                git: OnceCell::new(),
+                cpu_percent: None,
+                memory_kb: None,
+            });
+        } else {
+            for claude_pane in claude_panes {
+                let status = detection
+                    .and_then(|detection| {
+                        Self::capture_pane(&claude_pane.id, detection.status_lines, true)
+                            .map(|content| detect_status(&content, detection))
+                            .ok()
+                    })
+                    .unwrap_or(ClaudeCodeStatus::Unknown);
+
+                let working_directory =
+                    directory_pane_path(&panes, Some(claude_pane), directory_pane);
+
+                let (window_label, target_window_index) = if multi {
+                    (
+                        Some(claude_pane.window_name.clone()),
+                        Some(claude_pane.window_index.clone()),
+                    )
+                } else {
+                    (None, None)
+                };
+
+                rows.push(Session {
+                    name: name.clone(),
+                    created,
+                    activity,
+                    attached,
+                    working_directory,
+                    window_count,
+                    panes: panes.clone(),
+                    claude_code_pane: Some(claude_pane.id.clone()),
+                    claude_code_status: status,
+                    window_label,
+                    target_window_index,
+                    git: OnceCell::new(),
+                    cpu_percent: None,
+                    memory_kb: None,
+                });
+            }
+        }
+
+        rows
     }
 
     /// List all panes in a session, across every window
@@ -132,7 +269,15 @@ impl Tmux {
                 "-t",
                 session,
                 "-F",
-                "#{pane_id}\t#{pane_current_command}\t#{pane_current_path}\t#{window_index}\t#{window_name}",
+                &format_fields(&[
+                    "#{pane_id}",
+                    "#{pane_current_command}",
+                    "#{pane_current_path}",
+                    "#{window_index}",
+                    "#{window_name}",
+                    "#{&&:#{pane_active},#{window_active}}",
+                    "#{alternate_on}",
+                ]),
             ])
             .output()
             .context("Failed to execute tmux list-panes")?;
@@ -145,14 +290,15 @@ impl Tmux {
         let mut panes = Vec::new();
 
         for line in stdout.lines() {
-            let parts: Vec<&str> = line.split('\t').collect();
-            if parts.len() >= 5 {
+            if let Some(parts) = split_fields(line, 7) {
                 panes.push(Pane {
                     id: parts[0].to_string(),
                     current_command: parts[1].to_string(),
                     current_path: PathBuf::from(parts[2]),
                     window_index: parts[3].to_string(),
                     window_name: parts[4].to_string(),
+                    active: parts[5] == "1",
+                    alternate_on: parts[6] == "1",
                 });
             }
         }
@@ -160,6 +306,26 @@ impl Tmux {
         Ok(panes)
     }
 
+    /// CPU (percent of one core) and resident memory (KiB) of every process
+    /// running in a session's panes, children included. CPU is averaged
+    /// over each process's lifetime, as `ps` reports it. Read from `/proc`,
+    /// so `None` on platforms without it.
+    pub fn session_usage(session: &str) -> Option<(f64, u64)> {
+        let output = Command::new("tmux")
+            .args(["list-panes", "-s", "-t", session, "-F", "#{pane_pid}"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let roots: Vec<u32> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .collect();
+        process_tree_usage(&roots)
+    }
+
     /// Capture the last N lines of a pane's content
     ///
     /// If `strip_empty` is true, empty lines are filtered out before taking the last N.
@@ -168,15 +334,71 @@ impl Tmux {
     ///
     /// ANSI escape sequences are always included - the UI handles rendering them.
     pub fn capture_pane(pane_id: &str, lines: usize, strip_empty: bool) -> Result<String> {
+        Self::capture_pane_from(pane_id, lines, strip_empty, 0, false, CaptureFlags::DEFAULT)
+    }
+
+    /// Capture the last N lines of a pane's content for the preview, as
+    /// set by `capture`, including up to `capture.history` lines of
+    /// scrollback above the visible area
+    ///
+    /// The alternate screen (`alternate_on`) has no scrollback, so only its
+    /// visible content is captured. If that is blank, as while a full-screen
+    /// program is redrawing, the normal screen saved underneath it is
+    /// captured instead (unless `capture.alternate_screen` is off).
+    ///
+    /// Trailing empty lines are trimmed as for `capture_pane` with
+    /// `strip_empty: false`.
+    pub fn capture_pane_with_history(
+        pane_id: &str,
+        lines: usize,
+        capture: &PreviewCapture,
+        alternate_on: bool,
+    ) -> Result<String> {
+        let flags = CaptureFlags {
+            join_lines: capture.join_lines,
+            escapes: capture.escapes,
+        };
+        if !alternate_on {
+            return Self::capture_pane_from(pane_id, lines, false, capture.history, false, flags);
+        }
+
+        let visible = Self::capture_pane_from(pane_id, lines, false, 0, false, flags)?;
+        if !visible.trim().is_empty() || !capture.alternate_screen {
+            return Ok(visible);
+        }
+        Self::capture_pane_from(pane_id, lines, false, 0, true, flags)
+    }
+
+    /// Capture a pane's content. `saved_screen` captures the screen that is
+    /// not showing (`-a`): the normal screen while the alternate one is on.
+    fn capture_pane_from(
+        pane_id: &str,
+        lines: usize,
+        strip_empty: bool,
+        history: usize,
+        saved_screen: bool,
+        flags: CaptureFlags,
+    ) -> Result<String> {
+        let start = (-(history as i64)).to_string();
+        let mut args = vec![
+            "capture-pane",
+            "-t",
+            pane_id,
+            "-p", // Print to stdout
+            "-S", // Start this many lines into the scrollback
+            &start,
+        ];
+        if flags.join_lines {
+            args.push("-J"); // Join wrapped lines
+        }
+        if flags.escapes {
+            args.push("-e"); // Include escape sequences
+        }
+        if saved_screen {
+            args.extend(["-a", "-q"]);
+        }
         let output = Command::new("tmux")
-            .args([
-                "capture-pane",
-                "-t",
-                pane_id,
-                "-p", // Print to stdout
-                "-J", // Join wrapped lines
-                "-e", // Include escape sequences
-            ])
+            .args(&args)
             .output()
             .context("Failed to capture pane")?;
 
@@ -212,8 +434,28 @@ impl Tmux {
 
     /// Switch the current client to the specified session
     pub fn switch_to_session(session: &str) -> Result<()> {
+        Self::switch_client(session, false)
+    }
+
+    /// Switch the current client to the specified session in read-only mode
+    ///
+    /// Read-only is a property of the client, not the session: while it is
+    /// set only keys bound to `switch-client` or `detach-client` work, and it
+    /// stays set until toggled off with `switch-client -r` (or a normal switch
+    /// from claude-tmux).
+    pub fn switch_to_session_read_only(session: &str) -> Result<()> {
+        Self::switch_client(session, true)
+    }
+
+    fn switch_client(session: &str, read_only: bool) -> Result<()> {
+        let mut args = vec!["switch-client", "-t", session];
+        // -r toggles the flag, so only pass it when the state must change
+        if Self::client_is_read_only() != read_only {
+            args.push("-r");
+        }
+
         let status = Command::new("tmux")
-            .args(["switch-client", "-t", session])
+            .args(&args)
             .status()
             .context("Failed to switch session")?;
 
@@ -224,8 +466,49 @@ impl Tmux {
         Ok(())
     }
 
-    /// Create a new tmux session
-    pub fn new_session(name: &str, path: &std::path::Path, start_claude: bool) -> Result<()> {
+    /// Whether the current client is read-only
+    fn client_is_read_only() -> bool {
+        Command::new("tmux")
+            .args(["display-message", "-p", "#{client_readonly}"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+            .unwrap_or(false)
+    }
+
+    /// Attach the terminal to a session (for use outside tmux)
+    pub fn attach_session(session: &str, read_only: bool) -> Result<()> {
+        let mut args = vec!["attach-session", "-t", session];
+        if read_only {
+            args.push("-r");
+        }
+
+        let status = Command::new("tmux")
+            .args(&args)
+            .status()
+            .context("Failed to attach session")?;
+
+        if !status.success() {
+            anyhow::bail!("Failed to attach to session {}", session);
+        }
+
+        Ok(())
+    }
+
+    /// Whether a session with exactly this name exists
+    pub fn session_exists(session: &str) -> bool {
+        Command::new("tmux")
+            .args(["has-session", "-t", &format!("={}", session)])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Create a new tmux session, optionally sending `startup_command` to it
+    pub fn new_session(
+        name: &str,
+        path: &std::path::Path,
+        startup_command: Option<&str>,
+    ) -> Result<()> {
         let path_str = path.to_string_lossy();
 
         let status = Command::new("tmux")
@@ -237,16 +520,152 @@ impl Tmux {
             anyhow::bail!("Failed to create session {}", name);
         }
 
-        if start_claude {
-            // Send claude command to the new session
+        if let Some(command) = startup_command {
+            // Send the startup command to the new session
+            let _ = Command::new("tmux")
+                .args(["send-keys", "-t", name, command, "Enter"])
+                .status();
+        }
+
+        Ok(())
+    }
+
+    /// Record a session's windows, layouts and pane commands so it can be
+    /// rebuilt with `restore_session` after it is killed
+    pub fn snapshot_session(session: &str) -> Result<SessionSnapshot> {
+        let output = Command::new("tmux")
+            .args([
+                "list-windows",
+                "-t",
+                session,
+                "-F",
+                &format_fields(&["#{window_index}", "#{window_name}", "#{window_layout}"]),
+            ])
+            .output()
+            .context("Failed to execute tmux list-windows")?;
+
+        if !output.status.success() {
+            anyhow::bail!("Failed to list windows of {}", session);
+        }
+
+        let panes = Self::list_panes(session)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let windows = stdout
+            .lines()
+            .filter_map(|line| {
+                let parts = split_fields(line, 3)?;
+                Some(WindowSnapshot {
+                    name: parts[1].to_string(),
+                    layout: parts[2].to_string(),
+                    panes: panes
+                        .iter()
+                        .filter(|p| p.window_index == parts[0])
+                        .map(|p| PaneSnapshot {
+                            path: p.current_path.clone(),
+                            command: p.current_command.clone(),
+                        })
+                        .collect(),
+                })
+            })
+            .filter(|window| !window.panes.is_empty())
+            .collect();
+
+        Ok(SessionSnapshot {
+            name: session.to_string(),
+            windows,
+        })
+    }
+
+    /// Rebuild a session from a snapshot: same windows, pane directories and
+    /// layouts, with `claude_command` started in the Claude Code panes. A
+    /// snapshot without windows is an error, as there is nothing to rebuild.
+    pub fn restore_session(snapshot: &SessionSnapshot, claude_command: &str) -> Result<()> {
+        if snapshot.windows.is_empty() {
+            anyhow::bail!("No windows were recorded for session '{}'", snapshot.name);
+        }
+        for (i, window) in snapshot.windows.iter().enumerate() {
+            let first_path = window.panes[0].path.to_string_lossy();
+            let mut args = if i == 0 {
+                vec!["new-session", "-d", "-s", &snapshot.name]
+            } else {
+                vec!["new-window", "-d", "-t", &snapshot.name]
+            };
+            args.extend([
+                "-n",
+                &window.name,
+                "-c",
+                &first_path,
+                "-P",
+                "-F",
+                "#{pane_id}",
+            ]);
+
+            // Pane IDs also target their window for split-window and select-layout
+            let first_pane = Self::run_for_id(&args)
+                .with_context(|| format!("Failed to recreate window {}", window.name))?;
+
+            // Split from the newest pane so pane order matches the snapshot
+            let mut pane_ids = vec![first_pane.clone()];
+            for pane in &window.panes[1..] {
+                let path = pane.path.to_string_lossy();
+                let last = pane_ids[pane_ids.len() - 1].clone();
+                pane_ids.push(Self::run_for_id(&[
+                    "split-window",
+                    "-d",
+                    "-t",
+                    &last,
+                    "-c",
+                    &path,
+                    "-P",
+                    "-F",
+                    "#{pane_id}",
+                ])?);
+            }
+
+            // Best effort: the layout only applies if the pane count matches
             let _ = Command::new("tmux")
-                .args(["send-keys", "-t", name, "claude", "Enter"])
+                .args(["select-layout", "-t", &first_pane, &window.layout])
                 .status();
+
+            for (pane_id, pane) in pane_ids.iter().zip(&window.panes) {
+                if let Some(command) = pane.restart_command(claude_command) {
+                    let _ = Self::send_keys(pane_id, command);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Type `keys` into a pane and press Enter
+    pub fn send_keys(pane_id: &str, keys: &str) -> Result<()> {
+        let status = Command::new("tmux")
+            .args(["send-keys", "-t", pane_id, keys, "Enter"])
+            .status()
+            .context("Failed to send keys")?;
+
+        if !status.success() {
+            anyhow::bail!("Failed to send keys to pane {}", pane_id);
         }
 
         Ok(())
     }
 
+    /// Run a tmux command that prints an ID with `-P -F` and return it
+    fn run_for_id(args: &[&str]) -> Result<String> {
+        let output = Command::new("tmux")
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to execute tmux {}", args[0]))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("tmux {} failed: {}", args[0], stderr.trim());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
     /// Kill a tmux session
     pub fn kill_session(session: &str) -> Result<()> {
         let status = Command::new("tmux")
@@ -275,6 +694,229 @@ impl Tmux {
         Ok(())
     }
 
+    /// Rename a window, targeted as `session:index`
+    pub fn rename_window(target: &str, new_name: &str) -> Result<()> {
+        let status = Command::new("tmux")
+            .args(["rename-window", "-t", target, new_name])
+            .status()
+            .context("Failed to rename window")?;
+
+        if !status.success() {
+            anyhow::bail!("Failed to rename window {} to {}", target, new_name);
+        }
+
+        Ok(())
+    }
+
+    /// Set a user option (`@name`) on a session. Options follow the session
+    /// across renames and last as long as it does.
+    pub fn set_session_option(session: &str, name: &str, value: &str) -> Result<()> {
+        let status = Command::new("tmux")
+            .args(["set-option", "-t", session, name, value])
+            .status()
+            .context("Failed to set session option")?;
+
+        if !status.success() {
+            anyhow::bail!("Failed to set {} on session {}", name, session);
+        }
+
+        Ok(())
+    }
+
+    /// Value of a session's user option, if set
+    pub fn session_option(session: &str, name: &str) -> Option<String> {
+        let output = Command::new("tmux")
+            .args(["show-options", "-t", session, "-v", name])
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!value.is_empty()).then_some(value)
+    }
+
+    /// Remove a user option from a session
+    pub fn unset_session_option(session: &str, name: &str) -> Result<()> {
+        let status = Command::new("tmux")
+            .args(["set-option", "-u", "-t", session, name])
+            .status()
+            .context("Failed to unset session option")?;
+
+        if !status.success() {
+            anyhow::bail!("Failed to unset {} on session {}", name, session);
+        }
+
+        Ok(())
+    }
+
+    /// Open a window in the current session showing `left` and `right` side
+    /// by side. Each pane is a nested read-only client that does not resize
+    /// the session it shows; closing the window detaches them.
+    pub fn open_side_by_side(left: &str, right: &str) -> Result<()> {
+        let socket = Self::socket_path()?;
+        let view = |session: &str| {
+            let target = format!("={}", session);
+            [
+                "env",
+                "-u",
+                "TMUX",
+                "tmux",
+                "-S",
+                &socket,
+                "attach-session",
+                "-r",
+            ]
+            .into_iter()
+            .chain(["-f", "ignore-size", "-t", &target])
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+        };
+
+        let name = format!("{} | {}", left, right);
+        let output = Command::new("tmux")
+            .args(["new-window", "-P", "-F", "#{window_id}", "-n", &name])
+            .args(view(left))
+            .output()
+            .context("Failed to open a window")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to open a window: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let window = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        let split = Command::new("tmux")
+            .args(["split-window", "-h", "-t", &window])
+            .args(view(right))
+            .status();
+        if !split.as_ref().is_ok_and(|status| status.success()) {
+            // Don't leave a half-built window behind
+            let _ = Command::new("tmux")
+                .args(["kill-window", "-t", &window])
+                .status();
+            split.context("Failed to split the window")?;
+            anyhow::bail!("Failed to show session {} beside {}", right, left);
+        }
+
+        Ok(())
+    }
+
+    /// Socket of the tmux server, for nested clients of the same server
+    fn socket_path() -> Result<String> {
+        let output = Command::new("tmux")
+            .args(["display-message", "-p", "#{socket_path}"])
+            .output()
+            .context("Failed to get the tmux socket")?;
+
+        let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || path.is_empty() {
+            anyhow::bail!("Failed to get the tmux socket");
+        }
+        Ok(path)
+    }
+
+    /// List the clients attached to the server, in tmux's order
+    pub fn list_clients() -> Result<Vec<Client>> {
+        let output = Command::new("tmux")
+            .args([
+                "list-clients",
+                "-F",
+                &format_fields(&[
+                    "#{client_tty}",
+                    "#{client_session}",
+                    "#{client_width}x#{client_height}",
+                    "#{client_termname}",
+                    "#{client_readonly}",
+                ]),
+            ])
+            .output()
+            .context("Failed to execute tmux list-clients")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("tmux list-clients failed: {}", stderr.trim());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .filter_map(|line| split_fields(line, 5))
+            .map(|parts| Client {
+                tty: parts[0].to_string(),
+                session: parts[1].to_string(),
+                size: parts[2].to_string(),
+                termname: parts[3].to_string(),
+                read_only: parts[4] == "1",
+            })
+            .collect())
+    }
+
+    /// Terminal of the client claude-tmux runs in, if any
+    pub fn current_client() -> Option<String> {
+        let output = Command::new("tmux")
+            .args([
+                "display-message",
+                "-p",
+                &format_fields(&["#{client_tty}", "#{client_session}", "#{session_name}"]),
+            ])
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        // Without a client showing our session, tmux falls back to another one
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let parts = split_fields(stdout.trim_end_matches('\n'), 3)?;
+        (!parts[0].is_empty() && parts[1] == parts[2]).then(|| parts[0].to_string())
+    }
+
+    /// Switch another client (by its tty) to `target`
+    pub fn switch_client_to(tty: &str, target: &str) -> Result<()> {
+        let status = Command::new("tmux")
+            .args(["switch-client", "-c", tty, "-t", target])
+            .status()
+            .context("Failed to switch client")?;
+
+        if !status.success() {
+            anyhow::bail!("Failed to switch client {} to {}", tty, target);
+        }
+
+        Ok(())
+    }
+
+    /// Detach a client (by its tty) from the server
+    pub fn detach_client(tty: &str) -> Result<()> {
+        let status = Command::new("tmux")
+            .args(["detach-client", "-t", tty])
+            .status()
+            .context("Failed to detach client")?;
+
+        if !status.success() {
+            anyhow::bail!("Failed to detach client {}", tty);
+        }
+
+        Ok(())
+    }
+
+    /// Title of the current pane, as set by the program running in it
+    pub fn pane_title() -> Option<String> {
+        let output = Command::new("tmux")
+            .args(["display-message", "-p", "#{pane_title}"])
+            .output()
+            .ok()?;
+
+        output.status.success().then(|| {
+            String::from_utf8_lossy(&output.stdout)
+                .trim_end_matches('\n')
+                .to_string()
+        })
+    }
+
     /// Get the name of the currently attached session
     pub fn current_session() -> Result<Option<String>> {
         let output = Command::new("tmux")
@@ -294,3 +936,238 @@ impl Tmux {
         }
     }
 }
+
+/// Path of the pane selected by `preference`, used as a session's working
+/// directory. `claude_pane` is the Claude Code pane the session row
+/// represents, if any.
+fn directory_pane_path(
+    panes: &[Pane],
+    claude_pane: Option<&Pane>,
+    preference: DirectoryPane,
+) -> PathBuf {
+    let pane = match preference {
+        DirectoryPane::Claude => claude_pane.or_else(|| panes.first()),
+        DirectoryPane::Active => panes.iter().find(|p| p.active).or_else(|| panes.first()),
+        DirectoryPane::First => panes.first(),
+    };
+    pane.map(|p| p.current_path.clone()).unwrap_or_default()
+}
+
+/// A process as read from `/proc/<pid>/stat`
+#[derive(Debug, PartialEq)]
+struct ProcStat {
+    pid: u32,
+    ppid: u32,
+    /// User plus system time, in clock ticks
+    cpu_ticks: u64,
+    /// Start time after boot, in clock ticks
+    start_ticks: u64,
+}
+
+/// Sum the usage of `roots` and all their descendants, see
+/// `Tmux::session_usage`
+fn process_tree_usage(roots: &[u32]) -> Option<(f64, u64)> {
+    let uptime: f64 = fs::read_to_string("/proc/uptime")
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+
+    let processes: Vec<ProcStat> = fs::read_dir("/proc")
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|n| n.parse::<u32>().is_ok())
+        })
+        .filter_map(|entry| fs::read_to_string(entry.path().join("stat")).ok())
+        .filter_map(|stat| parse_proc_stat(&stat))
+        .collect();
+    let parents: Vec<(u32, u32)> = processes.iter().map(|p| (p.pid, p.ppid)).collect();
+    let tree = process_tree(&parents, roots);
+
+    let mut cpu_percent = 0.0;
+    let mut memory_kb = 0;
+    for process in processes.iter().filter(|p| tree.contains(&p.pid)) {
+        let running = uptime - process.start_ticks as f64 / CLOCK_TICKS;
+        if running > 0.0 {
+            cpu_percent += process.cpu_ticks as f64 / CLOCK_TICKS / running * 100.0;
+        }
+        memory_kb += fs::read_to_string(format!("/proc/{}/status", process.pid))
+            .ok()
+            .and_then(|status| parse_vm_rss(&status))
+            .unwrap_or(0);
+    }
+    Some((cpu_percent, memory_kb))
+}
+
+/// Parse `/proc/<pid>/stat`. The command name is parenthesized and may
+/// itself contain spaces or parentheses, so fields are counted from the
+/// last `)`.
+fn parse_proc_stat(stat: &str) -> Option<ProcStat> {
+    let (head, rest) = stat.rsplit_once(')')?;
+    let pid = head.split_once('(')?.0.trim().parse().ok()?;
+    // `rest` starts at field 3 (state); see proc(5) for the numbering
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |n: usize| fields.get(n - 3)?.parse::<u64>().ok();
+
+    Some(ProcStat {
+        pid,
+        ppid: field(4)? as u32,
+        cpu_ticks: field(14)? + field(15)?,
+        start_ticks: field(22)?,
+    })
+}
+
+/// Resident memory in KiB from `/proc/<pid>/status`; kernel threads have none
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// `roots` and every process descending from them, given `(pid, ppid)`
+/// pairs
+fn process_tree(parents: &[(u32, u32)], roots: &[u32]) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for &(pid, ppid) in parents {
+        children.entry(ppid).or_default().push(pid);
+    }
+
+    let mut tree = roots.to_vec();
+    let mut next = 0;
+    while next < tree.len() {
+        if let Some(kids) = children.get(&tree[next]) {
+            tree.extend(kids.iter().filter(|kid| !roots.contains(kid)));
+        }
+        next += 1;
+    }
+    tree
+}
+
+/// Join `-F` format fields with `FIELD_SEPARATOR`
+fn format_fields(fields: &[&str]) -> String {
+    fields.join(&FIELD_SEPARATOR.to_string())
+}
+
+/// Split a line of `format_fields` output, or None if it does not have
+/// exactly `count` fields
+fn split_fields(line: &str, count: usize) -> Option<Vec<&str>> {
+    let parts: Vec<&str> = line.split(FIELD_SEPARATOR).collect();
+    (parts.len() == count).then_some(parts)
+}
+
+/// Whether a `#{session_attached}` client count means any client is attached
+fn is_attached(count: &str) -> bool {
+    count.parse::<usize>().is_ok_and(|count| count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_fields() {
+        let line = "my\tsession\x1f1700000000\x1f0\x1f2";
+        assert_eq!(
+            split_fields(line, 4),
+            Some(vec!["my\tsession", "1700000000", "0", "2"])
+        );
+        assert_eq!(split_fields(line, 3), None);
+        assert_eq!(split_fields("", 4), None);
+    }
+
+    #[test]
+    fn test_is_attached() {
+        assert!(!is_attached("0"));
+        assert!(is_attached("1"));
+        assert!(is_attached("2"));
+        assert!(is_attached("10"));
+        assert!(!is_attached(""));
+    }
+
+    #[test]
+    fn test_parse_proc_stat() {
+        let stat = "4242 (tmux: server (x)) S 1 4242 4242 0 -1 4194560 1500 0 0 0 \
+                    250 130 0 0 20 0 1 0 98765 12345678 1024 18446744073709551615";
+        assert_eq!(
+            parse_proc_stat(stat),
+            Some(ProcStat {
+                pid: 4242,
+                ppid: 1,
+                cpu_ticks: 380,
+                start_ticks: 98765,
+            })
+        );
+        assert_eq!(parse_proc_stat("12 (short) S 1"), None);
+        assert_eq!(parse_proc_stat(""), None);
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tclaude\nVmPeak:\t  900000 kB\nVmRSS:\t  204800 kB\nThreads:\t12\n";
+        assert_eq!(parse_vm_rss(status), Some(204800));
+        assert_eq!(parse_vm_rss("Name:\tkthreadd\n"), None);
+    }
+
+    #[test]
+    fn test_process_tree() {
+        let parents = [
+            (1, 0),
+            (10, 1),
+            (11, 10),
+            (12, 11),
+            (20, 1),
+            (21, 20),
+            (13, 10),
+        ];
+        let mut tree = process_tree(&parents, &[10]);
+        tree.sort();
+        assert_eq!(tree, vec![10, 11, 12, 13]);
+        assert_eq!(process_tree(&parents, &[99]), vec![99]);
+    }
+
+    fn pane(id: &str, path: &str, active: bool) -> Pane {
+        Pane {
+            id: id.to_string(),
+            current_command: "zsh".to_string(),
+            current_path: PathBuf::from(path),
+            window_index: "0".to_string(),
+            window_name: "main".to_string(),
+            active,
+            alternate_on: false,
+        }
+    }
+
+    #[test]
+    fn test_directory_pane_path() {
+        let panes = vec![
+            pane("%0", "/repo", false),
+            pane("%1", "/repo/sub", false),
+            pane("%2", "/other", true),
+        ];
+        let claude = &panes[1];
+
+        let path = |claude_pane, preference| directory_pane_path(&panes, claude_pane, preference);
+        assert_eq!(
+            path(Some(claude), DirectoryPane::Claude),
+            PathBuf::from("/repo/sub")
+        );
+        assert_eq!(path(None, DirectoryPane::Claude), PathBuf::from("/repo"));
+        assert_eq!(
+            path(Some(claude), DirectoryPane::Active),
+            PathBuf::from("/other")
+        );
+        assert_eq!(
+            path(Some(claude), DirectoryPane::First),
+            PathBuf::from("/repo")
+        );
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! Provides rendering for all modal dialogs:
 //! - Confirmation dialogs (kill, merge PR, etc.)
-//! - Input dialogs (new session, rename, commit, new worktree, create PR)
+//! - Input dialogs (new session, rename, commit, squash, new worktree, create PR, create tag)
+//! - List viewers (tags, stage files, history)
+
+use std::path::Path;
 
 use ratatui::{
     layout::Alignment,
@@ -11,16 +14,26 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
-use crate::app::{App, CreatePullRequestField, NewSessionField, NewWorktreeField, SessionAction};
+use crate::app::{
+    truncate_display, App, CommentLine, CommitField, CreatePullRequestField, CreateTagField,
+    NewSessionField, NewWorktreeField, PaneDebug, SessionAction, TextInput,
+};
+use crate::color_tags::TagColor;
+use crate::config::Macro;
+use crate::git::{ChangedFile, MergeStrategy};
+use crate::history::{self, HistoryEntry};
+use crate::session::Client;
 
 use super::help::centered_rect;
+use super::input_spans;
 
 pub fn render_confirm_action(frame: &mut Frame, app: &App) {
     let session = app.selected_session();
     let session_name = session.map(|s| s.name.as_str()).unwrap_or("?");
     let is_worktree = session
-        .and_then(|s| s.git_context.as_ref())
+        .and_then(|s| s.git_context())
         .map(|g| g.is_worktree)
         .unwrap_or(false);
     let is_current_session = app
@@ -36,6 +49,7 @@ pub fn render_confirm_action(frame: &mut Frame, app: &App) {
 
             let dialog_height = if is_current_session { 11 } else { 9 };
             let area = centered_rect(55, dialog_height, frame.area());
+            let inner = area.width.saturating_sub(2) as usize;
 
             let block = Block::default()
                 .title(" Confirm ")
@@ -43,10 +57,16 @@ pub fn render_confirm_action(frame: &mut Frame, app: &App) {
                 .border_style(Style::default().fg(Color::Red));
 
             let mut lines = vec![
-                Line::from(format!("Kill session '{}'", session_name)),
+                Line::from(format!(
+                    "Kill session '{}'",
+                    truncate_display(session_name, inner.saturating_sub(15))
+                )),
                 Line::from("AND delete worktree at:"),
                 Line::styled(
-                    format!("  {}", worktree_path),
+                    format!(
+                        "  {}",
+                        truncate_display(&worktree_path, inner.saturating_sub(2))
+                    ),
                     Style::default().fg(Color::Yellow),
                 ),
                 Line::raw(""),
@@ -78,6 +98,33 @@ pub fn render_confirm_action(frame: &mut Frame, app: &App) {
             frame.render_widget(Clear, area);
             frame.render_widget(paragraph, area);
         }
+        Some(SessionAction::SwitchTo | SessionAction::SwitchReadOnly) => {
+            let area = centered_rect(55, 6, frame.area());
+            let inner = area.width.saturating_sub(2) as usize;
+
+            let block = Block::default()
+                .title(" Session Working ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow));
+
+            let lines = vec![
+                Line::from(format!(
+                    "Claude in '{}' is actively working.",
+                    truncate_display(session_name, inner.saturating_sub(31))
+                )),
+                Line::from("Switching in may interrupt it."),
+                Line::raw(""),
+                Line::from("Switch anyway? [Y]es  [n]o"),
+            ];
+
+            let paragraph = Paragraph::new(Text::from(lines))
+                .block(block)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(paragraph, area);
+        }
         Some(SessionAction::ClosePullRequest) => {
             let area = centered_rect(50, 5, frame.area());
 
@@ -96,14 +143,20 @@ pub fn render_confirm_action(frame: &mut Frame, app: &App) {
             frame.render_widget(paragraph, area);
         }
         Some(SessionAction::MergePullRequest) => {
-            let area = centered_rect(50, 5, frame.area());
+            let area = centered_rect(50, 7, frame.area());
 
             let block = Block::default()
                 .title(" Merge Pull Request ")
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Green));
 
-            let text = "Merge this pull request?\n\n[Y]es  [n]o";
+            let text = Text::from(vec![
+                Line::from("Merge this pull request?"),
+                Line::raw(""),
+                merge_strategy_line(app.merge_strategy),
+                Line::raw(""),
+                Line::from("[Y]es  [n]o"),
+            ]);
             let paragraph = Paragraph::new(text)
                 .block(block)
                 .alignment(Alignment::Center)
@@ -113,8 +166,9 @@ pub fn render_confirm_action(frame: &mut Frame, app: &App) {
             frame.render_widget(paragraph, area);
         }
         Some(SessionAction::MergePullRequestAndClose) => {
-            let dialog_height = if is_current_session { 12 } else { 10 };
+            let dialog_height = if is_current_session { 14 } else { 12 };
             let area = centered_rect(58, dialog_height, frame.area());
+            let inner = area.width.saturating_sub(2) as usize;
 
             let block = Block::default()
                 .title(" Merge PR + Close ")
@@ -137,9 +191,14 @@ pub fn render_confirm_action(frame: &mut Frame, app: &App) {
             }
 
             lines.push(Line::styled(
-                format!("  • Kill session '{}'", session_name),
+                format!(
+                    "  • Kill session '{}'",
+                    truncate_display(session_name, inner.saturating_sub(19))
+                ),
                 Style::default().fg(Color::Red),
             ));
+            lines.push(Line::raw(""));
+            lines.push(merge_strategy_line(app.merge_strategy));
 
             if is_current_session {
                 lines.push(Line::raw(""));
@@ -169,16 +228,18 @@ pub fn render_confirm_action(frame: &mut Frame, app: &App) {
 
             let dialog_height = if show_exit_warning { 7 } else { 5 };
             let area = centered_rect(55, dialog_height, frame.area());
+            let inner = area.width.saturating_sub(2) as usize;
 
             let block = Block::default()
                 .title(" Confirm ")
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Red));
 
+            let name_columns = inner.saturating_sub(action.label().width() + 4);
             let mut lines = vec![Line::from(format!(
                 "{} '{}'?",
                 action.label(),
-                session_name
+                truncate_display(session_name, name_columns)
             ))];
 
             if show_exit_warning {
@@ -206,10 +267,35 @@ pub fn render_confirm_action(frame: &mut Frame, app: &App) {
     }
 }
 
+/// Ask before Esc discards what was typed into a dialog
+pub fn render_discard_prompt(frame: &mut Frame) {
+    let area = centered_rect(48, 5, frame.area());
+
+    let block = Block::default()
+        .title(" Discard changes? ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+
+    let lines = vec![
+        Line::from("What you typed will be lost."),
+        Line::raw(""),
+        Line::styled(
+            "y/⏎ discard, any other key to keep editing",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
 pub fn render_new_session_dialog(
     frame: &mut Frame,
-    name: &str,
-    path: &str,
+    name: &TextInput,
+    path: &TextInput,
+    url: &TextInput,
     field: NewSessionField,
     path_suggestions: &[String],
     path_selected: Option<usize>,
@@ -225,7 +311,7 @@ pub fn render_new_session_dialog(
     } else {
         0
     };
-    let dialog_height = 8 + suggestions_to_show as u16 + suggestion_extra as u16;
+    let dialog_height = 10 + suggestions_to_show as u16 + suggestion_extra as u16;
 
     let area = centered_rect(60, dialog_height, frame.area());
 
@@ -250,48 +336,42 @@ pub fn render_new_session_dialog(
         Style::default()
     };
 
+    let url_style = if field == NewSessionField::Url {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+
     let mut lines = Vec::new();
 
     // Name field
-    lines.push(Line::from(vec![
-        Span::styled("Name: ", name_style),
-        Span::raw(name),
-        if field == NewSessionField::Name {
-            Span::raw("_")
-        } else {
-            Span::raw("")
-        },
-    ]));
+    let mut name_spans = vec![Span::styled("Name: ", name_style)];
+    name_spans.extend(input_spans(
+        name,
+        Style::default(),
+        field == NewSessionField::Name,
+        None,
+    ));
+    lines.push(Line::from(name_spans));
 
     lines.push(Line::raw(""));
 
     // Path field with ghost text
     let ghost_text = if field == NewSessionField::Path {
-        crate::completion::complete_path(path).ghost_text
+        crate::completion::complete_path(path.as_str()).ghost_text
     } else {
         None
     };
 
-    let mut path_spans = vec![
-        Span::styled("Path: ", path_style),
-        Span::styled(path, Style::default().fg(Color::Yellow)),
-    ];
-
-    // Add ghost text (completion suffix)
-    if let Some(ref ghost) = ghost_text {
-        path_spans.push(Span::styled(
-            ghost,
-            Style::default()
-                .fg(Color::DarkGray)
-                .add_modifier(Modifier::DIM),
-        ));
-    }
-
-    // Add cursor
-    if field == NewSessionField::Path {
-        path_spans.push(Span::raw("_"));
-    }
-
+    let mut path_spans = vec![Span::styled("Path: ", path_style)];
+    path_spans.extend(input_spans(
+        path,
+        Style::default().fg(Color::Yellow),
+        field == NewSessionField::Path,
+        ghost_text.as_deref(),
+    ));
     lines.push(Line::from(path_spans));
 
     // Show path suggestions when path field is active
@@ -327,6 +407,21 @@ pub fn render_new_session_dialog(
         ));
     }
 
+    lines.push(Line::raw(""));
+
+    // Optional URL to clone into the path first
+    let url_ghost = url
+        .is_empty()
+        .then_some("optional git URL to clone into the path");
+    let mut url_spans = vec![Span::styled("Clone: ", url_style)];
+    url_spans.extend(input_spans(
+        url,
+        Style::default().fg(Color::Cyan),
+        field == NewSessionField::Url,
+        url_ghost,
+    ));
+    lines.push(Line::from(url_spans));
+
     lines.push(Line::raw(""));
     lines.push(Line::styled(
         "Tab switch  ↑↓ select  → accept  Enter create  Esc cancel",
@@ -342,23 +437,113 @@ pub fn render_new_session_dialog(
     frame.render_widget(paragraph, area);
 }
 
-pub fn render_commit_dialog(frame: &mut Frame, message: &str) {
-    let area = centered_rect(60, 6, frame.area());
+pub fn render_commit_dialog(
+    frame: &mut Frame,
+    message: &TextInput,
+    author: &TextInput,
+    field: CommitField,
+) {
+    let area = centered_rect(60, 8, frame.area());
 
     let block = Block::default()
         .title(" Commit ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
+    let field_style = |active: bool| {
+        if active {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        }
+    };
+    let author_is_valid = author.is_empty() || crate::git::Identity::parse(author.as_str()).is_ok();
+
+    let mut message_spans = vec![Span::styled(
+        "Message: ",
+        field_style(field == CommitField::Message),
+    )];
+    message_spans.extend(input_spans(
+        message,
+        Style::default().fg(Color::Yellow),
+        field == CommitField::Message,
+        None,
+    ));
+
+    let mut author_spans = vec![Span::styled(
+        "Author:  ",
+        field_style(field == CommitField::Author),
+    )];
+    author_spans.extend(input_spans(
+        author,
+        Style::default().fg(if author_is_valid {
+            Color::Yellow
+        } else {
+            Color::Red
+        }),
+        field == CommitField::Author,
+        None,
+    ));
+    if author.is_empty() {
+        author_spans.push(Span::styled(
+            "(git config)",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    let text = Text::from(vec![
+        Line::from(message_spans),
+        Line::raw(""),
+        Line::from(author_spans),
+        Line::raw(""),
+        Line::styled(
+            "[Tab] Next  [^G] Editor  [Enter] Commit  [Esc] Cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .wrap(Wrap { trim: true });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_squash_dialog(
+    frame: &mut Frame,
+    base_branch: &str,
+    count: usize,
+    message: &TextInput,
+) {
+    let area = centered_rect(60, 8, frame.area());
+
+    let block = Block::default()
+        .title(" Squash Commits ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let mut message_spans = vec![Span::raw("Message: ")];
+    message_spans.extend(input_spans(
+        message,
+        Style::default().fg(Color::Yellow),
+        true,
+        None,
+    ));
+
     let text = Text::from(vec![
         Line::from(vec![
-            Span::raw("Message: "),
-            Span::styled(message, Style::default().fg(Color::Yellow)),
-            Span::raw("_"),
+            Span::raw(format!("Squash {} commits since ", count)),
+            Span::styled(base_branch, Style::default().fg(Color::Cyan)),
+            Span::raw(" into one"),
         ]),
         Line::raw(""),
+        Line::from(message_spans),
+        Line::raw(""),
         Line::styled(
-            "Press Enter to commit",
+            "Press Enter to squash",
             Style::default().fg(Color::DarkGray),
         ),
     ]);
@@ -373,12 +558,14 @@ pub fn render_commit_dialog(frame: &mut Frame, message: &str) {
 
 pub fn render_create_pr_dialog(
     frame: &mut Frame,
-    title: &str,
-    body: &str,
-    base_branch: &str,
+    title: &TextInput,
+    body: &TextInput,
+    base_branch: &TextInput,
+    issue: &TextInput,
+    draft: bool,
     field: CreatePullRequestField,
 ) {
-    let area = centered_rect(65, 12, frame.area());
+    let area = centered_rect(65, 16, frame.area());
 
     let block = Block::default()
         .title(" Create Pull Request ")
@@ -409,36 +596,85 @@ pub fn render_create_pr_dialog(
         Style::default()
     };
 
-    let cursor = |active: bool| if active { "_" } else { "" };
+    let mut title_spans = vec![Span::styled("Title: ", title_style)];
+    title_spans.extend(input_spans(
+        title,
+        Style::default().fg(Color::Yellow),
+        field == CreatePullRequestField::Title,
+        None,
+    ));
+
+    let mut body_spans = vec![Span::styled("Body:  ", body_style)];
+    body_spans.extend(input_spans(
+        body,
+        Style::default().fg(Color::Yellow),
+        field == CreatePullRequestField::Body,
+        None,
+    ));
+    if body.is_empty() {
+        body_spans.push(Span::styled(
+            "(optional, ^G to write in $EDITOR)",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    let mut base_spans = vec![Span::styled("Base:  ", base_style)];
+    base_spans.extend(input_spans(
+        base_branch,
+        Style::default().fg(Color::Cyan),
+        field == CreatePullRequestField::BaseBranch,
+        None,
+    ));
+
+    let issue_style = if field == CreatePullRequestField::Issue {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+
+    let mut issue_spans = vec![Span::styled("Issue: ", issue_style)];
+    issue_spans.extend(input_spans(
+        issue,
+        Style::default().fg(Color::Cyan),
+        field == CreatePullRequestField::Issue,
+        None,
+    ));
+    if issue.is_empty() {
+        issue_spans.push(Span::styled(
+            "(optional, closed when merged)",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    let draft_style = if field == CreatePullRequestField::Draft {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
 
     let text = Text::from(vec![
-        Line::from(vec![
-            Span::styled("Title: ", title_style),
-            Span::styled(title, Style::default().fg(Color::Yellow)),
-            Span::raw(cursor(field == CreatePullRequestField::Title)),
-        ]),
+        Line::from(title_spans),
+        Line::raw(""),
+        Line::from(body_spans),
+        Line::raw(""),
+        Line::from(base_spans),
+        Line::raw(""),
+        Line::from(issue_spans),
         Line::raw(""),
         Line::from(vec![
-            Span::styled("Body:  ", body_style),
+            Span::styled("Draft: ", draft_style),
             Span::styled(
-                if body.is_empty() { "(optional)" } else { body },
-                if body.is_empty() {
-                    Style::default().fg(Color::DarkGray)
-                } else {
-                    Style::default().fg(Color::Yellow)
-                },
+                if draft { "[x] draft" } else { "[ ] draft" },
+                Style::default().fg(Color::Cyan),
             ),
-            Span::raw(cursor(field == CreatePullRequestField::Body)),
-        ]),
-        Line::raw(""),
-        Line::from(vec![
-            Span::styled("Base:  ", base_style),
-            Span::styled(base_branch, Style::default().fg(Color::Cyan)),
-            Span::raw(cursor(field == CreatePullRequestField::BaseBranch)),
         ]),
         Line::raw(""),
         Line::styled(
-            "[Tab] Next field  [Enter] Create PR  [Esc] Cancel",
+            "[Tab] Next field  [Space] Toggle  [Enter] Create  [Esc] Cancel",
             Style::default().fg(Color::DarkGray),
         ),
     ]);
@@ -455,10 +691,11 @@ pub fn render_create_pr_dialog(
 pub fn render_new_worktree_dialog(
     frame: &mut Frame,
     app: &App,
-    branch_input: &str,
+    branch_input: &TextInput,
     selected_branch: Option<usize>,
-    worktree_path: &str,
-    session_name: &str,
+    worktree_path: &TextInput,
+    session_name: &TextInput,
+    draft_pr: bool,
     field: NewWorktreeField,
     path_suggestions: &[String],
     path_selected: Option<usize>,
@@ -467,7 +704,7 @@ pub fn render_new_worktree_dialog(
     let filtered_branches = app.filtered_branches();
     let is_new_branch = selected_branch.is_none()
         && !branch_input.is_empty()
-        && !filtered_branches.contains(&branch_input);
+        && !filtered_branches.contains(&branch_input.as_str());
 
     // Calculate dialog height based on suggestions shown
     let branches_to_show = if field == NewWorktreeField::Branch && !filtered_branches.is_empty() {
@@ -491,7 +728,7 @@ pub fn render_new_worktree_dialog(
     } else {
         0
     };
-    let dialog_height = 10
+    let dialog_height = 12
         + branches_to_show as u16
         + branch_extra as u16
         + path_suggestions_to_show as u16
@@ -526,31 +763,22 @@ pub fn render_new_worktree_dialog(
 
     // Calculate branch ghost text
     let branch_ghost = if field == NewWorktreeField::Branch {
-        crate::completion::branch_ghost_text(branch_input, &filtered_branches, selected_branch)
+        crate::completion::branch_ghost_text(
+            branch_input.as_str(),
+            &filtered_branches,
+            selected_branch,
+        )
     } else {
         None
     };
 
-    let mut branch_spans = vec![
-        Span::styled("Branch:  ", branch_style),
-        Span::styled(branch_input, Style::default().fg(Color::Yellow)),
-    ];
-
-    // Add branch ghost text
-    if let Some(ref ghost) = branch_ghost {
-        branch_spans.push(Span::styled(
-            ghost,
-            Style::default()
-                .fg(Color::DarkGray)
-                .add_modifier(Modifier::DIM),
-        ));
-    }
-
-    // Add cursor
-    if field == NewWorktreeField::Branch {
-        branch_spans.push(Span::raw("_"));
-    }
-
+    let mut branch_spans = vec![Span::styled("Branch:  ", branch_style)];
+    branch_spans.extend(input_spans(
+        branch_input,
+        Style::default().fg(Color::Yellow),
+        field == NewWorktreeField::Branch,
+        branch_ghost.as_deref(),
+    ));
     branch_spans.push(branch_indicator);
     lines.push(Line::from(branch_spans));
 
@@ -604,31 +832,18 @@ pub fn render_new_worktree_dialog(
 
     // Calculate path ghost text
     let path_ghost = if field == NewWorktreeField::Path {
-        crate::completion::complete_path(worktree_path).ghost_text
+        crate::completion::complete_path(worktree_path.as_str()).ghost_text
     } else {
         None
     };
 
-    let mut path_spans = vec![
-        Span::styled("Path:    ", path_style),
-        Span::styled(worktree_path, Style::default().fg(Color::Yellow)),
-    ];
-
-    // Add path ghost text
-    if let Some(ref ghost) = path_ghost {
-        path_spans.push(Span::styled(
-            ghost,
-            Style::default()
-                .fg(Color::DarkGray)
-                .add_modifier(Modifier::DIM),
-        ));
-    }
-
-    // Add cursor
-    if field == NewWorktreeField::Path {
-        path_spans.push(Span::raw("_"));
-    }
-
+    let mut path_spans = vec![Span::styled("Path:    ", path_style)];
+    path_spans.extend(input_spans(
+        worktree_path,
+        Style::default().fg(Color::Yellow),
+        field == NewWorktreeField::Path,
+        path_ghost.as_deref(),
+    ));
     lines.push(Line::from(path_spans));
 
     // Show path suggestions when path field is active
@@ -679,19 +894,39 @@ pub fn render_new_worktree_dialog(
         Style::default()
     };
 
+    let mut session_spans = vec![Span::styled("Session: ", session_style)];
+    session_spans.extend(input_spans(
+        session_name,
+        Style::default().fg(Color::Yellow),
+        field == NewWorktreeField::SessionName,
+        None,
+    ));
+    lines.push(Line::from(session_spans));
+
+    lines.push(Line::raw(""));
+
+    let draft_style = if field == NewWorktreeField::DraftPr {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
     lines.push(Line::from(vec![
-        Span::styled("Session: ", session_style),
-        Span::styled(session_name, Style::default().fg(Color::Yellow)),
-        if field == NewWorktreeField::SessionName {
-            Span::raw("_")
-        } else {
-            Span::raw("")
-        },
+        Span::styled("Draft:   ", draft_style),
+        Span::styled(
+            if draft_pr {
+                "[x] open a draft PR after the first push"
+            } else {
+                "[ ] open a draft PR after the first push"
+            },
+            Style::default().fg(Color::Cyan),
+        ),
     ]));
 
     lines.push(Line::raw(""));
     lines.push(Line::styled(
-        "Tab switch  ↑↓ select  → accept  Enter create  Esc cancel",
+        "Tab switch  ↑↓ select  → accept  Space toggle  Enter create",
         Style::default().fg(Color::DarkGray),
     ));
 
@@ -704,7 +939,77 @@ pub fn render_new_worktree_dialog(
     frame.render_widget(paragraph, area);
 }
 
-pub fn render_rename_dialog(frame: &mut Frame, old_name: &str, new_name: &str) {
+/// Strategy picker row of the merge confirmations
+fn merge_strategy_line(strategy: MergeStrategy) -> Line<'static> {
+    Line::from(vec![
+        Span::raw("Strategy: "),
+        Span::styled(
+            format!("◂ {} ▸", strategy.label()),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ])
+}
+
+pub fn render_type_to_confirm(frame: &mut Frame, app: &App, expected: &str, input: &TextInput) {
+    let area = centered_rect(55, 8, frame.area());
+
+    let block = Block::default()
+        .title(" Confirm ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+
+    let label = app
+        .pending_action
+        .as_ref()
+        .map(|action| action.label())
+        .unwrap_or("?");
+
+    let matches = input.as_str() == expected;
+    let input_color = if matches { Color::Green } else { Color::Yellow };
+    let mut input_line = vec![Span::raw("> ")];
+    input_line.extend(input_spans(
+        input,
+        Style::default().fg(input_color),
+        true,
+        None,
+    ));
+
+    let hint = if matches {
+        Line::styled(
+            "Press Enter to confirm",
+            Style::default().fg(Color::DarkGray),
+        )
+    } else {
+        Line::styled(
+            format!("Type '{}' to confirm", expected),
+            Style::default().fg(Color::DarkGray),
+        )
+    };
+
+    // The hint below keeps the full name, since it is what must be typed
+    let inner = area.width.saturating_sub(2) as usize;
+    let name_columns = inner.saturating_sub(label.width() + 4);
+    let text = Text::from(vec![
+        Line::from(format!(
+            "{} '{}'?",
+            label,
+            truncate_display(expected, name_columns)
+        )),
+        Line::raw(""),
+        Line::from(input_line),
+        Line::raw(""),
+        hint,
+    ]);
+
+    let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_rename_dialog(frame: &mut Frame, old_name: &str, new_name: &TextInput) {
     let area = centered_rect(50, 6, frame.area());
 
     let block = Block::default()
@@ -712,22 +1017,736 @@ pub fn render_rename_dialog(frame: &mut Frame, old_name: &str, new_name: &str) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
+    let mut name_spans = vec![Span::raw("New name: ")];
+    name_spans.extend(input_spans(
+        new_name,
+        Style::default().fg(Color::Yellow),
+        true,
+        None,
+    ));
+
+    let text = Text::from(vec![
+        Line::from(name_spans),
+        Line::raw(""),
+        Line::styled(
+            "Press Enter to confirm",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+
+    let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_rename_window_dialog(
+    frame: &mut Frame,
+    session: &str,
+    windows: &[(String, String)],
+    selected: usize,
+    new_name: &TextInput,
+) {
+    let area = centered_rect(50, 8, frame.area());
+
+    let (index, old_name) = &windows[selected];
+    let block = Block::default()
+        .title(format!(" Rename Window {}:{} ", session, index))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let mut name_spans = vec![Span::raw("New name: ")];
+    name_spans.extend(input_spans(
+        new_name,
+        Style::default().fg(Color::Yellow),
+        true,
+        None,
+    ));
+
+    let window_hint = if windows.len() > 1 {
+        format!(
+            "Window {} of {} ('{}'), Tab for the next",
+            selected + 1,
+            windows.len(),
+            old_name
+        )
+    } else {
+        format!("Currently '{}'", old_name)
+    };
+
     let text = Text::from(vec![
+        Line::from(name_spans),
+        Line::raw(""),
+        Line::styled(window_hint, Style::default().fg(Color::DarkGray)),
+        Line::styled(
+            "Press Enter to confirm",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+
+    let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_create_tag_dialog(
+    frame: &mut Frame,
+    name: &TextInput,
+    message: &TextInput,
+    push: bool,
+    field: CreateTagField,
+) {
+    let area = centered_rect(60, 10, frame.area());
+
+    let block = Block::default()
+        .title(" Tag Current Commit ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let field_style = |active: bool| {
+        if active {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        }
+    };
+    let name_is_valid = name.is_empty() || crate::git::is_valid_tag_name(name.as_str());
+
+    let mut name_spans = vec![Span::styled(
+        "Name:    ",
+        field_style(field == CreateTagField::Name),
+    )];
+    name_spans.extend(input_spans(
+        name,
+        Style::default().fg(if name_is_valid {
+            Color::Yellow
+        } else {
+            Color::Red
+        }),
+        field == CreateTagField::Name,
+        None,
+    ));
+
+    let mut message_spans = vec![Span::styled(
+        "Message: ",
+        field_style(field == CreateTagField::Message),
+    )];
+    message_spans.extend(input_spans(
+        message,
+        Style::default().fg(Color::Yellow),
+        field == CreateTagField::Message,
+        None,
+    ));
+    if message.is_empty() {
+        message_spans.push(Span::styled(
+            "(defaults to name)",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    let text = Text::from(vec![
+        Line::from(name_spans),
+        Line::raw(""),
+        Line::from(message_spans),
+        Line::raw(""),
         Line::from(vec![
-            Span::raw("New name: "),
-            Span::styled(new_name, Style::default().fg(Color::Yellow)),
-            Span::raw("_"),
+            Span::styled("Push:    ", field_style(field == CreateTagField::Push)),
+            Span::styled(
+                if push {
+                    "[x] push to remote"
+                } else {
+                    "[ ] push to remote"
+                },
+                Style::default().fg(Color::Cyan),
+            ),
         ]),
         Line::raw(""),
         Line::styled(
-            "Press Enter to confirm",
+            "[Tab] Next field  [Space] Toggle push  [Enter] Create  [Esc] Cancel",
             Style::default().fg(Color::DarkGray),
         ),
     ]);
 
-    let paragraph = Paragraph::new(text)
+    let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: false });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_tags(frame: &mut Frame, tags: &[String], scroll: usize) {
+    let area = centered_rect(50, 16, frame.area());
+
+    let block = Block::default()
+        .title(format!(" Tags ({}) ", tags.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let lines: Vec<Line> = if tags.is_empty() {
+        vec![Line::styled(
+            "No tags in this repository",
+            Style::default().fg(Color::DarkGray),
+        )]
+    } else {
+        tags.iter()
+            .skip(scroll)
+            .map(|tag| Line::styled(format!("  {}", tag), Style::default().fg(Color::Yellow)))
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_diff(frame: &mut Frame, title: &str, lines: &[String], scroll: usize) {
+    let parent = frame.area();
+    let area = centered_rect(
+        parent.width.saturating_sub(4),
+        parent.height.saturating_sub(2),
+        parent,
+    );
+
+    let position = if lines.is_empty() {
+        String::new()
+    } else {
+        format!("  {}/{}", scroll + 1, lines.len())
+    };
+    let block = Block::default()
+        .title(format!(" {}{} ", title, position))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    // Only the visible window is styled, so large diffs stay cheap to draw
+    let visible = area.height.saturating_sub(2) as usize;
+    let text: Vec<Line> = if lines.is_empty() {
+        vec![Line::styled(
+            "Empty diff",
+            Style::default().fg(Color::DarkGray),
+        )]
+    } else {
+        lines
+            .iter()
+            .skip(scroll)
+            .take(visible)
+            .map(|line| Line::styled(line.as_str(), diff_line_style(line)))
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(Text::from(text)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_pr_comments(frame: &mut Frame, title: &str, lines: &[CommentLine], scroll: usize) {
+    let parent = frame.area();
+    let area = centered_rect(
+        parent.width.saturating_sub(4),
+        parent.height.saturating_sub(2),
+        parent,
+    );
+
+    let position = if lines.is_empty() {
+        String::new()
+    } else {
+        format!("  {}/{}", scroll + 1, lines.len())
+    };
+    let block = Block::default()
+        .title(format!(" {}{} ", title, position))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    // Only the visible window is laid out, so long threads stay cheap to
+    // draw; wrapped lines push the rest of the window down
+    let visible = area.height.saturating_sub(2) as usize;
+    let text: Vec<Line> = if lines.is_empty() {
+        vec![Line::styled(
+            "No comments yet",
+            Style::default().fg(Color::DarkGray),
+        )]
+    } else {
+        lines
+            .iter()
+            .skip(scroll)
+            .take(visible)
+            .map(|line| match line {
+                CommentLine::Reviewer(name) => Line::styled(
+                    name.as_str(),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                CommentLine::Header(header) => Line::styled(
+                    format!("  {}", header),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                CommentLine::Body(body) => Line::from(format!("    {}", body)),
+                CommentLine::Blank => Line::default(),
+            })
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(Text::from(text))
         .block(block)
-        .wrap(Wrap { trim: true });
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+/// Style of a unified diff line: file headers bold, hunk headers cyan,
+/// additions green, deletions red
+fn diff_line_style(line: &str) -> Style {
+    if line.starts_with("diff ") || line.starts_with("+++") || line.starts_with("---") {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else if line.starts_with("@@") {
+        Style::default().fg(Color::Cyan)
+    } else if line.starts_with('+') {
+        Style::default().fg(Color::Green)
+    } else if line.starts_with('-') {
+        Style::default().fg(Color::Red)
+    } else {
+        Style::default()
+    }
+}
+
+pub fn render_detect_debug(frame: &mut Frame, session: &str, panes: &[PaneDebug], scroll: usize) {
+    let parent = frame.area();
+    let area = centered_rect(
+        parent.width.saturating_sub(4),
+        parent.height.saturating_sub(2),
+        parent,
+    );
+
+    let block = Block::default()
+        .title(format!(" Detection: {} ", session))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let mut lines: Vec<Line> = Vec::new();
+    for pane in panes {
+        let kind = if pane.claude {
+            "claude pane"
+        } else {
+            "not claude"
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} {}", pane.id, pane.command),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  ({})  ", kind),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!("{} {}", pane.status.symbol(), pane.status.label()),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]));
+        for note in &pane.notes {
+            lines.push(Line::styled(
+                format!("  · {}", note),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        for (i, line) in pane.capture.iter().enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:>4} │ ", i + 1),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(line.as_str()),
+            ]));
+        }
+        lines.push(Line::raw(""));
+    }
+    if panes.is_empty() {
+        lines.push(Line::styled(
+            "No panes",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    let visible = area.height.saturating_sub(2) as usize;
+    let text: Vec<Line> = lines.into_iter().skip(scroll).take(visible).collect();
+    let paragraph = Paragraph::new(Text::from(text)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_color_tag_picker(frame: &mut Frame, session: &str, selected: usize) {
+    let area = centered_rect(40, TagColor::ALL.len() as u16 + 3, frame.area());
+
+    let block = Block::default()
+        .title(format!(" Color tag: {} ", session))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let entries = TagColor::ALL
+        .iter()
+        .map(|&tag| (tag.name(), Style::default().fg(super::tag_color(tag))))
+        .chain([("none", Style::default().fg(Color::DarkGray))]);
+    let lines: Vec<Line> = entries
+        .enumerate()
+        .map(|(i, (name, style))| {
+            let (marker, style) = if i == selected {
+                ("▸ ", style.add_modifier(Modifier::BOLD))
+            } else {
+                ("  ", style)
+            };
+            Line::from(vec![Span::raw(marker), Span::styled(name, style)])
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_compare_picker(frame: &mut Frame, session: &str, others: &[String], selected: usize) {
+    let area = centered_rect(50, (others.len() as u16 + 2).min(16), frame.area());
+
+    let block = Block::default()
+        .title(format!(" Compare {} with ", session))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    // Keep the highlighted session in view when the list is taller than the box
+    let visible = area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = others
+        .iter()
+        .enumerate()
+        .skip((selected + 1).saturating_sub(visible))
+        .map(|(i, name)| {
+            if i == selected {
+                Line::from(vec![
+                    Span::raw("▸ "),
+                    Span::styled(
+                        name.clone(),
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ])
+            } else {
+                Line::from(vec![Span::raw("  "), Span::raw(name.clone())])
+            }
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_clients(
+    frame: &mut Frame,
+    clients: &[Client],
+    own: Option<&str>,
+    selected: usize,
+    target: Option<&str>,
+) {
+    let area = centered_rect(76, (clients.len() as u16 + 4).clamp(5, 18), frame.area());
+
+    let block = Block::default()
+        .title(" Clients ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let mut lines: Vec<Line> = if clients.is_empty() {
+        vec![Line::styled(
+            "No clients attached",
+            Style::default().fg(Color::DarkGray),
+        )]
+    } else {
+        let tty_width = clients.iter().map(|c| c.tty.width()).max().unwrap_or(0);
+        let session_width = clients.iter().map(|c| c.session.width()).max().unwrap_or(0);
+        clients
+            .iter()
+            .enumerate()
+            .map(|(i, client)| {
+                let (marker, tty_style) = if i == selected {
+                    (
+                        "▸ ",
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    ("  ", Style::default().fg(Color::Yellow))
+                };
+                let mut spans = vec![
+                    Span::raw(marker),
+                    Span::styled(format!("{:<tty_width$}", client.tty), tty_style),
+                    Span::raw("  "),
+                    Span::styled(
+                        format!("{:<session_width$}", client.session),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(
+                        format!("  {}  {}", client.size, client.termname),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ];
+                if client.read_only {
+                    spans.push(Span::styled(
+                        "  read-only",
+                        Style::default().fg(Color::Blue),
+                    ));
+                }
+                if own == Some(client.tty.as_str()) {
+                    spans.push(Span::styled(
+                        "  (this client)",
+                        Style::default().fg(Color::Green),
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    };
+
+    lines.push(Line::raw(""));
+    lines.push(Line::styled(
+        match target {
+            Some(name) => format!("Enter shows '{}' on the highlighted client", name),
+            None => "Select a session in the list to switch a client to it".to_string(),
+        },
+        Style::default().fg(Color::DarkGray),
+    ));
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_macros(frame: &mut Frame, macros: &[Macro], selected: usize) {
+    let area = centered_rect(60, (macros.len() as u16 + 2).min(16), frame.area());
+
+    let block = Block::default()
+        .title(" Run Macro ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let lines: Vec<Line> = macros
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let (marker, name_style) = if i == selected {
+                (
+                    "▸ ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(Color::Yellow))
+            };
+            Line::from(vec![
+                Span::raw(marker),
+                Span::styled(m.name.clone(), name_style),
+                Span::styled(
+                    format!("  {}", m.steps.join(" → ")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_batch_results(
+    frame: &mut Frame,
+    title: &str,
+    entries: &[(String, Result<String, String>)],
+) {
+    let area = centered_rect(70, (entries.len() as u16 + 2).min(16), frame.area());
+
+    let block = Block::default()
+        .title(format!(" {} ", title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let lines: Vec<Line> = entries
+        .iter()
+        .map(|(label, outcome)| {
+            let (mark, mark_style, detail) = match outcome {
+                Ok(detail) => ("✓", Style::default().fg(Color::Green), detail),
+                Err(reason) => ("✗", Style::default().fg(Color::Red), reason),
+            };
+            Line::from(vec![
+                Span::styled(format!(" {} ", mark), mark_style),
+                Span::styled(label.clone(), Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("  {}", detail),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_history(
+    frame: &mut Frame,
+    entries: &[HistoryEntry],
+    scroll: usize,
+    confirm_clear: bool,
+    stored: Option<&Path>,
+) {
+    let area = centered_rect(80, 18, frame.area());
+
+    let block = Block::default()
+        .title(format!(" History ({}) ", entries.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let mut lines: Vec<Line> = Vec::new();
+    if confirm_clear {
+        let target = if stored.is_some() {
+            "and delete the history file"
+        } else {
+            "for this run"
+        };
+        lines.push(Line::styled(
+            format!("Clear all {} entries {}? (y/n)", entries.len(), target),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::raw(""));
+    } else if let Some(path) = stored {
+        lines.push(Line::styled(
+            format!("Stored in {}", path.display()),
+            Style::default().fg(Color::DarkGray),
+        ));
+        lines.push(Line::raw(""));
+    }
+
+    lines.extend(if entries.is_empty() {
+        vec![Line::styled(
+            "No actions performed yet",
+            Style::default().fg(Color::DarkGray),
+        )]
+    } else {
+        let now = history::now();
+        entries
+            .iter()
+            .rev()
+            .skip(scroll)
+            .map(|entry| {
+                let (mark, mark_style) = if entry.ok {
+                    ("✓", Style::default().fg(Color::Green))
+                } else {
+                    ("✗", Style::default().fg(Color::Red))
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("  {:>8}  ", entry.age(now)),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(mark, mark_style),
+                    Span::raw(" "),
+                    Span::styled(entry.action.clone(), Style::default().fg(Color::White)),
+                    Span::styled(
+                        format!("  {}", entry.session),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(
+                        format!("  {}", entry.detail),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ])
+            })
+            .collect()
+    });
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_stage_files(
+    frame: &mut Frame,
+    files: &[ChangedFile],
+    selected: usize,
+    confirm_discard: bool,
+) {
+    let area = centered_rect(60, 18, frame.area());
+
+    let block = Block::default()
+        .title(format!(" Stage files ({}) ", files.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    // Keep the highlighted file visible (borders and confirmation line take 4 rows)
+    let visible = area.height.saturating_sub(4) as usize;
+    let skip = (selected + 1).saturating_sub(visible);
+
+    let mut lines: Vec<Line> = if files.is_empty() {
+        vec![Line::styled(
+            "No uncommitted changes",
+            Style::default().fg(Color::DarkGray),
+        )]
+    } else {
+        files
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(visible)
+            .map(|(i, file)| {
+                let code_color = if file.untracked {
+                    Color::Red
+                } else if file.staged && !file.unstaged {
+                    Color::Green
+                } else {
+                    Color::Yellow
+                };
+                let path_style = if i == selected {
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let marker = if i == selected { "▶ " } else { "  " };
+
+                Line::from(vec![
+                    Span::styled(marker, Style::default().fg(Color::Cyan)),
+                    Span::styled(file.status_code(), Style::default().fg(code_color)),
+                    Span::raw(" "),
+                    Span::styled(file.path.clone(), path_style),
+                ])
+            })
+            .collect()
+    };
+
+    if confirm_discard {
+        if let Some(file) = files.get(selected) {
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                format!("Discard all changes to {}? (y/n)", file.path),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+        }
+    }
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
 
     frame.render_widget(Clear, area);
     frame.render_widget(paragraph, area);
@@ -7,10 +7,11 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
-pub fn render_help(frame: &mut Frame) {
-    let area = centered_rect(60, 21, frame.area());
+use crate::app::truncate_display;
 
+pub fn render_help(frame: &mut Frame) {
     let block = Block::default()
         .title(" Help ")
         .borders(Borders::ALL)
@@ -23,6 +24,7 @@ pub fn render_help(frame: &mut Frame) {
         )),
         Line::raw("  j / ↓       Move down"),
         Line::raw("  k / ↑       Move up"),
+        Line::raw("  ] / [       Next / previous session waiting for input"),
         Line::raw("  l / →       Open action menu"),
         Line::raw("  Enter       Switch to session"),
         Line::raw(""),
@@ -31,10 +33,30 @@ pub fn render_help(frame: &mut Frame) {
             Style::default().add_modifier(Modifier::BOLD),
         )),
         Line::raw("  n           New session"),
+        Line::raw("  w           New session from worktree"),
+        Line::raw("  G           Open session at repository root"),
         Line::raw("  K           Kill session"),
+        Line::raw("  u           Recreate last killed session"),
+        Line::raw("  t           Retry push/fetch/pull after an auth failure"),
         Line::raw("  r           Rename session"),
+        Line::raw("  y           Copy cd command"),
         Line::raw("  /           Filter sessions"),
         Line::raw("  R           Refresh list"),
+        Line::raw("  Ctrl-R      Refresh selected session"),
+        Line::raw("  d           Toggle dense list"),
+        Line::raw("  g           Group by repository"),
+        Line::raw("  p           Only show the selected session's repository"),
+        Line::raw("  .           Show/hide hidden sessions"),
+        Line::raw("  o           Cycle sort (dirty, status, name, uptime, activity)"),
+        Line::raw("  0           Reset view (filter, sort, selection)"),
+        Line::raw("  F           Follow preview live"),
+        Line::raw("  M           Run a macro"),
+        Line::raw("  H           Action history"),
+        Line::raw("  C           Attached tmux clients"),
+        Line::raw("  s           Search preview (n/N older/newer)"),
+        Line::raw("  PgUp / PgDn Scroll the preview back / forward (also Ctrl-U / D)"),
+        Line::raw("  Shift-← / → Scroll wide preview lines sideways"),
+        Line::raw("  D           Detection debug (with --debug)"),
         Line::raw(""),
         Line::from(Span::styled(
             "Action Menu",
@@ -42,6 +64,23 @@ pub fn render_help(frame: &mut Frame) {
         )),
         Line::raw("  h / ←       Go back"),
         Line::raw("  Enter       Execute action"),
+        Line::raw("  i           Show / hide session details"),
+        Line::raw(""),
+        Line::from(Span::styled(
+            "Text Fields",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::raw("  ← / →       Move cursor"),
+        Line::raw("  Home / End  Start / end of field"),
+        Line::raw("  Ctrl-W      Delete word"),
+        Line::raw("  Ctrl-G      Write commit message / PR body in $EDITOR"),
+        Line::raw(""),
+        Line::from(Span::styled(
+            "Stage Files",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::raw("  Space       Stage / unstage file"),
+        Line::raw("  x           Discard file changes"),
         Line::raw(""),
         Line::from(Span::styled(
             "Other",
@@ -51,6 +90,8 @@ pub fn render_help(frame: &mut Frame) {
         Line::raw("  q / Esc     Quit"),
     ];
 
+    let area = centered_rect(60, help_text.len() as u16 + 2, frame.area());
+
     let paragraph = Paragraph::new(help_text)
         .block(block)
         .wrap(Wrap { trim: true });
@@ -62,13 +103,15 @@ pub fn render_help(frame: &mut Frame) {
 pub fn render_message(frame: &mut Frame, message: &str, color: Color) {
     let area = frame.area();
 
-    // Calculate height needed (at least 1, up to 3 for longer messages)
+    // Calculate height needed (at least 1, up to 3 for longer messages),
+    // cutting off anything longer rather than losing its end mid-wrap
     let max_width = area.width.saturating_sub(6) as usize;
-    let lines_needed = if max_width > 0 {
-        (message.len() / max_width + 1).min(3)
-    } else {
-        1
-    };
+    let message = truncate_display(message, max_width * 3);
+    let lines_needed = message
+        .width()
+        .checked_div(max_width)
+        .map(|n| (n + 1).min(3))
+        .unwrap_or(1);
     let height = lines_needed as u16;
 
     let msg_area = Rect {
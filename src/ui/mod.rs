@@ -8,6 +8,8 @@
 mod dialogs;
 mod help;
 
+use std::ops::Range;
+
 use ansi_to_tui::IntoText;
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
@@ -16,10 +18,13 @@ use ratatui::{
     widgets::{Clear, List, ListItem, Paragraph, StatefulWidget},
     Frame,
 };
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::app::{App, Mode};
-use crate::session::ClaudeCodeStatus;
+use crate::app::{format_memory, match_ranges, pr_label, App, Edits, Mode, SortMode, TextInput};
+use crate::color_tags::TagColor;
+use crate::git::{compact_count, RepoState};
+use crate::history::History;
+use crate::session::{ClaudeCodeStatus, Session};
 
 /// Render the application UI
 pub fn render(frame: &mut Frame, app: &mut App) {
@@ -50,9 +55,13 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         Mode::ConfirmAction => {
             dialogs::render_confirm_action(frame, app);
         }
+        Mode::TypeToConfirm { expected, input } => {
+            dialogs::render_type_to_confirm(frame, app, expected, input);
+        }
         Mode::NewSession {
             name,
             path,
+            url,
             field,
             path_suggestions,
             path_selected,
@@ -61,6 +70,7 @@ pub fn render(frame: &mut Frame, app: &mut App) {
                 frame,
                 name,
                 path,
+                url,
                 *field,
                 path_suggestions,
                 *path_selected,
@@ -69,14 +79,35 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         Mode::Rename { old_name, new_name } => {
             dialogs::render_rename_dialog(frame, old_name, new_name);
         }
-        Mode::Commit { message } => {
-            dialogs::render_commit_dialog(frame, message);
+        Mode::RenameWindow {
+            session,
+            windows,
+            selected,
+            new_name,
+        } => {
+            dialogs::render_rename_window_dialog(frame, session, windows, *selected, new_name);
+        }
+        Mode::Commit {
+            message,
+            author,
+            field,
+            ..
+        } => {
+            dialogs::render_commit_dialog(frame, message, author, *field);
+        }
+        Mode::Squash {
+            base_branch,
+            count,
+            message,
+        } => {
+            dialogs::render_squash_dialog(frame, base_branch, *count, message);
         }
         Mode::NewWorktree {
             branch_input,
             selected_branch,
             worktree_path,
             session_name,
+            draft_pr,
             field,
             path_suggestions,
             path_selected,
@@ -89,21 +120,113 @@ pub fn render(frame: &mut Frame, app: &mut App) {
                 *selected_branch,
                 worktree_path,
                 session_name,
+                *draft_pr,
                 *field,
                 path_suggestions,
                 *path_selected,
             );
         }
         Mode::Filter { input } => {
-            render_filter_bar(frame, input, layout[3]);
+            render_filter_bar(frame, "/ ", input, layout[3]);
+        }
+        Mode::PreviewSearch { input } => {
+            render_filter_bar(frame, "search: ", input, layout[3]);
         }
         Mode::CreatePullRequest {
             title,
             body,
             base_branch,
+            issue,
+            draft,
+            field,
+            ..
+        } => {
+            dialogs::render_create_pr_dialog(
+                frame,
+                title,
+                body,
+                base_branch,
+                issue,
+                *draft,
+                *field,
+            );
+        }
+        Mode::CreateTag {
+            name,
+            message,
+            push,
             field,
         } => {
-            dialogs::render_create_pr_dialog(frame, title, body, base_branch, *field);
+            dialogs::render_create_tag_dialog(frame, name, message, *push, *field);
+        }
+        Mode::Tags { tags, scroll } => {
+            dialogs::render_tags(frame, tags, *scroll);
+        }
+        Mode::Diff {
+            title,
+            lines,
+            scroll,
+        } => {
+            dialogs::render_diff(frame, title, lines, *scroll);
+        }
+        Mode::PrComments {
+            title,
+            lines,
+            scroll,
+        } => {
+            dialogs::render_pr_comments(frame, title, lines, *scroll);
+        }
+        Mode::DetectDebug {
+            session,
+            panes,
+            scroll,
+        } => {
+            dialogs::render_detect_debug(frame, session, panes, *scroll);
+        }
+        Mode::StageFiles {
+            files,
+            selected,
+            confirm_discard,
+            ..
+        } => {
+            dialogs::render_stage_files(frame, files, *selected, *confirm_discard);
+        }
+        Mode::ColorTag { session, selected } => {
+            dialogs::render_color_tag_picker(frame, session, *selected);
+        }
+        Mode::Compare {
+            session,
+            others,
+            selected,
+        } => {
+            dialogs::render_compare_picker(frame, session, others, *selected);
+        }
+        Mode::Macros { selected } => {
+            dialogs::render_macros(frame, &app.config.macros, *selected);
+        }
+        Mode::Clients {
+            clients,
+            own,
+            selected,
+        } => {
+            let target = app.selected_session().map(|s| s.name.as_str());
+            dialogs::render_clients(frame, clients, own.as_deref(), *selected, target);
+        }
+        Mode::BatchResults { title, entries } => {
+            dialogs::render_batch_results(frame, title, entries);
+        }
+        Mode::History {
+            scroll,
+            confirm_clear,
+        } => {
+            let stored = app.config.persist_history.then(History::path).flatten();
+            dialogs::render_history(
+                frame,
+                app.history.entries(),
+                *scroll,
+                *confirm_clear,
+                stored.as_deref(),
+            );
         }
         Mode::Help => {
             help::render_help(frame);
@@ -111,6 +234,10 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         Mode::Normal | Mode::ActionMenu => {}
     }
 
+    if app.mode.edits() == Some(Edits::ConfirmDiscard) {
+        dialogs::render_discard_prompt(frame);
+    }
+
     // Render error/message overlay
     if let Some(ref error) = app.error {
         help::render_message(frame, error, Color::Red);
@@ -148,15 +275,29 @@ fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
     // (items building borrows app immutably, scroll_state needs mutable access)
     let mut scroll_state = std::mem::take(&mut app.scroll_state);
 
-    let filtered = app.filtered_sessions();
+    let filtered = app.filtered_session_matches();
 
     if filtered.is_empty() {
-        let empty_msg = if app.filter.is_empty() {
-            "No tmux sessions found. Press 'n' to create one."
+        let hint_style = Style::default().fg(Color::Cyan);
+        let empty_lines = if !app.sessions.is_empty() {
+            vec![Line::raw("No sessions match the filter.")]
         } else {
-            "No sessions match the filter."
+            let cwd = std::env::current_dir()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| "~".to_string());
+            let headline = if app.no_server {
+                "No tmux server running."
+            } else {
+                "No tmux sessions found."
+            };
+            vec![
+                Line::raw(headline),
+                Line::raw(""),
+                Line::styled("Press Enter or 'n' to start a session in", hint_style),
+                Line::styled(cwd, hint_style),
+            ]
         };
-        let paragraph = Paragraph::new(empty_msg)
+        let paragraph = Paragraph::new(empty_lines)
             .style(Style::default().fg(Color::DarkGray))
             .alignment(Alignment::Center);
         frame.render_widget(paragraph, area);
@@ -165,153 +306,316 @@ fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
         return;
     }
 
-    // Calculate column widths
-    let display_names: Vec<String> = filtered.iter().map(|s| s.display_name()).collect();
-    let max_name_len = display_names
+    // Calculate column widths over every session, so they stay put while scrolling
+    let max_name_len = filtered
         .iter()
-        .map(|n| n.as_str().width())
+        .map(|(s, _)| s.display_name().as_str().width())
         .max()
         .unwrap_or(10)
         .max(10);
 
+    // Only the rows inside the visible window are built (and only their
+    // sessions' git context detected); rows above it are just counted
+    let visible = scroll_state.update(selected_index, total_items, visible_height);
     let mut items: Vec<ListItem> = Vec::new();
+    let mut shown_panes = Vec::new();
+    let mut row = 0;
 
-    for (i, session) in filtered.iter().enumerate() {
-        let is_selected = i == app.selected;
-        let is_current = app
-            .current_session
-            .as_ref()
-            .is_some_and(|c| c == &session.name);
+    for (i, (session, name_matches)) in filtered.iter().enumerate() {
+        if row >= visible.end {
+            break;
+        }
+
+        // Repo header row before the first session of each group
+        if app.group_by_repo && (i == 0 || filtered[i - 1].0.repo_key() != session.repo_key()) {
+            if visible.contains(&row) {
+                items.push(render_repo_header(session.repo_key()));
+            }
+            row += 1;
+        }
 
-        // Show ▾ when action menu is open for this session, ▸ when selected but collapsed
+        let is_selected = i == app.selected;
+        // Show expanded content when in action menu mode for this session
         let is_expanded = is_selected && matches!(app.mode, Mode::ActionMenu);
-        let marker = if is_selected {
-            if is_expanded {
-                "▾"
-            } else {
-                "▸"
+
+        if visible.contains(&row) {
+            items.push(render_session_row(
+                app,
+                session,
+                name_matches,
+                max_name_len,
+                is_selected,
+                is_expanded,
+            ));
+            shown_panes.extend(session.claude_code_pane.clone());
+        }
+        row += 1;
+
+        if is_expanded {
+            let mut expanded = Vec::new();
+            render_expanded_session_content(app, session, &mut expanded);
+            for item in expanded {
+                if visible.contains(&row) {
+                    items.push(item);
+                }
+                row += 1;
             }
+        }
+    }
+
+    // Scope the list rendering so borrows are released before we restore scroll_state
+    {
+        let list = List::new(items);
+
+        // The list only holds the visible rows, positioned by the scroll state
+        StatefulWidget::render(list, area, frame.buffer_mut(), scroll_state.list_state());
+    }
+
+    // Put scroll_state back into app (list borrows are now released)
+    app.scroll_state = scroll_state;
+    app.shown_panes = shown_panes;
+}
+
+/// Render one session's row of the session list
+fn render_session_row<'a>(
+    app: &'a App,
+    session: &'a Session,
+    name_matches: &[Range<usize>],
+    name_width: usize,
+    is_selected: bool,
+    is_expanded: bool,
+) -> ListItem<'a> {
+    let is_current = app
+        .current_session
+        .as_ref()
+        .is_some_and(|c| c == &session.name);
+
+    // Show ▾ when action menu is open for this session, ▸ when selected but collapsed
+    let marker = if is_selected {
+        if is_expanded {
+            "▾"
         } else {
-            " "
-        };
-        let status = &session.claude_code_status;
-
-        // Use brighter colors when selected so text is readable on dark background
-        let status_color = match (status, is_selected) {
-            (ClaudeCodeStatus::Working, _) => Color::Green,
-            (ClaudeCodeStatus::WaitingInput, _) => Color::Yellow,
-            (ClaudeCodeStatus::Idle, true) => Color::White,
-            (ClaudeCodeStatus::Idle, false) => Color::DarkGray,
-            (ClaudeCodeStatus::Unknown, true) => Color::Gray,
-            (ClaudeCodeStatus::Unknown, false) => Color::DarkGray,
-        };
+            "▸"
+        }
+    } else {
+        " "
+    };
+    let status = &session.claude_code_status;
+
+    // Use brighter colors when selected so text is readable on dark background
+    let status_color = match (status, is_selected) {
+        (ClaudeCodeStatus::Working, _) => Color::Green,
+        (ClaudeCodeStatus::WaitingInput, _) => Color::Yellow,
+        (ClaudeCodeStatus::Idle, true) => Color::White,
+        (ClaudeCodeStatus::Idle, false) => Color::DarkGray,
+        (ClaudeCodeStatus::Unknown, true) => Color::Gray,
+        (ClaudeCodeStatus::Unknown, false) => Color::DarkGray,
+    };
+
+    let path_color = if is_selected {
+        Color::White
+    } else {
+        Color::DarkGray
+    };
+
+    let mut name_style = if is_current {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    if let Some(tag) = app.color_tags.get(&session.name) {
+        name_style = name_style.fg(tag_color(tag));
+    }
 
-        let path_color = if is_selected {
-            Color::White
+    // Build git info spans
+    let git_spans = if let Some(git) = session.git_context() {
+        let (open, close) = if git.is_worktree {
+            ("[", "]")
         } else {
-            Color::DarkGray
+            ("(", ")")
         };
-
-        let name_style = if is_current {
-            Style::default().add_modifier(Modifier::BOLD)
+        let bracket_color = if git.is_worktree {
+            Color::Magenta
         } else {
-            Style::default()
+            Color::Cyan
         };
 
-        // Build git info spans
-        let git_spans = if let Some(ref git) = session.git_context {
-            let (open, close) = if git.is_worktree {
-                ("[", "]")
-            } else {
-                ("(", ")")
-            };
-            let bracket_color = if git.is_worktree {
-                Color::Magenta
-            } else {
-                Color::Cyan
-            };
-
-            // Show status indicators: + for staged, * for unstaged
-            let mut status_str = String::new();
-            if git.has_staged {
-                status_str.push('+');
-            }
-            if git.has_unstaged {
-                status_str.push('*');
-            }
-            let status_spans = if !status_str.is_empty() {
-                let color = if git.has_staged && !git.has_unstaged {
-                    Color::Green // Only staged = green
-                } else {
-                    Color::Yellow // Mixed state = yellow
-                };
-                vec![Span::styled(
-                    format!(" {}", status_str),
-                    Style::default().fg(color),
-                )]
+        // Show status indicators: + for staged, * for unstaged, ? for untracked
+        let mut status_str = String::new();
+        if git.has_staged {
+            status_str.push('+');
+        }
+        if git.has_unstaged {
+            status_str.push('*');
+        }
+        if git.has_untracked {
+            status_str.push('?');
+        }
+        let status_spans = if !status_str.is_empty() {
+            let color = if git.has_staged && !git.has_unstaged && !git.has_untracked {
+                Color::Green // Only staged = green
             } else {
-                vec![]
+                Color::Yellow // Mixed state = yellow
             };
-
-            let mut spans = vec![
-                Span::raw(" "),
-                Span::styled(open, Style::default().fg(bracket_color)),
-                Span::styled(&git.branch, Style::default().fg(Color::Cyan)),
-                Span::styled(close, Style::default().fg(bracket_color)),
-            ];
-            spans.extend(status_spans);
-            spans
+            vec![Span::styled(
+                format!(" {}", status_str),
+                Style::default().fg(color),
+            )]
         } else {
             vec![]
         };
 
-        let mut line_spans = vec![
-            Span::raw(format!(" {} ", marker)),
-            Span::styled(
-                format!("{:<width$}", display_names[i], width = max_name_len),
-                name_style,
-            ),
+        let mut spans = vec![
+            Span::raw(" "),
+            Span::styled(open, Style::default().fg(bracket_color)),
+            Span::styled(&git.branch, Style::default().fg(Color::Cyan)),
+            Span::styled(close, Style::default().fg(bracket_color)),
+        ];
+        spans.extend(status_spans);
+        if let Some(state) = git.state {
+            spans.push(Span::raw(" "));
+            spans.push(repo_state_badge(state));
+        }
+        if let Some(pr) = app
+            .pr_status
+            .get(&git.workdir, &git.branch)
+            .filter(|_| app.config.pr_column)
+        {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                pr_label(pr),
+                Style::default().fg(pr_state_color(&pr.state)),
+            ));
+        }
+        spans
+    } else {
+        vec![]
+    };
+
+    // Padded by display width, as names may hold wide characters; the
+    // parts the filter matched are highlighted
+    let name = session.display_name();
+    let padding = " ".repeat(name_width.saturating_sub(name.width()));
+    let filter_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let mut name_spans = highlight_ranges(
+        Line::from(Span::styled(name, name_style)),
+        name_matches,
+        filter_style,
+    )
+    .spans;
+    name_spans.push(Span::raw(padding));
+    let status_span = Span::styled(status.symbol(), Style::default().fg(status_color));
+    let path_span = Span::styled(session.display_path(), Style::default().fg(path_color));
+
+    // Dense mode drops the status label and tightens spacing to fit more rows
+    // Indent group members below their repo header
+    let indent = if app.group_by_repo { "  " } else { "" };
+
+    let lead = if app.dense {
+        format!("{}{}", indent, marker)
+    } else {
+        format!("{} {} ", indent, marker)
+    };
+    let mut line_spans = vec![Span::raw(lead)];
+    line_spans.extend(name_spans);
+    if app.dense {
+        line_spans.extend([Span::raw(" "), status_span, Span::raw(" "), path_span]);
+    } else {
+        line_spans.extend([
             Span::raw("  "),
-            Span::styled(status.symbol(), Style::default().fg(status_color)),
+            status_span,
             Span::raw(" "),
             Span::styled(
                 format!("{:<8}", status.label()),
                 Style::default().fg(status_color),
             ),
             Span::raw("  "),
-            Span::styled(session.display_path(), Style::default().fg(path_color)),
-        ];
-        line_spans.extend(git_spans);
+            path_span,
+        ]);
+    }
+    line_spans.extend(git_spans);
 
-        let line = Line::from(line_spans);
+    let line = Line::from(line_spans);
 
-        let style = if is_selected {
-            Style::default().bg(Color::DarkGray)
-        } else {
-            Style::default()
-        };
+    // The selection background wins over the status tint
+    let style = if is_selected {
+        Style::default().bg(Color::DarkGray)
+    } else if let Some(tint) = status_tint(status).filter(|_| app.config.status_tint) {
+        Style::default().bg(tint)
+    } else {
+        Style::default()
+    };
 
-        items.push(ListItem::new(line).style(style));
+    ListItem::new(line).style(style)
+}
 
-        // Show expanded content when in action menu mode for this session
-        if is_expanded {
-            render_expanded_session_content(app, session, &mut items);
-        }
+/// Faint row background for statuses that need attention, when tinting is on
+fn status_tint(status: &ClaudeCodeStatus) -> Option<Color> {
+    match status {
+        ClaudeCodeStatus::WaitingInput => Some(Color::Rgb(60, 52, 16)),
+        ClaudeCodeStatus::Working => Some(Color::Rgb(18, 44, 24)),
+        ClaudeCodeStatus::Idle | ClaudeCodeStatus::Unknown => None,
     }
+}
 
-    // Scope the list rendering so borrows are released before we restore scroll_state
-    {
-        let list = List::new(items);
-
-        // Update scroll state with centered scrolling behavior
-        let list_state = scroll_state.update(selected_index, total_items, visible_height);
+/// Color of a pull request state (OPEN, CLOSED, MERGED)
+/// Terminal color of a session color tag
+fn tag_color(tag: TagColor) -> Color {
+    match tag {
+        TagColor::Red => Color::Red,
+        TagColor::Green => Color::Green,
+        TagColor::Yellow => Color::Yellow,
+        TagColor::Blue => Color::Blue,
+        TagColor::Magenta => Color::Magenta,
+        TagColor::Cyan => Color::Cyan,
+    }
+}
 
-        // Render with stateful widget for proper scrolling
-        StatefulWidget::render(list, area, frame.buffer_mut(), list_state);
+fn pr_state_color(state: &str) -> Color {
+    match state {
+        "OPEN" => Color::Green,
+        "CLOSED" => Color::Red,
+        "MERGED" => Color::Magenta,
+        _ => Color::Gray,
     }
+}
 
-    // Put scroll_state back into app (list borrows are now released)
-    app.scroll_state = scroll_state;
+/// Badge for a merge, rebase or other operation left in progress
+fn repo_state_badge(state: RepoState) -> Span<'static> {
+    Span::styled(
+        format!(" {} ", state.label()),
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Red)
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+/// Render a non-selectable header row for a repository group
+fn render_repo_header(repo_root: Option<&std::path::Path>) -> ListItem<'static> {
+    let label = match repo_root {
+        Some(root) => {
+            let name = root
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| root.display().to_string());
+            format!(" {} ", name)
+        }
+        None => " (no repository) ".to_string(),
+    };
+
+    ListItem::new(Line::from(vec![
+        Span::styled(" ──", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            label,
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("──", Style::default().fg(Color::DarkGray)),
+    ]))
 }
 
 /// Render the expanded content for a session in action menu mode
@@ -319,6 +623,45 @@ fn render_expanded_session_content<'a>(
     app: &'a App,
     session: &'a crate::session::Session,
     items: &mut Vec<ListItem<'a>>,
+) {
+    if app.menu_details {
+        render_session_details(app, session, items);
+    }
+
+    // Separator
+    let sep_line = Line::from(Span::styled(
+        "     ────────────────────────",
+        Style::default().fg(Color::DarkGray),
+    ));
+    items.push(ListItem::new(sep_line));
+
+    // Action items
+    for (action_idx, action) in app.available_actions.iter().enumerate() {
+        let is_action_selected = action_idx == app.selected_action;
+        let action_marker = if is_action_selected { "▸" } else { " " };
+        let action_style = if is_action_selected {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::White)
+        };
+
+        let action_line = Line::from(vec![
+            Span::raw("     "),
+            Span::styled(format!("{} {}", action_marker, action.label()), action_style),
+        ]);
+        items.push(ListItem::new(action_line));
+    }
+
+    // White separator at end of submenu
+    let end_sep = Line::from(Span::styled("", Style::default().fg(Color::White)));
+    items.push(ListItem::new(end_sep));
+}
+
+/// Render the metadata, git and PR rows above the action menu
+fn render_session_details<'a>(
+    app: &'a App,
+    session: &'a crate::session::Session,
+    items: &mut Vec<ListItem<'a>>,
 ) {
     let label_style = Style::default().fg(Color::DarkGray);
     let value_style = Style::default().fg(Color::White);
@@ -326,6 +669,12 @@ fn render_expanded_session_content<'a>(
     // Session metadata row
     let attached_str = if session.attached { "yes" } else { "no" };
     let pane_count = session.panes.len();
+    let cpu = session
+        .cpu_percent
+        .map_or_else(|| "n/a".to_string(), |cpu| format!("{:.1}%", cpu));
+    let memory = session
+        .memory_kb
+        .map_or_else(|| "n/a".to_string(), format_memory);
 
     let meta_line = Line::from(vec![
         Span::raw("     "),
@@ -340,17 +689,28 @@ fn render_expanded_session_content<'a>(
         Span::raw("  "),
         Span::styled("attached: ", label_style),
         Span::styled(attached_str, value_style),
+        Span::raw("  "),
+        Span::styled("cpu: ", label_style),
+        Span::styled(cpu, value_style),
+        Span::raw("  "),
+        Span::styled("mem: ", label_style),
+        Span::styled(memory, value_style),
     ]);
     items.push(ListItem::new(meta_line));
 
     // Git metadata row (if available)
-    if let Some(ref git) = session.git_context {
+    if let Some(git) = session.git_context() {
         let mut git_spans = vec![
             Span::raw("     "),
             Span::styled("branch: ", label_style),
             Span::styled(&git.branch, Style::default().fg(Color::Cyan)),
         ];
 
+        if let Some(state) = git.state {
+            git_spans.push(Span::raw("  "));
+            git_spans.push(repo_state_badge(state));
+        }
+
         if git.ahead > 0 || git.behind > 0 {
             git_spans.push(Span::raw("  "));
             if git.ahead > 0 {
@@ -383,10 +743,45 @@ fn render_expanded_session_content<'a>(
             git_spans.push(Span::styled("yes", Style::default().fg(Color::Yellow)));
         }
 
+        if git.has_untracked {
+            git_spans.push(Span::raw("  "));
+            git_spans.push(Span::styled("untracked: ", label_style));
+            git_spans.push(Span::styled("yes", Style::default().fg(Color::Red)));
+        }
+
+        if let Some(stat) = app.diff_stat {
+            let files = if stat.files_changed == 1 {
+                "file"
+            } else {
+                "files"
+            };
+            git_spans.push(Span::raw("  "));
+            git_spans.push(Span::styled(
+                format!("{} {} ", stat.files_changed, files),
+                value_style,
+            ));
+            git_spans.push(Span::styled(
+                format!("+{}", stat.insertions),
+                Style::default().fg(Color::Green),
+            ));
+            git_spans.push(Span::raw(" "));
+            git_spans.push(Span::styled(
+                format!("-{}", stat.deletions),
+                Style::default().fg(Color::Red),
+            ));
+        }
+
         if git.is_worktree {
             git_spans.push(Span::raw("  "));
             git_spans.push(Span::styled("worktree: ", label_style));
             git_spans.push(Span::styled("yes", Style::default().fg(Color::Magenta)));
+            if app.branch_merged {
+                git_spans.push(Span::raw("  "));
+                git_spans.push(Span::styled(
+                    "merged — safe to remove",
+                    Style::default().fg(Color::Green),
+                ));
+            }
         }
 
         items.push(ListItem::new(Line::from(git_spans)));
@@ -404,12 +799,8 @@ fn render_expanded_session_content<'a>(
             ];
 
             // State with color
-            let (state_text, state_color) = match pr_info.state.as_str() {
-                "OPEN" => ("open", Color::Green),
-                "CLOSED" => ("closed", Color::Red),
-                "MERGED" => ("merged", Color::Magenta),
-                _ => (pr_info.state.as_str(), Color::Gray),
-            };
+            let state_text = pr_info.state.to_lowercase();
+            let state_color = pr_state_color(&pr_info.state);
             pr_spans.push(Span::styled(state_text, Style::default().fg(state_color)));
 
             // Mergeable status (only show for open PRs)
@@ -423,40 +814,39 @@ fn render_expanded_session_content<'a>(
                 pr_spans.push(Span::styled(merge_text, Style::default().fg(merge_color)));
             }
 
+            // Size, as far as the host reports it
+            if let Some(commits) = pr_info.commits {
+                let noun = if commits == 1 { "commit" } else { "commits" };
+                pr_spans.push(Span::raw("  "));
+                pr_spans.push(Span::styled(
+                    format!("{} {}", compact_count(commits), noun),
+                    value_style,
+                ));
+            }
+            if let (Some(additions), Some(deletions)) = (pr_info.additions, pr_info.deletions) {
+                let separator = if pr_info.commits.is_some() {
+                    ", "
+                } else {
+                    "  "
+                };
+                pr_spans.push(Span::styled(separator, value_style));
+                pr_spans.push(Span::styled(
+                    format!("+{}", compact_count(additions)),
+                    Style::default().fg(Color::Green),
+                ));
+                pr_spans.push(Span::raw(" "));
+                pr_spans.push(Span::styled(
+                    format!("-{}", compact_count(deletions)),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+
             items.push(ListItem::new(Line::from(pr_spans)));
         }
     }
-
-    // Separator
-    let sep_line = Line::from(Span::styled(
-        "     ────────────────────────",
-        Style::default().fg(Color::DarkGray),
-    ));
-    items.push(ListItem::new(sep_line));
-
-    // Action items
-    for (action_idx, action) in app.available_actions.iter().enumerate() {
-        let is_action_selected = action_idx == app.selected_action;
-        let action_marker = if is_action_selected { "▸" } else { " " };
-        let action_style = if is_action_selected {
-            Style::default().fg(Color::Yellow)
-        } else {
-            Style::default().fg(Color::White)
-        };
-
-        let action_line = Line::from(vec![
-            Span::raw("     "),
-            Span::styled(format!("{} {}", action_marker, action.label()), action_style),
-        ]);
-        items.push(ListItem::new(action_line));
-    }
-
-    // White separator at end of submenu
-    let end_sep = Line::from(Span::styled("", Style::default().fg(Color::White)));
-    items.push(ListItem::new(end_sep));
 }
 
-fn render_preview(frame: &mut Frame, app: &App, area: Rect) {
+fn render_preview(frame: &mut Frame, app: &mut App, area: Rect) {
     // Clear the entire preview area first to prevent stale content
     frame.render_widget(Clear, area);
 
@@ -488,6 +878,7 @@ fn render_preview(frame: &mut Frame, app: &App, area: Rect) {
         width: area.width,
         height: area.height.saturating_sub(2),
     };
+    app.preview_height = content_area.height as usize;
 
     let content = match &app.preview_content {
         Some(text) if !text.is_empty() => text,
@@ -508,16 +899,116 @@ fn render_preview(frame: &mut Frame, app: &App, area: Rect) {
         }
     };
 
-    // Take only the last N lines that fit in the content area
+    // Take the N lines that fit in the content area, ending `preview_scroll`
+    // lines above the bottom of the buffer
     let available_lines = content_area.height as usize;
     let total_lines = styled_text.lines.len();
-    let start = total_lines.saturating_sub(available_lines);
-    let visible_lines: Vec<Line> = styled_text.lines.into_iter().skip(start).collect();
+    let scroll = app
+        .preview_scroll
+        .min(total_lines.saturating_sub(available_lines));
+    let end = total_lines - scroll;
+    let start = end.saturating_sub(available_lines);
+    let visible_lines: Vec<Line> = styled_text
+        .lines
+        .into_iter()
+        .enumerate()
+        .skip(start)
+        .take(end - start)
+        .map(|(i, line)| match app.preview_search {
+            Some(ref search) if search.matches.contains(&i) => {
+                let style = if search.current_line() == Some(i) {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                } else {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                };
+                highlight_matches(line, &search.query, style)
+            }
+            _ => line,
+        })
+        .map(|line| skip_columns(line, app.preview_h_scroll))
+        .collect();
 
     let preview = Paragraph::new(visible_lines);
     frame.render_widget(preview, content_area);
 }
 
+/// Drop the first `columns` display columns of `line`, keeping the styling
+/// of the rest. A wide character cut in half becomes a space so the
+/// remaining text stays aligned.
+fn skip_columns(line: Line<'_>, columns: usize) -> Line<'_> {
+    if columns == 0 {
+        return line;
+    }
+
+    let mut skipped = 0;
+    let mut spans = Vec::new();
+    for span in &line.spans {
+        if skipped >= columns {
+            spans.push(span.clone());
+            continue;
+        }
+        let mut kept = String::new();
+        for c in span.content.chars() {
+            let width = c.width().unwrap_or(0);
+            if skipped >= columns {
+                kept.push(c);
+            } else if skipped + width > columns {
+                kept.push_str(&" ".repeat(skipped + width - columns));
+                skipped = columns;
+            } else {
+                skipped += width;
+            }
+        }
+        if !kept.is_empty() {
+            spans.push(Span::styled(kept, span.style));
+        }
+    }
+
+    Line { spans, ..line }
+}
+
+/// Patch `style` onto the parts of `line` matching `query`, splitting spans
+/// at match boundaries so the rest of the line keeps its ANSI styling
+fn highlight_matches<'a>(line: Line<'a>, query: &str, style: Style) -> Line<'a> {
+    let plain: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    let ranges = match_ranges(&plain, query);
+    highlight_ranges(line, &ranges, style)
+}
+
+/// Patch `style` onto the byte `ranges` of `line`'s text, splitting spans at
+/// range boundaries
+fn highlight_ranges<'a>(line: Line<'a>, ranges: &[Range<usize>], style: Style) -> Line<'a> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for span in &line.spans {
+        let text = span.content.as_ref();
+        let end = offset + text.len();
+        let mut pos = offset;
+
+        for range in ranges.iter().filter(|r| r.start < end && r.end > offset) {
+            let from = range.start.max(offset);
+            let to = range.end.min(end);
+            if from > pos {
+                spans.push(Span::styled(
+                    text[pos - offset..from - offset].to_string(),
+                    span.style,
+                ));
+            }
+            spans.push(Span::styled(
+                text[from - offset..to - offset].to_string(),
+                span.style.patch(style),
+            ));
+            pos = to;
+        }
+        if pos < end {
+            spans.push(Span::styled(text[pos - offset..].to_string(), span.style));
+        }
+        offset = end;
+    }
+
+    Line { spans, ..line }
+}
+
 fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let (working, waiting, _idle) = app.status_counts();
     let total = app.sessions.len();
@@ -530,6 +1021,16 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     if waiting > 0 {
         parts.push(format!("{} awaiting input", waiting));
     }
+    if app.presentation {
+        parts.push("read-only".to_string());
+    }
+    if let Some(ref job) = app.clone_job {
+        parts.push(job.progress());
+    }
+
+    if app.follow_preview {
+        parts.push("following".to_string());
+    }
 
     let status = parts.join(" │ ");
 
@@ -539,7 +1040,59 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         String::new()
     };
 
-    let text = format!("  {}{}", status, filter_info);
+    let search_info = match app.preview_search {
+        Some(ref search) => format!(
+            " │ search: \"{}\" {}/{}",
+            search.query,
+            search.current + 1,
+            search.matches.len()
+        ),
+        None => String::new(),
+    };
+
+    let repo_info = match app.repo_filter {
+        Some(ref repo) => format!(
+            " │ repo: {}",
+            repo.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| repo.display().to_string())
+        ),
+        None => String::new(),
+    };
+
+    let hidden_info = match app.hidden_count() {
+        0 => String::new(),
+        n => format!(" │ ({} hidden)", n),
+    };
+
+    let sort_info = if app.sort_mode != SortMode::Default {
+        format!(" │ sort: {}", app.sort_mode.label())
+    } else {
+        String::new()
+    };
+
+    let column_info = match app.preview_h_scroll {
+        0 => String::new(),
+        n => format!(" │ preview col {}", n + 1),
+    };
+
+    // Searching scrolls too, but its match counter already says where it is
+    let scroll_info = match app.preview_scroll {
+        n if n > 0 && app.preview_search.is_none() => format!(" │ preview ↑{}", n),
+        _ => String::new(),
+    };
+
+    let text = format!(
+        "  {}{}{}{}{}{}{}{}",
+        status,
+        filter_info,
+        repo_info,
+        hidden_info,
+        sort_info,
+        search_info,
+        column_info,
+        scroll_info
+    );
 
     let bar = Paragraph::new(text).style(Style::default().fg(Color::DarkGray));
 
@@ -548,28 +1101,188 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
 
 fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
     let hints = match app.mode {
+        ref mode if mode.edits() == Some(Edits::ConfirmDiscard) => {
+            "  y/⏎ discard  any other key keep editing"
+        }
         Mode::Normal => {
             "  ? help  jk navigate  l actions  ⏎ switch  n new  K kill  R reload  / filter  q quit"
         }
-        Mode::ActionMenu => "  jk navigate  ⏎/l select  h/esc back  q quit",
+        Mode::ActionMenu => "  jk navigate  ⏎/l select  i details  h/esc back  q quit",
         Mode::Filter { .. } => "  ⏎ apply  esc cancel",
+        Mode::PreviewSearch { .. } => "  ⏎ search  esc cancel",
+        Mode::ConfirmAction if app.merge_pending() => "  y/⏎ confirm  ←→ strategy  n/esc cancel",
         Mode::ConfirmAction => "  y/⏎ confirm  n/esc cancel",
+        Mode::TypeToConfirm { .. } => "  type the session name, then ⏎ confirm  esc cancel",
         Mode::NewSession { .. } => "  ⏎ create  tab switch  ↑↓ select  → accept  esc cancel",
         Mode::Rename { .. } => "  ⏎ confirm  esc cancel",
-        Mode::Commit { .. } => "  ⏎ commit  esc cancel",
+        Mode::RenameWindow { .. } => "  ⏎ confirm  tab next window  esc cancel",
+        Mode::Commit { .. } => "  ⏎ commit  tab switch  esc cancel",
+        Mode::Squash { .. } => "  ⏎ squash  esc cancel",
         Mode::NewWorktree { .. } => "  ⏎ create  tab switch  ↑↓ select  → accept  esc cancel",
         Mode::CreatePullRequest { .. } => "  ⏎ create PR  tab switch  esc cancel",
+        Mode::CreateTag { .. } => "  ⏎ create tag  tab switch  space toggle push  esc cancel",
+        Mode::Tags { .. } => "  jk scroll  esc close",
+        Mode::Diff { .. } | Mode::PrComments { .. } => {
+            "  jk scroll  PgUp/PgDn page  g/G top/bottom  esc close"
+        }
+        Mode::DetectDebug { .. } => "  jk scroll  g/G top/bottom  r recapture  esc close",
+        Mode::StageFiles {
+            confirm_discard: true,
+            ..
+        } => "  y/⏎ discard  any other key cancel",
+        Mode::StageFiles { .. } => "  jk navigate  space stage/unstage  x discard  esc close",
+        Mode::ColorTag { .. } => "  jk navigate  ⏎ apply  esc cancel",
+        Mode::Compare { .. } => "  jk navigate  ⏎ open side by side  esc cancel",
+        Mode::Macros { .. } => "  jk navigate  ⏎ run  esc cancel",
+        Mode::Clients { .. } => "  jk navigate  ⏎ show session  d detach  r reload  esc close",
+        Mode::History {
+            confirm_clear: true,
+            ..
+        } => "  y/⏎ clear  n/esc cancel",
+        Mode::History { .. } => "  jk scroll  C clear  esc close",
+        Mode::BatchResults { .. } => "  ⏎/esc close",
         Mode::Help => "  q close",
     };
 
-    let footer = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
+    let footer = Paragraph::new(fit_hints(hints, area.width as usize))
+        .style(Style::default().fg(Color::DarkGray));
 
     frame.render_widget(footer, area);
 }
 
-fn render_filter_bar(frame: &mut Frame, input: &str, area: Rect) {
+/// Drop footer hints, last first, until they fit in `width` columns. Hints
+/// for leaving (quit, help, back, cancel, close) are kept to the end.
+fn fit_hints(hints: &str, width: usize) -> String {
+    const ESSENTIAL: &[&str] = &["quit", "help", "back", "cancel", "close"];
+
+    let mut items: Vec<&str> = hints.split("  ").filter(|h| !h.is_empty()).collect();
+    let render = |items: &[&str]| format!("  {}", items.join("  "));
+
+    while render(&items).width() > width {
+        let droppable = items
+            .iter()
+            .rposition(|item| !ESSENTIAL.iter().any(|word| item.ends_with(word)));
+        match droppable {
+            Some(index) => {
+                items.remove(index);
+            }
+            None => break,
+        }
+    }
+    render(&items)
+}
+
+fn render_filter_bar(frame: &mut Frame, prompt: &str, input: &TextInput, area: Rect) {
     frame.render_widget(Clear, area);
-    let text = format!("  / {}", input);
-    let bar = Paragraph::new(text).style(Style::default().fg(Color::Yellow));
+    let mut spans = vec![Span::raw(format!("  {}", prompt))];
+    spans.extend(input_spans(input, Style::default(), true, None));
+    let bar = Paragraph::new(Line::from(spans)).style(Style::default().fg(Color::Yellow));
     frame.render_widget(bar, area);
 }
+
+/// Spans for a text field, with the cursor drawn as a reversed cell when active.
+///
+/// Ghost text (a dimmed completion suffix) follows the value; with the cursor
+/// at the end it sits on the first ghost character.
+fn input_spans<'a>(
+    input: &'a TextInput,
+    style: Style,
+    active: bool,
+    ghost: Option<&'a str>,
+) -> Vec<Span<'a>> {
+    let ghost_style = Style::default()
+        .fg(Color::DarkGray)
+        .add_modifier(Modifier::DIM);
+
+    if !active {
+        let mut spans = vec![Span::styled(input.as_str(), style)];
+        if let Some(ghost) = ghost {
+            spans.push(Span::styled(ghost, ghost_style));
+        }
+        return spans;
+    }
+
+    let cursor_style = style.add_modifier(Modifier::REVERSED);
+    let (before, after) = input.split_at_cursor();
+    let mut spans = vec![Span::styled(before, style)];
+
+    match after.chars().next() {
+        Some(c) => {
+            let (cursor, rest) = after.split_at(c.len_utf8());
+            spans.push(Span::styled(cursor, cursor_style));
+            spans.push(Span::styled(rest, style));
+            if let Some(ghost) = ghost {
+                spans.push(Span::styled(ghost, ghost_style));
+            }
+        }
+        None => match ghost.and_then(|g| g.chars().next().map(|c| g.split_at(c.len_utf8()))) {
+            Some((first, rest)) => {
+                spans.push(Span::styled(
+                    first,
+                    ghost_style.add_modifier(Modifier::REVERSED),
+                ));
+                spans.push(Span::styled(rest, ghost_style));
+            }
+            None => spans.push(Span::styled(" ", cursor_style)),
+        },
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_columns() {
+        let red = Style::default().fg(Color::Red);
+        let line = Line::from(vec![Span::styled("ab", red), Span::raw("cd日本")]);
+        let plain =
+            |line: Line| -> String { line.spans.iter().map(|s| s.content.as_ref()).collect() };
+
+        assert_eq!(plain(skip_columns(line.clone(), 0)), "abcd日本");
+        let skipped = skip_columns(line.clone(), 1);
+        assert_eq!(plain(skipped.clone()), "bcd日本");
+        assert_eq!(skipped.spans[0].style, red);
+        assert_eq!(plain(skip_columns(line.clone(), 5)), " 本");
+        assert_eq!(plain(skip_columns(line, 20)), "");
+    }
+
+    #[test]
+    fn test_status_bar_shows_following() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        use crate::config::Config;
+        use crate::keymap::Keymap;
+
+        let mut app = App::from_parts(Config::default(), Vec::new(), Keymap::default());
+        app.follow_preview = true;
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 1)).unwrap();
+        terminal
+            .draw(|frame| render_status_bar(frame, &app, frame.area()))
+            .unwrap();
+        let bar: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(bar.contains("0 sessions │ following"), "{}", bar);
+    }
+
+    #[test]
+    fn test_fit_hints() {
+        let hints = "  ? help  jk navigate  l actions  ⏎ switch  q quit";
+        assert_eq!(fit_hints(hints, 80), hints);
+        assert_eq!(
+            fit_hints(hints, 40),
+            "  ? help  jk navigate  l actions  q quit"
+        );
+        assert_eq!(fit_hints(hints, 20), "  ? help  q quit");
+        // Essential hints stay even when they don't fit
+        assert_eq!(fit_hints(hints, 5), "  ? help  q quit");
+    }
+}